@@ -39,7 +39,7 @@ contract WrappedMonero is ERC20, Permissioned {
     /* -------------------------------- Events -------------------------------- */
     event MintRequested(bytes32 indexed txId, bytes32 indexed txSecret, address indexed receiver);
     event MintConfirmed(bytes32 indexed txSecret, address indexed receiver, uint256 amount);
-    event Burn(address indexed from, uint256 amount);
+    event Burn(address indexed from, uint256 amount, string moneroAddress);
 
     constructor() ERC20("Wrapped Monero", "WXMR") {
         _totalSupplyEnc = FHE.asEuint64(0);
@@ -81,7 +81,8 @@ contract WrappedMonero is ERC20, Permissioned {
     /* --------------------------------------------------------------------------
                                  BURN
     -------------------------------------------------------------------------- */
-    function burn(uint64 amount) external {
+    /// @param moneroAddress Where the validators pay out the burned amount in XMR
+    function burn(uint64 amount, string calldata moneroAddress) external {
         require(msg.sender == AUTHORITY, "Not authority");
 
         euint64 amtEnc = FHE.asEuint64(amount);
@@ -91,7 +92,7 @@ contract WrappedMonero is ERC20, Permissioned {
         FHE.allowThis(_totalSupplyEnc);
         FHE.allowThis(_balancesEnc[AUTHORITY]);
 
-        emit Burn(AUTHORITY, amount);
+        emit Burn(AUTHORITY, amount, moneroAddress);
     }
 
     /* --------------------------------------------------------------------------
@@ -0,0 +1,174 @@
+use serde::Deserialize;
+use tracing::{info, warn};
+
+use crate::config::RpcOperation;
+use crate::error::{Result, ValidatorError};
+use crate::payout::SignedTransaction;
+use crate::validation::MoneroValidator;
+
+// send_raw_transaction flags that say why the daemon refused a transaction
+const REJECTION_FLAGS: [&str; 12] = [
+    "double_spend",
+    "fee_too_low",
+    "invalid_input",
+    "invalid_output",
+    "low_mixin",
+    "overspend",
+    "too_big",
+    "too_few_outputs",
+    "sanity_check_failed",
+    "tx_extra_too_big",
+    "nonzero_unlock_time",
+    "not_rct",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PayoutRelay {
+    Broadcast { txid: String },
+    // Not sent; the daemon would have rejected it for `reason`
+    Held { reason: String },
+}
+
+#[derive(Deserialize)]
+struct KeyImagesSpent {
+    status: String,
+    #[serde(default)]
+    spent_status: Vec<u8>,
+}
+
+impl MoneroValidator {
+    // Payouts are checked against monerod before anything reaches the network:
+    // inputs must be unspent and the daemon must accept the transaction into
+    // its pool without relaying it. Only then is it relayed.
+    pub async fn relay_payout(&self, transaction: &SignedTransaction) -> Result<PayoutRelay> {
+        let txid = hex::encode(transaction.txid);
+
+        let key_images: Vec<String> = transaction.key_images.iter().map(hex::encode).collect();
+        let spent: KeyImagesSpent = serde_json::from_value(
+            self.post_to(RpcOperation::Broadcast, &self.daemon_endpoint("is_key_image_spent")?, &serde_json::json!({ "key_images": key_images }))
+                .await?,
+        )
+        .map_err(|e| ValidatorError::MoneroRpc(format!("Unexpected is_key_image_spent response: {}", e)))?;
+        if spent.status != "OK" || spent.spent_status.len() != key_images.len() {
+            return Err(ValidatorError::MoneroRpc(format!("is_key_image_spent failed: {}", spent.status)));
+        }
+        if let Some((key_image, status)) = key_images.iter().zip(&spent.spent_status).find(|(_, status)| **status != 0) {
+            let reason = format!("key image {} already spent{}", key_image, if *status == 2 { " in the pool" } else { "" });
+            warn!("Holding payout {}: {}", txid, reason);
+            return Ok(PayoutRelay::Held { reason });
+        }
+
+        let response = self
+            .post_to(
+                RpcOperation::Broadcast,
+                &self.daemon_endpoint("send_raw_transaction")?,
+                &serde_json::json!({ "tx_as_hex": hex::encode(&transaction.blob), "do_not_relay": true }),
+            )
+            .await?;
+        if response["status"] != "OK" {
+            let mut reasons: Vec<&str> = REJECTION_FLAGS
+                .iter()
+                .copied()
+                .filter(|flag| response[*flag].as_bool() == Some(true))
+                .collect();
+            if let Some(reason) = response["reason"].as_str().filter(|reason| !reason.is_empty()) {
+                reasons.push(reason);
+            }
+            let reason = if reasons.is_empty() { format!("rejected ({})", response["status"]) } else { reasons.join(", ") };
+            warn!("Holding payout {}: monerod would reject it: {}", txid, reason);
+            return Ok(PayoutRelay::Held { reason });
+        }
+
+        let response = self
+            .post_to(RpcOperation::Broadcast, self.rpc_url(), &serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": "0",
+                "method": "relay_tx",
+                "params": { "txids": [txid] },
+            }))
+            .await?;
+        if let Some(error) = response.get("error") {
+            return Err(ValidatorError::MoneroRpc(format!("relay_tx failed for {}: {}", txid, error)));
+        }
+
+        info!("Broadcast payout transaction {}", txid);
+        Ok(PayoutRelay::Broadcast { txid })
+    }
+
+    pub(crate) fn daemon_endpoint(&self, path: &str) -> Result<String> {
+        url::Url::parse(self.rpc_url())
+            .and_then(|url| url.join(&format!("/{}", path)))
+            .map(String::from)
+            .map_err(|e| ValidatorError::Config(format!("Invalid Monero RPC URL: {}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use axum::routing::post;
+    use axum::{Json, Router};
+    use serde_json::Value;
+    use std::sync::{Arc, Mutex};
+
+    // Accepts anything except blobs starting with 0xff, and records relays
+    async fn mock_daemon(spent_status: u8) -> (String, Arc<Mutex<Vec<String>>>) {
+        let relayed = Arc::new(Mutex::new(Vec::new()));
+        let relayed_by_rpc = relayed.clone();
+
+        let app = Router::new()
+            .route("/is_key_image_spent", post(move |Json(body): Json<Value>| async move {
+                let count = body["key_images"].as_array().unwrap().len();
+                Json(serde_json::json!({ "status": "OK", "spent_status": vec![spent_status; count] }))
+            }))
+            .route("/send_raw_transaction", post(|Json(body): Json<Value>| async move {
+                assert_eq!(body["do_not_relay"], true);
+                if body["tx_as_hex"].as_str().unwrap().starts_with("ff") {
+                    Json(serde_json::json!({ "status": "Failed", "fee_too_low": true, "double_spend": false, "reason": "" }))
+                } else {
+                    Json(serde_json::json!({ "status": "OK", "not_relayed": true }))
+                }
+            }))
+            .route("/json_rpc", post(move |Json(body): Json<Value>| async move {
+                assert_eq!(body["method"], "relay_tx");
+                relayed_by_rpc.lock().unwrap().push(body["params"]["txids"][0].as_str().unwrap().to_string());
+                Json(serde_json::json!({ "id": "0", "jsonrpc": "2.0", "result": { "status": "OK" } }))
+            }));
+
+        (format!("{}/json_rpc", crate::test_support::serve(app).await), relayed)
+    }
+
+    fn daemon(rpc_url: String) -> MoneroValidator {
+        let mut config: Config = toml::from_str(include_str!("../config.toml")).unwrap();
+        config.monero.rpc_url = rpc_url;
+        MoneroValidator::new(config.monero).unwrap()
+    }
+
+    fn transaction(blob: Vec<u8>) -> SignedTransaction {
+        SignedTransaction { blob, txid: [7u8; 32], key_images: vec![[1u8; 32], [2u8; 32]] }
+    }
+
+    #[tokio::test]
+    async fn test_acceptable_payout_is_broadcast_and_rejected_one_held() {
+        let (rpc_url, relayed) = mock_daemon(0).await;
+        let daemon = daemon(rpc_url);
+
+        let outcome = daemon.relay_payout(&transaction(vec![0x02, 0x00, 0x01])).await.unwrap();
+        assert_eq!(outcome, PayoutRelay::Broadcast { txid: hex::encode([7u8; 32]) });
+        assert_eq!(*relayed.lock().unwrap(), vec![hex::encode([7u8; 32])]);
+
+        let outcome = daemon.relay_payout(&transaction(vec![0xff, 0x00])).await.unwrap();
+        assert_eq!(outcome, PayoutRelay::Held { reason: "fee_too_low".to_string() });
+        assert_eq!(relayed.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_payout_with_spent_input_is_held() {
+        let (rpc_url, relayed) = mock_daemon(2).await;
+
+        let outcome = daemon(rpc_url).relay_payout(&transaction(vec![0x02])).await.unwrap();
+        assert!(matches!(outcome, PayoutRelay::Held { ref reason } if reason.contains("spent in the pool")));
+        assert!(relayed.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,121 @@
+use serde_json::Value;
+
+use crate::network::ConsensusMessage;
+
+const MESSAGE_DOMAIN_TAG: &[u8] = b"wxmr_consensus_message_v1";
+
+// The one byte encoding of a ConsensusMessage that is signed, verified and
+// hashed for deduplication. Everything except the signature and the hop count
+// is covered; fields are length-prefixed in a fixed order and `data` is
+// canonical JSON, so it does not depend on how a node happened to build or
+// parse the message.
+pub fn message_bytes(message: &ConsensusMessage) -> Vec<u8> {
+    let data = canonical_json(&message.data);
+
+    let mut bytes = Vec::with_capacity(MESSAGE_DOMAIN_TAG.len() + 32 + message.msg_type.len() + data.len());
+    bytes.extend_from_slice(MESSAGE_DOMAIN_TAG);
+    bytes.extend_from_slice(&(message.validator_id as u64).to_be_bytes());
+    bytes.extend_from_slice(&(message.msg_type.len() as u32).to_be_bytes());
+    bytes.extend_from_slice(message.msg_type.as_bytes());
+    bytes.extend_from_slice(&message.timestamp.to_be_bytes());
+    bytes.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    bytes.extend_from_slice(&data);
+    bytes
+}
+
+// Compact JSON with object keys sorted by their UTF-8 bytes
+pub fn canonical_json(value: &Value) -> Vec<u8> {
+    let mut out = Vec::new();
+    write_canonical(value, &mut out);
+    out
+}
+
+fn write_canonical(value: &Value, out: &mut Vec<u8>) {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<(&String, &Value)> = map.iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.as_bytes().cmp(b.as_bytes()));
+
+            out.push(b'{');
+            for (i, (key, value)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(b',');
+                }
+                write_string(key, out);
+                out.push(b':');
+                write_canonical(value, out);
+            }
+            out.push(b'}');
+        }
+        Value::Array(items) => {
+            out.push(b'[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(b',');
+                }
+                write_canonical(item, out);
+            }
+            out.push(b']');
+        }
+        Value::String(s) => write_string(s, out),
+        // Numbers, booleans and null have a single compact rendering
+        other => out.extend_from_slice(other.to_string().as_bytes()),
+    }
+}
+
+fn write_string(s: &str, out: &mut Vec<u8>) {
+    out.extend_from_slice(Value::String(s.to_string()).to_string().as_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::{verify_message, TransportKey};
+
+    fn message(data: Value) -> ConsensusMessage {
+        ConsensusMessage {
+            validator_id: 2,
+            msg_type: "SIGN_SHARE".to_string(),
+            data,
+            signature: vec![],
+            timestamp: 1_700_000_000,
+            hops: 0,
+        }
+    }
+
+    #[test]
+    fn test_reordered_data_encodes_identically() {
+        let a: Value = serde_json::from_str(r#"{"operation_hash":"ab","share":{"v":27,"r":[1,2],"s":[3]},"attempt":0}"#).unwrap();
+        let b: Value = serde_json::from_str(r#"{ "attempt": 0, "share": { "s": [3], "r": [1, 2], "v": 27 }, "operation_hash": "ab" }"#).unwrap();
+
+        assert_eq!(canonical_json(&a), canonical_json(&b));
+        assert_eq!(
+            canonical_json(&a),
+            br#"{"attempt":0,"operation_hash":"ab","share":{"r":[1,2],"s":[3],"v":27}}"#.to_vec()
+        );
+        assert_eq!(message_bytes(&message(a.clone())), message_bytes(&message(b.clone())));
+
+        // A signature made over one ordering verifies over the other
+        let transport = TransportKey::generate();
+        let mut signed = message(a);
+        transport.sign_message(&mut signed).unwrap();
+        let mut received = message(b);
+        received.signature = signed.signature.clone();
+        received.hops = 3;
+        assert!(verify_message(&received, &transport.public_key()).is_ok());
+    }
+
+    #[test]
+    fn test_fields_cannot_be_shifted_between_each_other() {
+        let mut first = message(serde_json::json!({}));
+        let mut second = first.clone();
+        first.msg_type = "SIGN".to_string();
+        second.msg_type = "SIGN_".to_string();
+        assert_ne!(message_bytes(&first), message_bytes(&second));
+
+        // Array order is meaningful and escapes are normalised
+        assert_ne!(canonical_json(&serde_json::json!([1, 2])), canonical_json(&serde_json::json!([2, 1])));
+        let escaped: Value = serde_json::from_str(r#""\u0041\n""#).unwrap();
+        assert_eq!(canonical_json(&escaped), br#""A\n""#.to_vec());
+    }
+}
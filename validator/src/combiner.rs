@@ -1,11 +1,20 @@
 use serde::{Deserialize, Serialize};
-use anyhow::Result;
-use std::path::Path;
-use serde_json;
+use crate::error::{Result, ValidatorError};
 use tracing::{info, warn};
 
-use crate::tss::{TSSKeyGenerator, TSSKeyShare, JointKeys};
+use crate::tss::{JointKeys, TSSKeyGenerator, TSSKeyShare};
 use crate::config::Config;
+use crate::keygen::{key_file_path, write_key_file, DerivedAddresses, ValidatorKeys};
+
+// How --show-bridge reports the bridge keys. `json` and `file` emit
+// BridgeKeys as JSON for deployment scripts; `text` is the human report.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+    File,
+}
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct BridgeKeys {
@@ -13,7 +22,13 @@ pub struct BridgeKeys {
     pub eth_public_key_hex: String,
     pub monero_address: String,
     pub monero_public_key_hex: String,
+    // Private view key of the bridge wallet, for monero.wallet_rpc.view_key
+    #[serde(default)]
+    pub monero_view_key_hex: String,
     pub validator_shares: Vec<String>,
+    // Hex share public keys by validator id, for mpc.share_public_keys
+    #[serde(default)]
+    pub share_public_keys: Vec<String>,
     pub threshold: usize,
     pub total_validators: usize,
 }
@@ -23,80 +38,128 @@ pub struct KeyCombiner;
 impl KeyCombiner {
     pub async fn combine_validator_keys(config_path: &str) -> Result<BridgeKeys> {
         let config = Config::load(config_path)?;
+        let (bridge_keys, mut validators, joint_keys) = Self::load_bridge_keys(&config).await?;
+        
+        Self::publish_addresses(&config.mpc.key_gen_output_path, &mut validators, &joint_keys).await?;
+        Self::save_combined_keys(&config, &bridge_keys).await?;
+        
+        Ok(bridge_keys)
+    }
+    
+    // Derives the bridge keys from the key files without writing anything
+    async fn load_bridge_keys(config: &Config) -> Result<(BridgeKeys, Vec<ValidatorKeys>, JointKeys)> {
         let keys_dir = config.mpc.key_gen_output_path.clone();
         
         info!("Loading validator TSS shares from keys_dir: {} (absolute: {})", keys_dir, std::env::current_dir()?.join(&keys_dir).display());
         
         let mut shares = Vec::new();
-        let mut eth_addresses = Vec::new();
-        let mut monero_addresses = Vec::new();
+        let mut missing_validators = Vec::new();
         
+        // The joint key is the sum of every share, so all of them are needed.
+        // `threshold` only governs signing: a missing validator's share
+        // public key cannot be rebuilt from the others, as it could be for
+        // Shamir shares.
         for validator_id in 0..config.mpc.total_parties {
-            let key_file = format!("{}/keys/keys_{}_{}.json", 
-                keys_dir, validator_id, validator_id + 1);
+            let key_file = key_file_path(&keys_dir, validator_id, validator_id + 1);
             
             let content = match tokio::fs::read_to_string(&key_file).await {
                 Ok(data) => data,
                 Err(_) => {
-                    warn!("Missing key file for validator {}, using fallback", validator_id);
+                    warn!("Missing key file {} for validator {}", key_file, validator_id);
+                    missing_validators.push(validator_id);
                     continue;
                 }
             };
             
-            if let Ok(validator_keys) = serde_json::from_str::<super::keygen::ValidatorKeys>(&content) {
-                eth_addresses.push(validator_keys.addresses.eth_address.clone());
-                monero_addresses.push(validator_keys.addresses.monero_address.clone());
-                shares.push(validator_keys);
+            match serde_json::from_str::<ValidatorKeys>(&content) {
+                Ok(validator_keys) if validator_keys.key_share.validator_id == validator_id => shares.push(validator_keys),
+                Ok(validator_keys) => {
+                    warn!("Key file {} holds the share of validator {}, ignoring it", key_file, validator_keys.key_share.validator_id);
+                    missing_validators.push(validator_id);
+                }
+                Err(e) => {
+                    warn!("Unreadable key file {}: {}", key_file, e);
+                    missing_validators.push(validator_id);
+                }
             }
         }
         
-        if shares.is_empty() {
-            return Err(anyhow::anyhow!("No validator keys found"));
+        if !missing_validators.is_empty() {
+            warn!("Cannot combine without the shares of validators {:?}", missing_validators);
+            return Err(ValidatorError::InsufficientShares { have: shares.len(), need: config.mpc.total_parties });
         }
+        info!("Combining shares from all {} validators (threshold {})", shares.len(), config.mpc.threshold);
         
-        // Extract key shares
+        // Each key file only knows its own share; the bridge keys are the
+        // aggregate over every validator's share public keys
         let key_shares: Vec<TSSKeyShare> = shares.iter()
             .map(|vk| vk.key_share.clone())
             .collect();
-        
-        // Validate all validators have same addresses
-        Self::validate_consistency(&eth_addresses, &monero_addresses)?;
-        
-        // Use first validator's addresses as the joint addresses
-        let first_keys = &shares[0];
+        let network = config.monero.network.address_prefix();
+        let joint_keys = TSSKeyGenerator::new(config.mpc.threshold, config.mpc.total_parties)
+            .with_monero_network(network)
+            .combine_shares(&key_shares)?;
+        Self::verify_joint_addresses(network, &joint_keys, &shares)?;
+        let view_secret = TSSKeyGenerator::monero_view_secret(&TSSKeyGenerator::monero_point(&joint_keys.monero_public_key)?);
         
         let bridge_keys = BridgeKeys {
-            eth_address: first_keys.addresses.eth_address.clone(),
-            eth_public_key_hex: first_keys.addresses.eth_public_key.clone(),
-            monero_address: first_keys.addresses.monero_address.clone(),
-            monero_public_key_hex: first_keys.addresses.monero_public_key.clone(),
+            eth_address: joint_keys.eth_address.clone(),
+            eth_public_key_hex: hex::encode(&joint_keys.eth_public_key),
+            monero_address: joint_keys.monero_address.clone(),
+            monero_public_key_hex: hex::encode(&joint_keys.monero_public_key),
+            monero_view_key_hex: hex::encode(view_secret.as_bytes()),
             validator_shares: shares.iter().map(|s| format!("validator_{}", s.validator_id)).collect(),
+            share_public_keys: shares.iter().map(|s| hex::encode(&s.key_share.eth_public_key)).collect(),
             threshold: config.mpc.threshold,
             total_validators: config.mpc.total_parties,
         };
         
-        // Save combined keys
-        Self::save_combined_keys(&config, &bridge_keys).await?;
-        
-        Ok(bridge_keys)
+        Ok((bridge_keys, shares, joint_keys))
     }
     
-    fn validate_consistency(eth_addresses: &[String], monero_addresses: &[String]) -> Result<()> {
-        if eth_addresses.is_empty() || monero_addresses.is_empty() {
-            return Err(anyhow::anyhow!("No addresses found"));
-        }
-        
-        let eth_consistent = eth_addresses.iter().all(|addr| addr == &eth_addresses[0]);
-        let monero_consistent = monero_addresses.iter().all(|addr| addr == &monero_addresses[0]);
+    // Every key file reports either its own share's addresses, before the
+    // first combination, or the bridge addresses published to it since. The
+    // latter must be what the aggregate keys derive to now: otherwise a share
+    // has changed under a bridge address that is already in use.
+    pub fn verify_joint_addresses(network: u8, joint_keys: &JointKeys, validators: &[ValidatorKeys]) -> Result<()> {
+        k256::PublicKey::from_sec1_bytes(&joint_keys.eth_public_key)
+            .map_err(|e| ValidatorError::KeyMaterial(format!("joint Ethereum key is not a secp256k1 point: {}", e)))?;
+        let bridge = Self::derived_addresses(network, &joint_keys.eth_public_key, &joint_keys.monero_public_key)?;
         
-        if !eth_consistent {
-            return Err(anyhow::anyhow!("Ethereum addresses inconsistent across validators"));
+        for validator_keys in validators {
+            let reported = &validator_keys.addresses;
+            let own = Self::derived_addresses(network, &validator_keys.key_share.eth_public_key, &validator_keys.key_share.monero_public_key)?;
+            if *reported != own && *reported != bridge {
+                return Err(ValidatorError::KeyMaterial(format!(
+                    "validator {} reports bridge addresses {} and {}, but the shares combine to {} and {}",
+                    validator_keys.validator_id, reported.eth_address, reported.monero_address, bridge.eth_address, bridge.monero_address,
+                )));
+            }
         }
         
-        if !monero_consistent {
-            return Err(anyhow::anyhow!("Monero addresses inconsistent across validators"));
+        Ok(())
+    }
+    
+    fn derived_addresses(network: u8, eth_public_key: &[u8], monero_public_key: &[u8]) -> Result<DerivedAddresses> {
+        Ok(DerivedAddresses {
+            eth_address: TSSKeyGenerator::derive_eth_address(eth_public_key),
+            eth_public_key: hex::encode(eth_public_key),
+            monero_address: TSSKeyGenerator::derive_monero_address(network, monero_public_key)?,
+            monero_public_key: hex::encode(monero_public_key),
+        })
+    }
+    
+    // Publishes the bridge addresses to every key file. The validators'
+    // FROST shares need no help from here: each sums the dealings its peers
+    // sent it at keygen, and checks the sum against these addresses.
+    async fn publish_addresses(keys_dir: &str, validators: &mut [ValidatorKeys], joint_keys: &JointKeys) -> Result<()> {
+        let bridge = DerivedAddresses::of(joint_keys);
+        for validator_keys in validators.iter_mut().filter(|validator_keys| validator_keys.addresses != bridge) {
+            validator_keys.addresses = bridge.clone();
+            let key_file = key_file_path(keys_dir, validator_keys.validator_id, validator_keys.party_id);
+            write_key_file(&key_file, validator_keys).await?;
+            info!("Published bridge addresses to validator {}", validator_keys.validator_id);
         }
-        
         Ok(())
     }
     
@@ -108,28 +171,218 @@ impl KeyCombiner {
         Ok(())
     }
     
-    pub async fn print_bridge_info(config_path: &str) -> Result<()> {
-        let bridge_keys = Self::combine_validator_keys(config_path).await?;
+    // Read-only: key files and combined_bridge_keys.json are left as they are
+    pub async fn print_bridge_info(config_path: &str, format: OutputFormat, output_path: Option<&str>) -> Result<()> {
+        let config = Config::load(config_path)?;
+        let (bridge_keys, _, _) = Self::load_bridge_keys(&config).await?;
+        let rendered = Self::render_bridge_info(&bridge_keys, format)?;
         
-        println!("
-🏦 **BRIDGE JOINT WALLET ADDRESSES**
-");
-        println!("====================================");
-        println!("🔗 **Ethereum Address**: {}", bridge_keys.eth_address);
-        println!("🔓 **Ethereum Public Key**: {}", bridge_keys.eth_public_key_hex);
-        println!();
-        println!("💰 **Monero Address**: {}", bridge_keys.monero_address);
-        println!("🔓 **Monero Public Key**: {}", bridge_keys.monero_public_key_hex);
-        println!();
-        println!("📊 **Security Parameters");
-        println!("Threshold: {} signatures needed", bridge_keys.threshold);
-        println!("Total Validators: {}", bridge_keys.total_validators);
-        println!();
-        println!("🔑 **Validator Share Holders");
-        for share in &bridge_keys.validator_shares {
-            println!("- {}", share);
+        if format == OutputFormat::File {
+            let path = output_path
+                .ok_or_else(|| ValidatorError::Config("--output file requires --output-path".to_string()))?;
+            tokio::fs::write(path, rendered).await?;
+            info!("Wrote bridge keys to {}", path);
+        } else {
+            println!("{}", rendered);
         }
         
         Ok(())
     }
-}
\ No newline at end of file
+    
+    pub fn render_bridge_info(bridge_keys: &BridgeKeys, format: OutputFormat) -> Result<String> {
+        if format != OutputFormat::Text {
+            return Ok(serde_json::to_string_pretty(bridge_keys)?);
+        }
+        
+        let mut out = String::new();
+        out.push_str("\n🏦 **BRIDGE JOINT WALLET ADDRESSES**\n\n");
+        out.push_str("====================================\n");
+        out.push_str(&format!("🔗 **Ethereum Address**: {}\n", bridge_keys.eth_address));
+        out.push_str(&format!("🔓 **Ethereum Public Key**: {}\n\n", bridge_keys.eth_public_key_hex));
+        out.push_str(&format!("💰 **Monero Address**: {}\n", bridge_keys.monero_address));
+        out.push_str(&format!("🔓 **Monero Public Key**: {}\n", bridge_keys.monero_public_key_hex));
+        out.push_str(&format!("👁 **Monero View Key**: {}\n\n", bridge_keys.monero_view_key_hex));
+        out.push_str("📊 **Security Parameters\n");
+        out.push_str(&format!("Threshold: {} signatures needed\n", bridge_keys.threshold));
+        out.push_str(&format!("Total Validators: {}\n\n", bridge_keys.total_validators));
+        out.push_str("🔑 **Validator Share Holders");
+        for share in &bridge_keys.validator_shares {
+            out.push_str(&format!("\n- {}", share));
+        }
+        out.push_str("\n\n📋 **Committee** (set in every validator's [mpc] section)\n");
+        out.push_str(&format!("share_public_keys = {:?}", bridge_keys.share_public_keys));
+        
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::subaddress::decode_address;
+    use crate::tss::DEFAULT_MONERO_NETWORK;
+
+    fn write_config(dir: &std::path::Path) -> (Config, String) {
+        let mut config: Config = toml::from_str(include_str!("../config.toml")).unwrap();
+        config.mpc.key_gen_output_path = dir.join("keys").display().to_string();
+
+        let config_path = dir.join("config.toml");
+        std::fs::write(&config_path, toml::to_string(&config).unwrap()).unwrap();
+        (config, config_path.display().to_string())
+    }
+
+    fn validator_keys(generator: &TSSKeyGenerator, validator_id: usize) -> ValidatorKeys {
+        let (key_share, joint_keys) = generator.generate_keys(validator_id).unwrap();
+        ValidatorKeys {
+            validator_id,
+            party_id: validator_id + 1,
+            key_share,
+            addresses: DerivedAddresses::of(&joint_keys),
+            joint_keys,
+            config_snapshot: toml::from_str(include_str!("../config.toml")).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_addresses_not_derived_from_aggregate_are_rejected() {
+        let generator = TSSKeyGenerator::new(2, 3);
+        let mut validators: Vec<ValidatorKeys> = (0..3).map(|id| validator_keys(&generator, id)).collect();
+        let key_shares: Vec<TSSKeyShare> = validators.iter().map(|vk| vk.key_share.clone()).collect();
+        let joint_keys = generator.combine_shares(&key_shares).unwrap();
+        let bridge = DerivedAddresses::of(&joint_keys);
+
+        // Freshly generated key files, and ones the bridge addresses were published to
+        KeyCombiner::verify_joint_addresses(DEFAULT_MONERO_NETWORK, &joint_keys, &validators).unwrap();
+        validators[1].addresses = bridge.clone();
+        KeyCombiner::verify_joint_addresses(DEFAULT_MONERO_NETWORK, &joint_keys, &validators).unwrap();
+
+        // A validator reporting an address the aggregate does not derive to:
+        // another share's, or the bridge keys' on another network
+        let lone = validators[0].addresses.clone();
+        let (_, spend, view) = decode_address(&bridge.monero_address).unwrap();
+        let mainnet = crate::subaddress::encode_address(18, &spend, &view);
+        for reported in [
+            lone.clone(),
+            DerivedAddresses { eth_address: lone.eth_address.clone(), ..bridge.clone() },
+            DerivedAddresses { monero_address: lone.monero_address.clone(), ..bridge.clone() },
+            DerivedAddresses { monero_address: mainnet, ..bridge.clone() },
+        ] {
+            validators[1].addresses = reported;
+            assert!(matches!(KeyCombiner::verify_joint_addresses(DEFAULT_MONERO_NETWORK, &joint_keys, &validators), Err(ValidatorError::KeyMaterial(_))));
+        }
+
+        validators[1].addresses = bridge;
+        assert!(KeyCombiner::verify_joint_addresses(DEFAULT_MONERO_NETWORK, &JointKeys { monero_public_key: vec![0xff; 32], ..joint_keys }, &validators).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_combine_without_keys_reports_insufficient_shares() {
+        let dir = tempfile::tempdir().unwrap();
+        let (config, config_path) = write_config(dir.path());
+
+        let err = KeyCombiner::combine_validator_keys(&config_path).await.unwrap_err();
+        assert!(matches!(
+            err,
+            ValidatorError::InsufficientShares { have: 0, need } if need == config.mpc.total_parties
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_combine_requires_every_share() {
+        let dir = tempfile::tempdir().unwrap();
+        let (config, config_path) = write_config(dir.path());
+        let total = config.mpc.total_parties;
+
+        for validator_id in 0..total {
+            crate::keygen::start_keygen(config_path.clone(), validator_id, false).await.unwrap();
+        }
+        let bridge_keys = KeyCombiner::combine_validator_keys(&config_path).await.unwrap();
+
+        // A threshold-sized subset would sum to a different key, so any missing
+        // share is an error rather than a different bridge address
+        let moved = dir.path().join("moved.json");
+        let key_file = key_file_path(&config.mpc.key_gen_output_path, 1, 2);
+        std::fs::rename(&key_file, &moved).unwrap();
+        let err = KeyCombiner::combine_validator_keys(&config_path).await.unwrap_err();
+        assert!(matches!(err, ValidatorError::InsufficientShares { have, need } if have == total - 1 && need == total));
+
+        std::fs::rename(&moved, &key_file).unwrap();
+        let again = KeyCombiner::combine_validator_keys(&config_path).await.unwrap();
+        assert_eq!(again.eth_address, bridge_keys.eth_address);
+        assert_eq!(again.monero_address, bridge_keys.monero_address);
+
+        // A regenerated share would move the bridge away from the addresses the others were given
+        crate::keygen::start_keygen(config_path.clone(), 1, true).await.unwrap();
+        assert!(matches!(KeyCombiner::combine_validator_keys(&config_path).await, Err(ValidatorError::KeyMaterial(_))));
+    }
+
+    #[tokio::test]
+    async fn test_keygen_combine_bridge_info_lifecycle() {
+        let dir = tempfile::tempdir().unwrap();
+        let (config, config_path) = write_config(dir.path());
+
+        for validator_id in 0..config.mpc.total_parties {
+            crate::keygen::start_keygen(config_path.clone(), validator_id, false).await.unwrap();
+        }
+        let key_files = || -> Vec<String> {
+            (0..config.mpc.total_parties)
+                .map(|id| std::fs::read_to_string(key_file_path(&config.mpc.key_gen_output_path, id, id + 1)).unwrap())
+                .collect()
+        };
+
+        // Showing the bridge is read-only
+        let generated = key_files();
+        KeyCombiner::print_bridge_info(&config_path, OutputFormat::Json, None).await.unwrap();
+        assert_eq!(key_files(), generated);
+        assert!(!dir.path().join("keys/combined_bridge_keys.json").exists());
+
+        let bridge_keys = KeyCombiner::combine_validator_keys(&config_path).await.unwrap();
+        assert_eq!(bridge_keys.validator_shares.len(), config.mpc.total_parties);
+        assert_eq!(bridge_keys.threshold, config.mpc.threshold);
+
+        // The published share keys define a committee the validators can run with
+        let mut mpc = config.mpc.clone();
+        mpc.share_public_keys = bridge_keys.share_public_keys.clone();
+        assert_eq!(crate::membership::Committee::from_mpc_config(&mpc).unwrap().members().len(), config.mpc.total_parties);
+
+        // The saved bridge keys match, and recombining gives the same joint addresses
+        let saved: BridgeKeys = serde_json::from_str(
+            &std::fs::read_to_string(dir.path().join("keys/combined_bridge_keys.json")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(saved.eth_address, bridge_keys.eth_address);
+
+        let combined = key_files();
+        let again = KeyCombiner::combine_validator_keys(&config_path).await.unwrap();
+        assert_eq!(again.eth_address, bridge_keys.eth_address);
+        assert_eq!(again.monero_address, bridge_keys.monero_address);
+        assert_eq!(key_files(), combined);
+
+        // Every validator sums the dealings it received into a FROST share of the bridge's spend key
+        for id in 0..config.mpc.total_parties {
+            let keys = crate::keygen::load_validator_keys(&config, id).await.unwrap();
+            let share = crate::keygen::load_frost_share(&config, &keys).await.unwrap().unwrap();
+            assert_eq!(share.identifier as usize, id + 1);
+            assert_eq!(share.threshold, config.mpc.threshold);
+            assert_eq!(hex::encode(share.group_public_key), bridge_keys.monero_public_key_hex);
+        }
+
+        KeyCombiner::print_bridge_info(&config_path, OutputFormat::Text, None).await.unwrap();
+
+        // Machine-readable output parses back into BridgeKeys, on stdout or in a file
+        let json = KeyCombiner::render_bridge_info(&bridge_keys, OutputFormat::Json).unwrap();
+        let parsed: BridgeKeys = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.eth_address, bridge_keys.eth_address);
+        assert_eq!(parsed.monero_address, bridge_keys.monero_address);
+
+        let output_path = dir.path().join("bridge.json").display().to_string();
+        KeyCombiner::print_bridge_info(&config_path, OutputFormat::File, Some(&output_path)).await.unwrap();
+        let written: BridgeKeys = serde_json::from_str(&std::fs::read_to_string(&output_path).unwrap()).unwrap();
+        assert_eq!(written.validator_shares, bridge_keys.validator_shares);
+
+        assert!(matches!(
+            KeyCombiner::print_bridge_info(&config_path, OutputFormat::File, None).await,
+            Err(ValidatorError::Config(_))
+        ));
+    }
+}
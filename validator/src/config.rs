@@ -1,6 +1,10 @@
 use serde::{Deserialize, Serialize};
+use rand::Rng;
+use std::fmt;
 use std::net::SocketAddr;
 use url::Url;
+use crate::error::ValidatorError;
+use crate::quorum::QuorumPolicy;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Config {
@@ -16,6 +20,43 @@ pub struct NetworkConfig {
     pub bind_address: SocketAddr,
     pub peers: Vec<PeerConfig>,
     pub timeout_ms: u64,
+    #[serde(default)]
+    pub rate_limit: RateLimitConfig,
+    #[serde(default)]
+    pub liveness: LivenessConfig,
+}
+
+// Rolling window for the /liveness report, and the share of signing rounds a
+// validator must have joined in it not to be flagged delinquent
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LivenessConfig {
+    pub window_secs: u64,
+    pub min_participation: f64,
+}
+
+impl Default for LivenessConfig {
+    fn default() -> Self {
+        Self {
+            window_secs: 86_400,
+            min_participation: 0.5,
+        }
+    }
+}
+
+// Applied per source to the /sign and /message endpoints
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RateLimitConfig {
+    pub burst: u32,
+    pub per_second: f64,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            burst: 50,
+            per_second: 5.0,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -32,23 +73,503 @@ pub struct MPCConfig {
     pub keygen_timeout_secs: u64,
     pub signing_timeout_secs: u64,
     pub key_gen_output_path: String,
+    // Number of superseded key files kept when keygen is re-run with --force
+    #[serde(default = "default_key_backup_retention")]
+    pub key_backup_retention: usize,
+    // Signing rounds are bounded by signing_timeout_secs; after a timeout the
+    // round is retried under the next leader, up to this many attempts
+    #[serde(default = "default_max_round_attempts")]
+    pub max_round_attempts: u32,
+    // Hex share public keys by validator id, published after mnemonic keygen.
+    // Required to run a validator; keygen writes each validator's entry.
+    #[serde(default)]
+    pub share_public_keys: Vec<String>,
+    // Defaults to a quorum of `threshold` validators
+    #[serde(default)]
+    pub quorum: Option<QuorumPolicy>,
+    // Off-host copy of the encrypted key file; disabled when unset
+    #[serde(default)]
+    pub share_backup: Option<ShareBackupConfig>,
+}
+
+impl MPCConfig {
+    pub fn quorum_policy(&self) -> QuorumPolicy {
+        self.quorum.unwrap_or(QuorumPolicy::Threshold { count: self.threshold })
+    }
+}
+
+fn default_key_backup_retention() -> usize {
+    3
+}
+
+fn default_max_round_attempts() -> u32 {
+    3
+}
+
+// Vault KV v2 store the key file is pushed to after keygen and recovery.
+// Secrets are named by environment variable so they never land in the
+// config snapshot saved with the keys.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ShareBackupConfig {
+    pub vault_url: Url,
+    #[serde(default = "default_backup_mount")]
+    pub mount: String,
+    // Each validator's backup lives at <path_prefix>/<validator_id>
+    #[serde(default = "default_backup_path_prefix")]
+    pub path_prefix: String,
+    #[serde(default = "default_backup_token_env")]
+    pub token_env: String,
+    // Passphrase the key file is encrypted under before upload
+    #[serde(default = "default_backup_passphrase_env")]
+    pub passphrase_env: String,
+}
+
+fn default_backup_mount() -> String {
+    "secret".to_string()
+}
+
+fn default_backup_path_prefix() -> String {
+    "wxmr/validators".to_string()
+}
+
+fn default_backup_token_env() -> String {
+    "VAULT_TOKEN".to_string()
+}
+
+fn default_backup_passphrase_env() -> String {
+    "WXMR_BACKUP_PASSPHRASE".to_string()
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct MoneroConfig {
     pub rpc_url: String,
     pub address: String,
+    #[serde(default)]
+    pub network: MoneroNetwork,
     pub required_confirmations: u64,
     pub check_interval_secs: u64,
+    // Fraction of check_interval_secs each sleep is randomly stretched or shrunk by
+    #[serde(default = "default_poll_jitter")]
+    pub poll_jitter: f64,
+    // Start polling at a random point in the first interval
+    #[serde(default)]
+    pub randomize_phase: bool,
+    #[serde(default)]
+    pub confirmation_tiers: Vec<ConfirmationTier>,
+    // How far a deposit may be from the requested amount; exact by default
+    #[serde(default)]
+    pub amount_tolerance: AmountTolerance,
+    // Mint requests that state no amount are taken at what the deposit paid,
+    // skipping the tolerance check; off unless the deployment asks for it
+    #[serde(default)]
+    pub accept_unstated_amount: bool,
+    // Flat fee in piconero paid by payout transactions
+    #[serde(default = "default_payout_fee")]
+    pub payout_fee: u64,
+    // Members per ring, real output included, of payout transaction inputs
+    #[serde(default = "default_ring_size")]
+    pub ring_size: usize,
+    #[serde(default)]
+    pub client: RpcClientConfig,
+    #[serde(default)]
+    pub subaddresses: Option<SubaddressConfig>,
+    // Balances and deposit scanning; chain queries always go to rpc_url
+    #[serde(default)]
+    pub wallet_rpc: Option<WalletRpcConfig>,
+    // Credentials for a monerod started with --rpc-login (HTTP digest auth)
+    #[serde(default)]
+    pub rpc_username: Option<String>,
+    #[serde(default)]
+    pub rpc_password: Option<String>,
+}
+
+// monero-wallet-rpc with a view-only copy of the bridge wallet, restored from
+// `address` and the private view key
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WalletRpcConfig {
+    pub url: String,
+    #[serde(default)]
+    pub account: u32,
+    #[serde(default)]
+    pub rpc_username: Option<String>,
+    #[serde(default)]
+    pub rpc_password: Option<String>,
+    #[serde(default = "default_wallet_request_timeout_ms")]
+    pub request_timeout_ms: u64,
+    // Private view key of the bridge wallet; payouts are only funded from
+    // the wallet's outputs when it is set
+    #[serde(default)]
+    pub view_key: Option<String>,
+}
+
+fn default_wallet_request_timeout_ms() -> u64 {
+    30_000
+}
+
+// Subaddresses (account, 1..=count) of the bridge wallet to accept deposits on,
+// derived from its private view key and the public spend key in `address`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SubaddressConfig {
+    pub view_key: String,
+    #[serde(default)]
+    pub account: u32,
+    pub count: u32,
+    #[serde(default)]
+    pub recipients: Vec<SubaddressRecipient>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SubaddressRecipient {
+    pub index: u32,
+    pub recipient: String,
+}
+
+// HTTP client settings for monerod. Only connection failures and timeouts are
+// retried; an error returned by the daemon itself is final.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RpcClientConfig {
+    pub pool_max_idle_per_host: usize,
+    pub connect_timeout_ms: u64,
+    pub connect_retries: u32,
+    pub retry_backoff_ms: u64,
+    #[serde(default)]
+    pub timeouts: RpcTimeouts,
+}
+
+// monerod calls that get their own time budget, connection retries included
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RpcOperation {
+    CheckTx,
+    OutputDistribution,
+    GetOuts,
+    Broadcast,
+    GetTransactions,
+}
+
+impl fmt::Display for RpcOperation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::CheckTx => "check_tx",
+            Self::OutputDistribution => "get_output_distribution",
+            Self::GetOuts => "get_outs",
+            Self::Broadcast => "broadcast",
+            Self::GetTransactions => "get_transactions",
+        })
+    }
+}
+
+// A quick check_tx_key should not share a budget with scanning the whole
+// output distribution
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct RpcTimeouts {
+    pub check_tx_ms: u64,
+    pub output_distribution_ms: u64,
+    pub get_outs_ms: u64,
+    // Pre-checking and relaying a payout transaction
+    #[serde(default = "default_broadcast_ms")]
+    pub broadcast_ms: u64,
+    // Fetching the transactions that funded the bridge wallet's outputs
+    pub get_transactions_ms: u64,
+}
+
+fn default_broadcast_ms() -> u64 {
+    30_000
+}
+
+impl Default for RpcTimeouts {
+    fn default() -> Self {
+        Self {
+            check_tx_ms: 10_000,
+            output_distribution_ms: 60_000,
+            get_outs_ms: 20_000,
+            broadcast_ms: default_broadcast_ms(),
+            get_transactions_ms: 20_000,
+        }
+    }
+}
+
+impl RpcTimeouts {
+    pub fn for_operation(&self, operation: RpcOperation) -> std::time::Duration {
+        std::time::Duration::from_millis(match operation {
+            RpcOperation::CheckTx => self.check_tx_ms,
+            RpcOperation::OutputDistribution => self.output_distribution_ms,
+            RpcOperation::GetOuts => self.get_outs_ms,
+            RpcOperation::Broadcast => self.broadcast_ms,
+            RpcOperation::GetTransactions => self.get_transactions_ms,
+        })
+    }
+}
+
+impl Default for RpcClientConfig {
+    fn default() -> Self {
+        Self {
+            pool_max_idle_per_host: 8,
+            connect_timeout_ms: 5_000,
+            connect_retries: 2,
+            retry_backoff_ms: 500,
+            timeouts: RpcTimeouts::default(),
+        }
+    }
+}
+
+fn default_poll_jitter() -> f64 {
+    0.2
+}
+
+fn default_payout_fee() -> u64 {
+    60_000_000
+}
+
+fn default_ring_size() -> usize {
+    crate::payout::DEFAULT_RING_SIZE
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ConfirmationTier {
+    pub min_amount: u64, // piconero
+    pub required_confirmations: u64,
+}
+
+// A deposit may be off by relative_bps of the requested amount (at most
+// 100%) in either direction, and by no more than `absolute` when that is set
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct AmountTolerance {
+    pub absolute: u64, // piconero, 0 for no cap
+    pub relative_bps: u32,
+}
+
+impl AmountTolerance {
+    pub fn accepts(&self, received: u64, expected: u64) -> bool {
+        let relative = (expected as u128 * self.relative_bps.min(10_000) as u128 / 10_000) as u64;
+        let allowed = if self.absolute > 0 { relative.min(self.absolute) } else { relative };
+        received >= expected - allowed && received <= expected.saturating_add(allowed)
+    }
+}
+
+impl MoneroConfig {
+    // The tier with the largest threshold not exceeding the amount wins
+    pub fn required_confirmations_for(&self, amount: u64) -> u64 {
+        self.confirmation_tiers
+            .iter()
+            .filter(|tier| amount >= tier.min_amount)
+            .max_by_key(|tier| tier.min_amount)
+            .map(|tier| tier.required_confirmations)
+            .unwrap_or(self.required_confirmations)
+    }
+    
+    // Spreads validators sharing a monerod across the interval instead of
+    // having them all poll in lockstep
+    pub fn poll_interval(&self) -> std::time::Duration {
+        let base = self.check_interval_secs as f64;
+        let jitter = self.poll_jitter.clamp(0.0, 1.0);
+        let factor = if jitter > 0.0 {
+            rand::thread_rng().gen_range(1.0 - jitter..=1.0 + jitter)
+        } else {
+            1.0
+        };
+        std::time::Duration::from_secs_f64(base * factor)
+    }
+    
+    pub fn initial_poll_delay(&self) -> std::time::Duration {
+        if !self.randomize_phase || self.check_interval_secs == 0 {
+            return std::time::Duration::ZERO;
+        }
+        let base = self.check_interval_secs as f64;
+        std::time::Duration::from_secs_f64(rand::thread_rng().gen_range(0.0..base))
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct EthereumConfig {
+    // Presets fill in rpc_url, chain_id and contract_address when left unset
+    #[serde(default)]
+    pub chain: Chain,
+    #[serde(default)]
     pub rpc_url: String,
+    #[serde(default)]
+    pub chain_id: u64,
+    #[serde(default)]
     pub contract_address: String,
     pub private_key: Option<String>, // For validators
     pub gas_limit: u64,
     pub max_gas_price: String,
+    // Blocks a mint request event must be buried under before it is acted on
+    #[serde(default = "default_finality_depth")]
+    pub finality_depth: u64,
+    #[serde(default)]
+    pub signing_scheme: SigningScheme,
+    #[serde(default)]
+    pub discovery: DiscoveryMode,
+    // Websocket endpoint for eth_subscribe, alongside the HTTP rpc_url
+    #[serde(default)]
+    pub ws_url: Option<String>,
+    // Block the mint request cursor starts after on first run
+    #[serde(default)]
+    pub start_block: u64,
+    #[serde(default)]
+    pub rpc_timeouts: EthRpcTimeouts,
+}
+
+// Budget per Ethereum RPC call. The event cursor stays locked while a scan
+// runs, so a node that stops answering must not hold it indefinitely.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct EthRpcTimeouts {
+    pub block_number_ms: u64,
+    pub get_logs_ms: u64,
+}
+
+impl Default for EthRpcTimeouts {
+    fn default() -> Self {
+        Self {
+            block_number_ms: 10_000,
+            get_logs_ms: 30_000,
+        }
+    }
+}
+
+// How mint requests are found: eth_getLogs on every Monero poll, or a scan
+// triggered by each new head from a websocket subscription. `auto`
+// subscribes whenever ws_url is set.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiscoveryMode {
+    Poll,
+    Subscribe,
+    #[default]
+    Auto,
+}
+
+// What the threshold signature is computed over. `eip712` matches the digest
+// the contract gets from _hashTypedDataV4, so it can ecrecover the signer.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SigningScheme {
+    #[default]
+    Legacy,
+    Eip712,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MoneroNetwork {
+    Mainnet,
+    #[default]
+    Stagenet,
+    Testnet,
+}
+
+impl MoneroNetwork {
+    // Prefix of standard addresses on the network
+    pub fn address_prefix(self) -> u8 {
+        match self {
+            MoneroNetwork::Mainnet => 18,
+            MoneroNetwork::Stagenet => 24,
+            MoneroNetwork::Testnet => 53,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Chain {
+    Sepolia,
+    Mainnet,
+    Holesky,
+    // Anvil/Hardhat devnet
+    Local,
+    #[default]
+    Custom,
+}
+
+pub struct ChainPreset {
+    pub rpc_url: &'static str,
+    pub chain_id: u64,
+    // None where the bridge contract has not been deployed yet
+    pub contract_address: Option<&'static str>,
+}
+
+impl Chain {
+    pub fn preset(self) -> Option<ChainPreset> {
+        match self {
+            Chain::Sepolia => Some(ChainPreset {
+                rpc_url: "https://rpc.sepolia.org",
+                chain_id: 11155111,
+                contract_address: Some("0x34c209a799b47A4ba5753E17A1Dbf2F5a612fd23"),
+            }),
+            Chain::Mainnet => Some(ChainPreset {
+                rpc_url: "https://ethereum-rpc.publicnode.com",
+                chain_id: 1,
+                contract_address: None,
+            }),
+            Chain::Holesky => Some(ChainPreset {
+                rpc_url: "https://ethereum-holesky-rpc.publicnode.com",
+                chain_id: 17000,
+                contract_address: None,
+            }),
+            Chain::Local => Some(ChainPreset {
+                rpc_url: "http://127.0.0.1:8545",
+                chain_id: 31337,
+                contract_address: None,
+            }),
+            Chain::Custom => None,
+        }
+    }
+}
+
+impl EthereumConfig {
+    // Fills unset fields from the chain preset. An explicit chain_id must agree
+    // with the preset, since it is what mint signatures are bound to.
+    pub fn resolve_chain(&mut self) -> crate::error::Result<()> {
+        let missing = |field: &str| ValidatorError::Config(format!("ethereum.{} must be set for chain {:?}", field, self.chain));
+
+        if let Some(preset) = self.chain.preset() {
+            if self.chain_id == 0 {
+                self.chain_id = preset.chain_id;
+            } else if self.chain_id != preset.chain_id {
+                return Err(ValidatorError::Config(format!(
+                    "ethereum.chain_id {} does not match chain {:?} ({})",
+                    self.chain_id, self.chain, preset.chain_id
+                )));
+            }
+            if self.rpc_url.is_empty() {
+                self.rpc_url = preset.rpc_url.to_string();
+            }
+            if self.contract_address.is_empty() {
+                self.contract_address = preset.contract_address.ok_or_else(|| missing("contract_address"))?.to_string();
+            }
+        }
+
+        if self.rpc_url.is_empty() {
+            return Err(missing("rpc_url"));
+        }
+        if self.chain_id == 0 {
+            return Err(missing("chain_id"));
+        }
+        if self.contract_address.is_empty() {
+            return Err(missing("contract_address"));
+        }
+        self.discovery_mode()?;
+        Ok(())
+    }
+
+    // Poll or Subscribe, with Auto resolved against ws_url
+    pub fn discovery_mode(&self) -> crate::error::Result<DiscoveryMode> {
+        match (self.discovery, &self.ws_url) {
+            (DiscoveryMode::Subscribe, None) => Err(ValidatorError::Config(
+                "ethereum.discovery = \"subscribe\" requires ethereum.ws_url".to_string(),
+            )),
+            (DiscoveryMode::Auto, Some(_)) => Ok(DiscoveryMode::Subscribe),
+            (DiscoveryMode::Auto, None) => Ok(DiscoveryMode::Poll),
+            (mode, _) => Ok(mode),
+        }
+    }
+}
+
+fn default_finality_depth() -> u64 {
+    12
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -57,12 +578,75 @@ pub struct ValidatorConfig {
     pub threshold: usize,
     pub enable_consensus: bool,
     pub reshare_period_days: u32,
+    // Defaults to <key_gen_output_path>/<validator_id>/transport_key
+    #[serde(default)]
+    pub transport_key_path: Option<String>,
+    // Validator id of the node hosting the mint reservation store shared with
+    // the relay, on /reserve. Every node must name the same one; a validator
+    // will not start without it.
+    #[serde(default)]
+    pub reservation_authority: Option<usize>,
+    // A reservation whose holder never submitted lapses after this long
+    #[serde(default = "default_reservation_ttl_secs")]
+    pub reservation_ttl_secs: u64,
+    // Hex transport public keys of submitters outside the committee, e.g. the relay
+    #[serde(default)]
+    pub reservation_clients: Vec<String>,
+}
+
+fn default_reservation_ttl_secs() -> u64 {
+    600
 }
 
 impl Config {
-    pub fn load(path: &str) -> anyhow::Result<Self> {
+    pub fn load(path: &str) -> crate::error::Result<Self> {
         let content = std::fs::read_to_string(path)?;
-        let config: Config = toml::from_str(&content)?;
+        let mut config: Config = toml::from_str(&content)?;
+        config.ethereum.resolve_chain()?;
+        config.mpc.quorum_policy().validate(config.mpc.total_parties, config.mpc.threshold)?;
         Ok(config)
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ethereum_config(toml: &str) -> EthereumConfig {
+        toml::from_str(&format!("{}\ngas_limit = 300000\nmax_gas_price = \"20\"", toml)).unwrap()
+    }
+
+    #[test]
+    fn test_preset_populates_consistent_defaults() {
+        let mut config = ethereum_config(r#"chain = "sepolia""#);
+        config.resolve_chain().unwrap();
+
+        assert_eq!(config.chain_id, 11155111);
+        assert_eq!(config.rpc_url, "https://rpc.sepolia.org");
+        assert_eq!(config.contract_address, "0x34c209a799b47A4ba5753E17A1Dbf2F5a612fd23");
+
+        // Overrides win, but the chain id must still match the preset
+        let mut config = ethereum_config("chain = \"sepolia\"\nrpc_url = \"http://node:8545\"");
+        config.resolve_chain().unwrap();
+        assert_eq!(config.rpc_url, "http://node:8545");
+
+        let mut config = ethereum_config("chain = \"sepolia\"\nchain_id = 1");
+        assert!(matches!(config.resolve_chain(), Err(ValidatorError::Config(_))));
+
+        // No deployment is known on mainnet yet
+        let mut config = ethereum_config(r#"chain = "mainnet""#);
+        assert!(config.resolve_chain().is_err());
+    }
+
+    #[test]
+    fn test_custom_chain_requires_explicit_fields() {
+        let mut config = ethereum_config("chain = \"custom\"\nchain_id = 42");
+        assert!(matches!(config.resolve_chain(), Err(ValidatorError::Config(_))));
+
+        let mut config = ethereum_config(
+            "rpc_url = \"http://node:8545\"\nchain_id = 42\ncontract_address = \"0x0000000000000000000000000000000000000001\"",
+        );
+        config.resolve_chain().unwrap();
+        assert_eq!(config.chain, Chain::Custom);
+        assert_eq!(config.chain_id, 42);
+    }
+}
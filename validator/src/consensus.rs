@@ -0,0 +1,287 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use tracing::{debug, info, warn};
+
+use crate::error::{Result, ValidatorError};
+use crate::network::{ConsensusMessage, NetworkState};
+use crate::signing::SigningResult;
+use crate::transport::TransportKey;
+
+pub const SIGN_SHARE: &str = "SIGN_SHARE";
+pub const SIGN_COMPLETE: &str = "SIGN_COMPLETE";
+
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+#[async_trait]
+pub trait RoundDriver: Send + Sync {
+    // Runs one signing round coordinated by `leader`, resolving once the
+    // leader has assembled `threshold` signatures. Rounds that cannot complete
+    // may never resolve; the coordinator bounds them with a timeout.
+    async fn run_round(&self, leader: usize, attempt: u32, operation_hash: [u8; 32], threshold: usize) -> Result<Vec<SigningResult>>;
+}
+
+#[derive(Debug, Clone)]
+pub struct RoundOutcome {
+    pub leader: usize,
+    pub signatures: Vec<SigningResult>,
+}
+
+// Round-robin over the live set, starting at a position derived from the
+// operation so different operations don't all land on the same leader
+pub fn select_leader(operation_hash: &[u8; 32], attempt: u32, live: &[usize]) -> Option<usize> {
+    let mut live = live.to_vec();
+    live.sort_unstable();
+    live.dedup();
+    if live.is_empty() {
+        return None;
+    }
+
+    let start = u64::from_be_bytes(operation_hash[..8].try_into().unwrap()) as usize;
+    Some(live[start.wrapping_add(attempt as usize) % live.len()])
+}
+
+pub struct RoundCoordinator {
+    threshold: usize,
+    round_timeout: Duration,
+    max_attempts: u32,
+}
+
+impl RoundCoordinator {
+    pub fn new(threshold: usize, round_timeout: Duration, max_attempts: u32) -> Self {
+        Self { threshold, round_timeout, max_attempts }
+    }
+
+    pub async fn run(&self, driver: &dyn RoundDriver, operation_hash: [u8; 32], live: &[usize]) -> Result<RoundOutcome> {
+        if live.len() < self.threshold {
+            return Err(ValidatorError::QuorumNotReached {
+                msg_type: SIGN_SHARE.to_string(),
+                need: self.threshold,
+                have: live.len(),
+            });
+        }
+
+        for attempt in 0..self.max_attempts {
+            let leader = select_leader(&operation_hash, attempt, live).unwrap();
+
+            match tokio::time::timeout(self.round_timeout, driver.run_round(leader, attempt, operation_hash, self.threshold)).await {
+                Ok(Ok(signatures)) => {
+                    info!("Signing round for {} completed by leader {} after {} attempt(s)",
+                        hex::encode(operation_hash), leader, attempt + 1);
+                    return Ok(RoundOutcome { leader, signatures });
+                }
+                Ok(Err(e)) => {
+                    warn!("Signing round {} for {} failed under leader {}: {}", attempt, hex::encode(operation_hash), leader, e);
+                }
+                Err(_) => {
+                    warn!("Signing round {} for {} timed out under leader {}", attempt, hex::encode(operation_hash), leader);
+                }
+            }
+        }
+
+        Err(ValidatorError::RoundFailed {
+            operation_hash: hex::encode(operation_hash),
+            attempts: self.max_attempts,
+        })
+    }
+}
+
+// A threshold of distinct validators, each contributing exactly the share
+// they broadcast
+pub fn verify_completion(signatures: &[SigningResult], shares: &[SigningResult], threshold: usize) -> bool {
+    let mut signers: Vec<usize> = signatures.iter().map(|s| s.validator_id).collect();
+    signers.sort_unstable();
+    signers.dedup();
+
+    signers.len() == signatures.len()
+        && signers.len() >= threshold
+        && signatures.iter().all(|signature| shares.contains(signature))
+}
+
+// Drives rounds over the consensus message bus. Every validator broadcasts its
+// share; the leader waits for a threshold of shares and announces the result.
+// Shares survive a leader change, so the next leader can usually finish at once.
+pub struct MessageRoundDriver {
+    validator_id: usize,
+    network: NetworkState,
+    transport_key: Arc<TransportKey>,
+    own_share: SigningResult,
+}
+
+impl MessageRoundDriver {
+    pub fn new(validator_id: usize, network: NetworkState, transport_key: Arc<TransportKey>, own_share: SigningResult) -> Self {
+        Self { validator_id, network, transport_key, own_share }
+    }
+
+    async fn broadcast(&self, msg_type: &str, data: serde_json::Value) -> Result<()> {
+        let mut message = ConsensusMessage {
+            validator_id: self.validator_id,
+            msg_type: msg_type.to_string(),
+            data,
+            signature: vec![],
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_secs(),
+            hops: 0,
+        };
+        self.transport_key.sign_message(&mut message)?;
+        self.network.broadcast_message(message).await
+    }
+
+    async fn collected_shares(&self, operation_hash: &str) -> Vec<SigningResult> {
+        let mut shares = BTreeMap::new();
+        shares.insert(self.validator_id, self.own_share.clone());
+
+        for message in self.network.messages.read().await.iter() {
+            if message.msg_type != SIGN_SHARE || message.data["operation_hash"] != operation_hash {
+                continue;
+            }
+            if let Ok(share) = serde_json::from_value::<SigningResult>(message.data["share"].clone()) {
+                // The transport signature binds the message to its sender
+                if share.validator_id == message.validator_id {
+                    shares.insert(message.validator_id, share);
+                }
+            }
+        }
+
+        shares.into_values().collect()
+    }
+
+    // The leader's announcement is only believed for shares we hold ourselves:
+    // each must be the one its validator broadcast for this operation, and
+    // there must be a threshold of them. Shares still in flight fail the check
+    // until they arrive.
+    async fn completed_by(&self, leader: usize, operation_hash: &str, threshold: usize) -> Option<Vec<SigningResult>> {
+        let completions: Vec<Vec<SigningResult>> = self.network.messages.read().await.iter()
+            .filter(|m| m.msg_type == SIGN_COMPLETE && m.validator_id == leader && m.data["operation_hash"] == operation_hash)
+            .filter_map(|m| serde_json::from_value(m.data["signatures"].clone()).ok())
+            .collect();
+        if completions.is_empty() {
+            return None;
+        }
+
+        let shares = self.collected_shares(operation_hash).await;
+        completions.into_iter().find(|signatures| {
+            let verified = verify_completion(signatures, &shares, threshold);
+            if !verified {
+                debug!("SIGN_COMPLETE from leader {} for {} does not match the shares we hold yet", leader, operation_hash);
+            }
+            verified
+        })
+    }
+}
+
+#[async_trait]
+impl RoundDriver for MessageRoundDriver {
+    async fn run_round(&self, leader: usize, attempt: u32, operation_hash: [u8; 32], threshold: usize) -> Result<Vec<SigningResult>> {
+        let operation_hash = hex::encode(operation_hash);
+
+        self.broadcast(SIGN_SHARE, serde_json::json!({
+            "operation_hash": operation_hash,
+            "attempt": attempt,
+            "share": self.own_share,
+        })).await?;
+
+        loop {
+            if leader == self.validator_id {
+                let shares = self.collected_shares(&operation_hash).await;
+                if shares.len() >= threshold {
+                    self.broadcast(SIGN_COMPLETE, serde_json::json!({
+                        "operation_hash": operation_hash,
+                        "attempt": attempt,
+                        "signatures": shares,
+                    })).await?;
+                    return Ok(shares);
+                }
+            } else if let Some(signatures) = self.completed_by(leader, &operation_hash, threshold).await {
+                return Ok(signatures);
+            }
+
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    fn share(validator_id: usize) -> SigningResult {
+        SigningResult { r: [validator_id as u8; 32], s: [1u8; 32], v: 27, validator_id }
+    }
+
+    // Every validator but `offline` answers immediately
+    struct MockCommittee {
+        offline: usize,
+        leaders: Mutex<Vec<usize>>,
+    }
+
+    #[async_trait]
+    impl RoundDriver for MockCommittee {
+        async fn run_round(&self, leader: usize, _attempt: u32, _operation_hash: [u8; 32], threshold: usize) -> Result<Vec<SigningResult>> {
+            self.leaders.lock().unwrap().push(leader);
+            if leader == self.offline {
+                std::future::pending::<()>().await;
+            }
+            Ok((0..7).filter(|id| *id != self.offline).take(threshold).map(share).collect())
+        }
+    }
+
+    #[test]
+    fn test_leader_rotates_through_live_set() {
+        let live = [5, 1, 3, 0];
+        let hash = [0u8; 32];
+
+        let leaders: Vec<usize> = (0..4).map(|attempt| select_leader(&hash, attempt, &live).unwrap()).collect();
+        assert_eq!(leaders, vec![0, 1, 3, 5]);
+        assert_eq!(select_leader(&hash, 4, &live), Some(0));
+        assert_eq!(select_leader(&hash, 0, &[]), None);
+    }
+
+    #[tokio::test]
+    async fn test_unresponsive_leader_times_out_and_rotates() {
+        let live: Vec<usize> = (0..7).collect();
+        let hash = [9u8; 32];
+        let first_leader = select_leader(&hash, 0, &live).unwrap();
+
+        let committee = MockCommittee { offline: first_leader, leaders: Mutex::new(vec![]) };
+        let coordinator = RoundCoordinator::new(4, Duration::from_millis(50), 3);
+
+        let outcome = coordinator.run(&committee, hash, &live).await.unwrap();
+
+        assert_ne!(outcome.leader, first_leader);
+        assert_eq!(outcome.signatures.len(), 4);
+        assert_eq!(*committee.leaders.lock().unwrap(), vec![first_leader, outcome.leader]);
+    }
+
+    #[test]
+    fn test_completion_must_match_broadcast_shares() {
+        let shares: Vec<SigningResult> = (0..4).map(share).collect();
+
+        assert!(verify_completion(&shares[..3], &shares, 3));
+        // Too few signers, or one signer counted twice
+        assert!(!verify_completion(&shares[..2], &shares, 3));
+        assert!(!verify_completion(&[share(0), share(0), share(1)], &shares, 3));
+
+        // A share its validator never broadcast
+        let mut forged = share(2);
+        forged.s = [2u8; 32];
+        assert!(!verify_completion(&[share(0), share(1), forged], &shares, 3));
+        assert!(!verify_completion(&[share(0), share(1), share(5)], &shares, 3));
+    }
+
+    #[tokio::test]
+    async fn test_round_gives_up_after_max_attempts() {
+        let hash = [9u8; 32];
+        let live = [select_leader(&hash, 0, &[0, 1, 2, 3]).unwrap()];
+        let committee = MockCommittee { offline: live[0], leaders: Mutex::new(vec![]) };
+        let coordinator = RoundCoordinator::new(1, Duration::from_millis(20), 2);
+
+        let err = coordinator.run(&committee, hash, &live).await.unwrap_err();
+        assert!(matches!(err, ValidatorError::RoundFailed { attempts: 2, .. }));
+    }
+}
@@ -0,0 +1,310 @@
+use std::collections::BTreeSet;
+
+use async_trait::async_trait;
+use rand::Rng;
+use serde::Deserialize;
+use tracing::debug;
+
+use crate::config::RpcOperation;
+use crate::error::{Result, ValidatorError};
+use crate::payout::{OwnedOutput, RingMember};
+use crate::validation::MoneroValidator;
+
+// Parameters of wallet2's gamma picker, fitted to real spend ages in seconds
+const GAMMA_SHAPE: f64 = 19.28;
+const GAMMA_SCALE: f64 = 1.0 / 1.61;
+const DIFFICULTY_TARGET_SECS: f64 = 120.0;
+// Outputs younger than this many blocks are locked and cannot be ring members
+const SPENDABLE_AGE_BLOCKS: usize = 10;
+const DEFAULT_UNLOCK_TIME_SECS: f64 = SPENDABLE_AGE_BLOCKS as f64 * DIFFICULTY_TARGET_SECS;
+const RECENT_SPEND_WINDOW_SECS: f64 = 15.0 * DIFFICULTY_TARGET_SECS;
+const BLOCKS_IN_A_YEAR: usize = 86_400 * 365 / 120;
+// Picks per ring member before giving up on a thin distribution
+const PICK_ATTEMPTS_PER_MEMBER: usize = 100;
+
+// Cumulative count of RingCT outputs at each block from genesis
+#[derive(Debug, Clone)]
+pub struct OutputDistribution {
+    pub cumulative: Vec<u64>,
+}
+
+// Where the output distribution and the ring members' keys come from
+#[async_trait]
+pub trait OutputSource: Send + Sync {
+    async fn output_distribution(&self) -> Result<OutputDistribution>;
+    async fn ring_members(&self, global_indices: &[u64]) -> Result<Vec<RingMember>>;
+}
+
+#[derive(Deserialize)]
+struct DistributionEntry {
+    distribution: Vec<u64>,
+}
+
+#[derive(Deserialize)]
+struct OutKey {
+    key: String,
+    mask: String,
+    unlocked: bool,
+}
+
+#[async_trait]
+impl OutputSource for MoneroValidator {
+    async fn output_distribution(&self) -> Result<OutputDistribution> {
+        let response = self.post_to(RpcOperation::OutputDistribution, self.rpc_url(), &serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": "0",
+            "method": "get_output_distribution",
+            "params": { "amounts": [0], "cumulative": true, "from_height": 0, "binary": false },
+        })).await?;
+
+        let entry: DistributionEntry = serde_json::from_value(response["result"]["distributions"][0].clone())
+            .map_err(|e| ValidatorError::MoneroRpc(format!("Unexpected get_output_distribution response: {}", e)))?;
+        Ok(OutputDistribution { cumulative: entry.distribution })
+    }
+
+    async fn ring_members(&self, global_indices: &[u64]) -> Result<Vec<RingMember>> {
+        let endpoint = url::Url::parse(self.rpc_url())
+            .and_then(|url| url.join("/get_outs"))
+            .map_err(|e| ValidatorError::Config(format!("Invalid Monero RPC URL: {}", e)))?;
+        let outputs: Vec<serde_json::Value> = global_indices
+            .iter()
+            .map(|index| serde_json::json!({ "amount": 0, "index": index }))
+            .collect();
+        let response = self.post_to(RpcOperation::GetOuts, endpoint.as_str(), &serde_json::json!({ "outputs": outputs, "get_txid": false })).await?;
+
+        let outs: Vec<OutKey> = serde_json::from_value(response["outs"].clone())
+            .map_err(|e| ValidatorError::MoneroRpc(format!("Unexpected get_outs response: {}", e)))?;
+        if outs.len() != global_indices.len() {
+            return Err(ValidatorError::MoneroRpc(format!("Asked for {} outputs, got {}", global_indices.len(), outs.len())));
+        }
+
+        global_indices
+            .iter()
+            .zip(outs)
+            .filter(|(_, out)| out.unlocked)
+            .map(|(index, out)| {
+                Ok(RingMember {
+                    global_index: *index,
+                    public_key: key_bytes(&out.key)?,
+                    commitment: key_bytes(&out.mask)?,
+                })
+            })
+            .collect()
+    }
+}
+
+fn key_bytes(value: &str) -> Result<[u8; 32]> {
+    hex::decode(value)?
+        .try_into()
+        .map_err(|_| ValidatorError::MoneroRpc(format!("{} is not a 32 byte key", value)))
+}
+
+// Output ages following wallet2's gamma picker: ages are drawn from a gamma
+// distribution over log-seconds and mapped onto outputs by the chain's
+// recent output rate
+pub struct GammaPicker {
+    cumulative: Vec<u64>,
+    num_outputs: u64,
+    average_output_time: f64,
+}
+
+impl GammaPicker {
+    pub fn new(distribution: &OutputDistribution) -> Result<Self> {
+        let offsets = &distribution.cumulative;
+        if offsets.len() <= SPENDABLE_AGE_BLOCKS {
+            return Err(ValidatorError::Payout("Output distribution is too short to pick decoys from".to_string()));
+        }
+
+        let blocks_to_consider = offsets.len().min(BLOCKS_IN_A_YEAR);
+        let outputs_to_consider = offsets[offsets.len() - 1]
+            - if blocks_to_consider < offsets.len() { offsets[offsets.len() - blocks_to_consider - 1] } else { 0 };
+        if outputs_to_consider == 0 {
+            return Err(ValidatorError::Payout("No outputs in the last year to pick decoys from".to_string()));
+        }
+
+        let cumulative = offsets[..offsets.len() - SPENDABLE_AGE_BLOCKS].to_vec();
+        Ok(Self {
+            num_outputs: *cumulative.last().unwrap(),
+            cumulative,
+            average_output_time: DIFFICULTY_TARGET_SECS * blocks_to_consider as f64 / outputs_to_consider as f64,
+        })
+    }
+
+    // Spendable outputs the picker can choose from
+    pub fn num_outputs(&self) -> u64 {
+        self.num_outputs
+    }
+
+    // A global output index, or None when the draw fell outside the chain and should be retried
+    pub fn pick<R: Rng>(&self, rng: &mut R) -> Option<u64> {
+        let mut age = sample_gamma(rng, GAMMA_SHAPE, GAMMA_SCALE).exp();
+        if age > DEFAULT_UNLOCK_TIME_SECS {
+            age -= DEFAULT_UNLOCK_TIME_SECS;
+        } else {
+            age = rng.gen_range(0.0..RECENT_SPEND_WINDOW_SECS);
+        }
+
+        let outputs_back = (age / self.average_output_time) as u64;
+        if outputs_back >= self.num_outputs {
+            return None;
+        }
+        let target = self.num_outputs - 1 - outputs_back;
+
+        // Land on the block holding the target, then pick uniformly within it
+        let block = self.cumulative.partition_point(|count| *count <= target);
+        let first = if block == 0 { 0 } else { self.cumulative[block - 1] };
+        let in_block = self.cumulative[block] - first;
+        if in_block == 0 {
+            return None;
+        }
+        Some(first + rng.gen_range(0..in_block))
+    }
+}
+
+// Marsaglia and Tsang's method, valid for shape >= 1
+fn sample_gamma<R: Rng>(rng: &mut R, shape: f64, scale: f64) -> f64 {
+    let d = shape - 1.0 / 3.0;
+    let c = 1.0 / (9.0 * d).sqrt();
+    loop {
+        let x = sample_standard_normal(rng);
+        let v = (1.0 + c * x).powi(3);
+        if v <= 0.0 {
+            continue;
+        }
+        let u: f64 = rng.gen();
+        if u.ln() < 0.5 * x * x + d - d * v + d * v.ln() {
+            return d * v * scale;
+        }
+    }
+}
+
+fn sample_standard_normal<R: Rng>(rng: &mut R) -> f64 {
+    let u1: f64 = 1.0 - rng.gen::<f64>();
+    let u2: f64 = rng.gen();
+    (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+}
+
+pub struct DecoySelector {
+    ring_size: usize,
+}
+
+impl DecoySelector {
+    pub fn new(ring_size: usize) -> Self {
+        Self { ring_size }
+    }
+
+    // Picks ring_size - 1 distinct, unlocked decoys for `real`
+    pub async fn select(&self, source: &dyn OutputSource, real: &OwnedOutput) -> Result<Vec<RingMember>> {
+        let picker = GammaPicker::new(&source.output_distribution().await?)?;
+        let wanted = self.ring_size.saturating_sub(1);
+        if picker.num_outputs() < self.ring_size as u64 {
+            return Err(ValidatorError::Payout(format!(
+                "Only {} spendable outputs on chain, a ring of {} needs more", picker.num_outputs(), self.ring_size
+            )));
+        }
+
+        let mut decoys = Vec::with_capacity(wanted);
+        let mut chosen = BTreeSet::from([real.global_index]);
+        let mut attempts = 0;
+        while decoys.len() < wanted {
+            let mut batch = BTreeSet::new();
+            while batch.len() < wanted - decoys.len() {
+                if attempts == PICK_ATTEMPTS_PER_MEMBER * self.ring_size {
+                    return Err(ValidatorError::Payout(format!(
+                        "Found only {} of {} decoys for output {}", decoys.len(), wanted, real.global_index
+                    )));
+                }
+                attempts += 1;
+                if let Some(index) = picker.pick(&mut rand::thread_rng()) {
+                    if !chosen.contains(&index) {
+                        batch.insert(index);
+                    }
+                }
+            }
+
+            // Outputs that turn out to be locked are dropped and picked again
+            let batch: Vec<u64> = batch.into_iter().collect();
+            chosen.extend(batch.iter().copied());
+            let members = source.ring_members(&batch).await?;
+            debug!("Got {} of {} requested decoys for output {}", members.len(), batch.len(), real.global_index);
+            decoys.extend(members);
+        }
+
+        Ok(decoys)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    const OUTPUTS_PER_BLOCK: u64 = 40;
+    const CHAIN_HEIGHT: u64 = 400_000;
+
+    struct SyntheticChain;
+
+    #[async_trait]
+    impl OutputSource for SyntheticChain {
+        async fn output_distribution(&self) -> Result<OutputDistribution> {
+            Ok(OutputDistribution {
+                cumulative: (1..=CHAIN_HEIGHT).map(|height| height * OUTPUTS_PER_BLOCK).collect(),
+            })
+        }
+
+        async fn ring_members(&self, global_indices: &[u64]) -> Result<Vec<RingMember>> {
+            Ok(global_indices
+                .iter()
+                .map(|index| RingMember { global_index: *index, public_key: [1u8; 32], commitment: [2u8; 32] })
+                .collect())
+        }
+    }
+
+    fn age_in_blocks(index: u64) -> u64 {
+        CHAIN_HEIGHT - 1 - index / OUTPUTS_PER_BLOCK
+    }
+
+    #[tokio::test]
+    async fn test_selector_fills_ring_with_plausible_ages() {
+        let real = OwnedOutput { global_index: 5_000_000, public_key: [3u8; 32], amount: 1, mask: [0u8; 32] };
+        let decoys = DecoySelector::new(16).select(&SyntheticChain, &real).await.unwrap();
+
+        assert_eq!(decoys.len(), 15);
+        let distinct: BTreeSet<u64> = decoys.iter().map(|d| d.global_index).collect();
+        assert_eq!(distinct.len(), 15);
+        assert!(!distinct.contains(&real.global_index));
+
+        // Over many picks the ages follow the gamma fit: never locked, mostly
+        // recent, with a median around a couple of days and a long tail
+        let picker = GammaPicker::new(&SyntheticChain.output_distribution().await.unwrap()).unwrap();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        let mut ages: Vec<u64> = (0..5_000).filter_map(|_| picker.pick(&mut rng)).map(age_in_blocks).collect();
+        ages.sort_unstable();
+
+        assert!(ages[0] >= SPENDABLE_AGE_BLOCKS as u64);
+        let median = ages[ages.len() / 2];
+        assert!((300..5_000).contains(&median), "median age {} blocks", median);
+        assert!(ages.iter().filter(|age| **age < 720).count() > ages.len() / 4);
+        assert!(*ages.last().unwrap() > 20_000);
+    }
+
+    #[tokio::test]
+    async fn test_not_enough_outputs_for_ring() {
+        struct TinyChain;
+
+        #[async_trait]
+        impl OutputSource for TinyChain {
+            async fn output_distribution(&self) -> Result<OutputDistribution> {
+                Ok(OutputDistribution { cumulative: (1..=20).collect() })
+            }
+
+            async fn ring_members(&self, _global_indices: &[u64]) -> Result<Vec<RingMember>> {
+                Ok(vec![])
+            }
+        }
+
+        let real = OwnedOutput { global_index: 0, public_key: [3u8; 32], amount: 1, mask: [0u8; 32] };
+        let err = DecoySelector::new(16).select(&TinyChain, &real).await.unwrap_err();
+        assert!(matches!(err, ValidatorError::Payout(_)), "{}", err);
+    }
+}
@@ -0,0 +1,108 @@
+use std::collections::HashMap;
+
+use md5::{Digest, Md5};
+
+// HTTP digest authentication (RFC 2617) as used by monerod's --rpc-login
+#[derive(Debug, Clone)]
+pub struct DigestChallenge {
+    pub realm: String,
+    pub nonce: String,
+    pub opaque: Option<String>,
+    pub qop_auth: bool,
+}
+
+impl DigestChallenge {
+    pub fn parse(header: &str) -> Option<Self> {
+        let params = header.trim().strip_prefix("Digest ")?;
+        let fields = parse_params(params);
+
+        if let Some(algorithm) = fields.get("algorithm") {
+            if !algorithm.eq_ignore_ascii_case("MD5") {
+                return None;
+            }
+        }
+
+        Some(Self {
+            realm: fields.get("realm")?.clone(),
+            nonce: fields.get("nonce")?.clone(),
+            opaque: fields.get("opaque").cloned(),
+            qop_auth: fields
+                .get("qop")
+                .map(|qop| qop.split(',').any(|q| q.trim() == "auth"))
+                .unwrap_or(false),
+        })
+    }
+
+    pub fn authorization(&self, username: &str, password: &str, method: &str, uri: &str, nc: u32, cnonce: &str) -> String {
+        let ha1 = md5_hex(&format!("{}:{}:{}", username, self.realm, password));
+        let ha2 = md5_hex(&format!("{}:{}", method, uri));
+        let nc = format!("{:08x}", nc);
+
+        let response = if self.qop_auth {
+            md5_hex(&format!("{}:{}:{}:{}:auth:{}", ha1, self.nonce, nc, cnonce, ha2))
+        } else {
+            md5_hex(&format!("{}:{}:{}", ha1, self.nonce, ha2))
+        };
+
+        let mut header = format!(
+            "Digest username=\"{}\", realm=\"{}\", nonce=\"{}\", uri=\"{}\", algorithm=MD5, response=\"{}\"",
+            username, self.realm, self.nonce, uri, response
+        );
+        if self.qop_auth {
+            header.push_str(&format!(", qop=auth, nc={}, cnonce=\"{}\"", nc, cnonce));
+        }
+        if let Some(ref opaque) = self.opaque {
+            header.push_str(&format!(", opaque=\"{}\"", opaque));
+        }
+        header
+    }
+}
+
+// Splits `key="value", key=value` pairs, allowing commas inside quotes
+pub fn parse_params(params: &str) -> HashMap<String, String> {
+    let mut fields = HashMap::new();
+    let mut rest = params.trim();
+
+    while !rest.is_empty() {
+        let Some((key, after_key)) = rest.split_once('=') else { break };
+        let key = key.trim().trim_start_matches(',').trim().to_lowercase();
+
+        let (value, remaining) = if let Some(quoted) = after_key.trim_start().strip_prefix('"') {
+            match quoted.split_once('"') {
+                Some((value, remaining)) => (value, remaining),
+                None => (quoted, ""),
+            }
+        } else {
+            after_key.split_once(',').unwrap_or((after_key, ""))
+        };
+
+        fields.insert(key, value.trim().to_string());
+        rest = remaining.trim_start().trim_start_matches(',').trim_start();
+    }
+
+    fields
+}
+
+fn md5_hex(input: &str) -> String {
+    hex::encode(Md5::digest(input.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rfc2617_example() {
+        let challenge = DigestChallenge::parse(
+            r#"Digest realm="testrealm@host.com", qop="auth,auth-int", nonce="dcd98b7102dd2f0e8b11d0f600bfb0c093", opaque="5ccc069c403ebaf9f0171e9517f40e41""#,
+        )
+        .unwrap();
+
+        let header = challenge.authorization("Mufasa", "Circle Of Life", "GET", "/dir/index.html", 1, "0a4f113b");
+        let fields = parse_params(header.strip_prefix("Digest ").unwrap());
+
+        assert_eq!(fields["response"], "6629fae49393a05397450978507c4ef1");
+        assert_eq!(fields["nc"], "00000001");
+        assert_eq!(fields["opaque"], "5ccc069c403ebaf9f0171e9517f40e41");
+    }
+}
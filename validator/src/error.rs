@@ -0,0 +1,114 @@
+use thiserror::Error;
+
+use crate::config::RpcOperation;
+
+#[derive(Debug, Error)]
+pub enum ValidatorError {
+    #[error("configuration error: {0}")]
+    Config(String),
+
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+
+    #[error("invalid encoding: {0}")]
+    Encoding(#[from] hex::FromHexError),
+
+    #[error("insufficient key shares: have {have}, need {need}")]
+    InsufficientShares { have: usize, need: usize },
+
+    #[error("key material error: {0}")]
+    KeyMaterial(String),
+
+    #[error("keys already exist at {0}; pass --force to overwrite")]
+    KeysExist(String),
+
+    #[error("peer {peer} unreachable: {source}")]
+    PeerUnreachable {
+        peer: String,
+        #[source]
+        source: reqwest::Error,
+    },
+
+    #[error("monerod error: {0}")]
+    MoneroRpc(String),
+
+    #[error("monero-wallet-rpc error: {0}")]
+    WalletRpc(String),
+
+    #[error("signature verification failed: {0}")]
+    SignatureVerification(String),
+
+    #[error("validator {0} is not a committee member")]
+    NotCommitteeMember(usize),
+
+    #[error("quorum not reached for {msg_type}: need {need}, have {have}")]
+    QuorumNotReached { msg_type: String, need: usize, have: usize },
+
+    #[error("signing round for {operation_hash} failed after {attempts} attempts")]
+    RoundFailed { operation_hash: String, attempts: u32 },
+
+    #[error("{0} timed out")]
+    Timeout(RpcOperation),
+
+    #[error("cannot build payout: {0}")]
+    Payout(String),
+
+    #[error("party {party_index} is held by validator {validator_id} under another identity")]
+    PartyConflict { validator_id: usize, party_index: usize },
+
+    #[error("share backup error: {0}")]
+    Backup(String),
+}
+
+impl ValidatorError {
+    // Transient failures worth retrying; everything else needs operator attention
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            ValidatorError::Io(_)
+                | ValidatorError::PeerUnreachable { .. }
+                | ValidatorError::MoneroRpc(_)
+                | ValidatorError::WalletRpc(_)
+                | ValidatorError::Timeout(_)
+                | ValidatorError::QuorumNotReached { .. }
+                | ValidatorError::RoundFailed { .. }
+        )
+    }
+}
+
+impl From<k256::ecdsa::Error> for ValidatorError {
+    fn from(e: k256::ecdsa::Error) -> Self {
+        ValidatorError::SignatureVerification(e.to_string())
+    }
+}
+
+impl From<bip39::Error> for ValidatorError {
+    fn from(e: bip39::Error) -> Self {
+        ValidatorError::KeyMaterial(format!("invalid recovery mnemonic: {}", e))
+    }
+}
+
+impl From<toml::de::Error> for ValidatorError {
+    fn from(e: toml::de::Error) -> Self {
+        ValidatorError::Config(e.to_string())
+    }
+}
+
+pub type Result<T> = std::result::Result<T, ValidatorError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retryable_classification() {
+        assert!(ValidatorError::MoneroRpc("busy".to_string()).is_retryable());
+        assert!(ValidatorError::QuorumNotReached { msg_type: "SIGN".to_string(), need: 4, have: 3 }.is_retryable());
+        assert!(!ValidatorError::InsufficientShares { have: 3, need: 4 }.is_retryable());
+        assert!(!ValidatorError::NotCommitteeMember(9).is_retryable());
+        assert!(!ValidatorError::SignatureVerification("bad s".to_string()).is_retryable());
+    }
+}
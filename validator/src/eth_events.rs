@@ -0,0 +1,370 @@
+use async_trait::async_trait;
+use futures::{SinkExt, StreamExt};
+use serde::Deserialize;
+use serde_json::Value;
+use sha3::{Digest, Keccak256};
+use tokio_tungstenite::tungstenite::Message;
+use tracing::{debug, info, warn};
+
+use crate::config::EthRpcTimeouts;
+use crate::error::{Result, ValidatorError};
+use crate::event_cursor::{BurnEvent, EventSource, HeadSubscription, MintRequestEvent};
+
+const MINT_REQUESTED_SIGNATURE: &str = "MintRequested(bytes32,bytes32,address)";
+const BURN_SIGNATURE: &str = "Burn(address,uint256,string)";
+
+fn parse_quantity(value: &str) -> Result<u64> {
+    u64::from_str_radix(value.trim_start_matches("0x"), 16)
+        .map_err(|e| ValidatorError::Config(format!("Invalid quantity {} from Ethereum RPC: {}", value, e)))
+}
+
+fn rpc_error(method: &str, detail: impl std::fmt::Display) -> ValidatorError {
+    ValidatorError::Config(format!("Ethereum RPC {} failed: {}", method, detail))
+}
+
+// Plain JSON-RPC over HTTP, enough to follow contract logs
+pub struct EthRpc {
+    client: reqwest::Client,
+    url: String,
+    timeouts: EthRpcTimeouts,
+}
+
+impl EthRpc {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self { client: reqwest::Client::new(), url: url.into(), timeouts: EthRpcTimeouts::default() }
+    }
+
+    pub fn with_timeouts(mut self, timeouts: EthRpcTimeouts) -> Self {
+        self.timeouts = timeouts;
+        self
+    }
+
+    async fn call(&self, method: &str, params: Value, timeout_ms: u64) -> Result<Value> {
+        let mut response: Value = self.client
+            .post(&self.url)
+            .timeout(std::time::Duration::from_millis(timeout_ms))
+            .json(&serde_json::json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params }))
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|source| ValidatorError::PeerUnreachable { peer: self.url.clone(), source })?
+            .json()
+            .await
+            .map_err(|source| ValidatorError::PeerUnreachable { peer: self.url.clone(), source })?;
+
+        if let Some(error) = response.get("error") {
+            return Err(rpc_error(method, error));
+        }
+        Ok(response["result"].take())
+    }
+
+    pub async fn block_number(&self) -> Result<u64> {
+        let result = self.call("eth_blockNumber", serde_json::json!([]), self.timeouts.block_number_ms).await?;
+        parse_quantity(result.as_str().ok_or_else(|| rpc_error("eth_blockNumber", "no result"))?)
+    }
+
+    pub async fn get_logs(&self, address: &str, topic: &str, from_block: u64, to_block: u64) -> Result<Vec<Log>> {
+        let filter = serde_json::json!({
+            "address": address,
+            "topics": [topic],
+            "fromBlock": format!("0x{:x}", from_block),
+            "toBlock": format!("0x{:x}", to_block),
+        });
+        let result = self.call("eth_getLogs", serde_json::json!([filter]), self.timeouts.get_logs_ms).await?;
+        serde_json::from_value(result).map_err(|e| rpc_error("eth_getLogs", e))
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Log {
+    pub block_number: String,
+    pub block_hash: String,
+    pub log_index: String,
+    #[serde(default)]
+    pub transaction_hash: String,
+    pub topics: Vec<String>,
+    #[serde(default)]
+    pub data: String,
+    #[serde(default)]
+    pub removed: bool,
+}
+
+// The bridge contract's MintRequested(txId, txSecret, receiver) logs. The
+// event states no amount, so requests carry 0, which validation refuses
+// unless monero.accept_unstated_amount is set.
+pub struct MintRequestedLogs {
+    rpc: EthRpc,
+    contract_address: String,
+    topic: String,
+}
+
+impl MintRequestedLogs {
+    pub fn new(rpc_url: &str, contract_address: &str) -> Self {
+        Self {
+            rpc: EthRpc::new(rpc_url),
+            contract_address: contract_address.to_string(),
+            topic: format!("0x{}", hex::encode(Keccak256::digest(MINT_REQUESTED_SIGNATURE))),
+        }
+    }
+
+    pub fn with_timeouts(mut self, timeouts: EthRpcTimeouts) -> Self {
+        self.rpc = self.rpc.with_timeouts(timeouts);
+        self
+    }
+
+    fn decode(log: &Log) -> Result<MintRequestEvent> {
+        let [_, txid, tx_secret, receiver] = log.topics.as_slice() else {
+            return Err(rpc_error("eth_getLogs", format!("MintRequested log with {} topics", log.topics.len())));
+        };
+        let receiver = receiver.trim_start_matches("0x");
+
+        Ok(MintRequestEvent {
+            block_number: parse_quantity(&log.block_number)?,
+            block_hash: log.block_hash.clone(),
+            log_index: parse_quantity(&log.log_index)?,
+            txid: txid.trim_start_matches("0x").to_string(),
+            tx_key: tx_secret.trim_start_matches("0x").to_string(),
+            amount: 0,
+            destination: format!("0x{}", &receiver[receiver.len().saturating_sub(40)..]),
+        })
+    }
+}
+
+#[async_trait]
+impl EventSource for MintRequestedLogs {
+    type Event = MintRequestEvent;
+
+    async fn latest_block(&self) -> Result<u64> {
+        self.rpc.block_number().await
+    }
+
+    async fn events_in_range(&self, from_block: u64, to_block: u64) -> Result<Vec<MintRequestEvent>> {
+        self.rpc
+            .get_logs(&self.contract_address, &self.topic, from_block, to_block)
+            .await?
+            .iter()
+            .filter(|log| !log.removed)
+            .map(Self::decode)
+            .collect()
+    }
+}
+
+// The bridge contract's Burn(from, amount, moneroAddress) logs
+pub struct BurnLogs {
+    rpc: EthRpc,
+    contract_address: String,
+    topic: String,
+}
+
+impl BurnLogs {
+    pub fn new(rpc_url: &str, contract_address: &str) -> Self {
+        Self {
+            rpc: EthRpc::new(rpc_url),
+            contract_address: contract_address.to_string(),
+            topic: format!("0x{}", hex::encode(Keccak256::digest(BURN_SIGNATURE))),
+        }
+    }
+
+    pub fn with_timeouts(mut self, timeouts: EthRpcTimeouts) -> Self {
+        self.rpc = self.rpc.with_timeouts(timeouts);
+        self
+    }
+
+    fn decode(log: &Log) -> Result<BurnEvent> {
+        let [_, from] = log.topics.as_slice() else {
+            return Err(rpc_error("eth_getLogs", format!("Burn log with {} topics", log.topics.len())));
+        };
+        let from = from.trim_start_matches("0x");
+        let (amount, monero_address) = decode_amount_and_string(&log.data)
+            .ok_or_else(|| rpc_error("eth_getLogs", format!("Burn log with undecodable data {}", log.data)))?;
+
+        Ok(BurnEvent {
+            block_number: parse_quantity(&log.block_number)?,
+            block_hash: log.block_hash.clone(),
+            log_index: parse_quantity(&log.log_index)?,
+            tx_hash: log.transaction_hash.clone(),
+            from: format!("0x{}", &from[from.len().saturating_sub(40)..]),
+            amount,
+            monero_address,
+        })
+    }
+}
+
+#[async_trait]
+impl EventSource for BurnLogs {
+    type Event = BurnEvent;
+
+    async fn latest_block(&self) -> Result<u64> {
+        self.rpc.block_number().await
+    }
+
+    // A burn that cannot be decoded can never be paid out, so it is logged and
+    // passed over instead of holding back every burn after it
+    async fn events_in_range(&self, from_block: u64, to_block: u64) -> Result<Vec<BurnEvent>> {
+        Ok(self.rpc
+            .get_logs(&self.contract_address, &self.topic, from_block, to_block)
+            .await?
+            .iter()
+            .filter(|log| !log.removed)
+            .filter_map(|log| Self::decode(log)
+                .map_err(|e| warn!("Skipping burn log {} in block {}: {}", log.log_index, log.block_number, e))
+                .ok())
+            .collect())
+    }
+}
+
+// ABI-encoded (uint256, string) event data; None unless the amount fits a u64
+// and the string is well-formed UTF-8
+fn decode_amount_and_string(data: &str) -> Option<(u64, String)> {
+    let data = hex::decode(data.trim_start_matches("0x")).ok()?;
+    let word = |offset: usize| data.get(offset..offset.checked_add(32)?);
+    let small = |word: &[u8]| -> Option<u64> {
+        word[..24].iter().all(|byte| *byte == 0).then(|| u64::from_be_bytes(word[24..].try_into().unwrap()))
+    };
+
+    let amount = small(word(0)?)?;
+    let offset = usize::try_from(small(word(32)?)?).ok()?;
+    let len = usize::try_from(small(word(offset)?)?).ok()?;
+    let start = offset.checked_add(32)?;
+    let bytes = data.get(start..start.checked_add(len)?)?;
+    Some((amount, String::from_utf8(bytes.to_vec()).ok()?))
+}
+
+type WsStream = tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>;
+
+// eth_subscribe("newHeads") over a websocket
+pub struct WsNewHeads {
+    stream: WsStream,
+    subscription: String,
+}
+
+impl WsNewHeads {
+    pub async fn connect(ws_url: &str) -> Result<Self> {
+        let ws_error = |e: tokio_tungstenite::tungstenite::Error| rpc_error("eth_subscribe", e);
+        let (mut stream, _) = tokio_tungstenite::connect_async(ws_url).await.map_err(ws_error)?;
+
+        let request = serde_json::json!({ "jsonrpc": "2.0", "id": 1, "method": "eth_subscribe", "params": ["newHeads"] });
+        stream.send(Message::Text(request.to_string())).await.map_err(ws_error)?;
+
+        let subscription = loop {
+            let message = stream.next().await.ok_or_else(|| rpc_error("eth_subscribe", "connection closed"))?.map_err(ws_error)?;
+            let Message::Text(text) = message else { continue };
+            let response: Value = serde_json::from_str(&text)?;
+            if response["id"] != 1 {
+                continue;
+            }
+            if let Some(error) = response.get("error") {
+                return Err(rpc_error("eth_subscribe", error));
+            }
+            break response["result"].as_str().ok_or_else(|| rpc_error("eth_subscribe", "no subscription id"))?.to_string();
+        };
+
+        info!("Subscribed to new heads at {}", ws_url);
+        Ok(Self { stream, subscription })
+    }
+}
+
+#[async_trait]
+impl HeadSubscription for WsNewHeads {
+    async fn next_head(&mut self) -> Result<u64> {
+        loop {
+            let message = self.stream
+                .next()
+                .await
+                .ok_or_else(|| rpc_error("eth_subscribe", "connection closed"))?
+                .map_err(|e| rpc_error("eth_subscribe", e))?;
+            let text = match message {
+                Message::Text(text) => text,
+                Message::Close(frame) => return Err(rpc_error("eth_subscribe", format!("closed by node: {:?}", frame))),
+                _ => continue,
+            };
+
+            let notification: Value = serde_json::from_str(&text)?;
+            if notification["params"]["subscription"] != self.subscription.as_str() {
+                debug!("Ignoring websocket message: {}", text);
+                continue;
+            }
+            if let Some(number) = notification["params"]["result"]["number"].as_str() {
+                return parse_quantity(number);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decodes_mint_requested_log() {
+        let source = MintRequestedLogs::new("http://localhost:8545", "0x34c209a799b47A4ba5753E17A1Dbf2F5a612fd23");
+        assert_eq!(source.topic, format!("0x{}", hex::encode(Keccak256::digest(b"MintRequested(bytes32,bytes32,address)"))));
+
+        let log: Log = serde_json::from_value(serde_json::json!({
+            "blockNumber": "0x10",
+            "blockHash": "0xabc",
+            "logIndex": "0x2",
+            "topics": [
+                source.topic,
+                format!("0x{}", "11".repeat(32)),
+                format!("0x{}", "22".repeat(32)),
+                format!("0x{}{}", "00".repeat(12), "33".repeat(20)),
+            ],
+        }))
+        .unwrap();
+
+        let event = MintRequestedLogs::decode(&log).unwrap();
+        assert_eq!((event.block_number, event.log_index), (16, 2));
+        assert_eq!(event.txid, "11".repeat(32));
+        assert_eq!(event.tx_key, "22".repeat(32));
+        assert_eq!(event.destination, format!("0x{}", "33".repeat(20)));
+        assert_eq!(event.amount, 0);
+    }
+
+    #[tokio::test]
+    async fn test_unresponsive_node_times_out() {
+        // Accepts connections but never answers
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let source = MintRequestedLogs::new(&format!("http://{}", listener.local_addr().unwrap()), "0x34c209a799b47A4ba5753E17A1Dbf2F5a612fd23")
+            .with_timeouts(EthRpcTimeouts { block_number_ms: 200, get_logs_ms: 300 });
+
+        let started = std::time::Instant::now();
+        assert!(matches!(source.latest_block().await, Err(ValidatorError::PeerUnreachable { .. })));
+        assert!(matches!(source.events_in_range(1, 2).await, Err(ValidatorError::PeerUnreachable { .. })));
+        assert!(started.elapsed() < std::time::Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_decodes_burn_log() {
+        let source = BurnLogs::new("http://localhost:8545", "0x34c209a799b47A4ba5753E17A1Dbf2F5a612fd23");
+        assert_eq!(source.topic, format!("0x{}", hex::encode(Keccak256::digest(b"Burn(address,uint256,string)"))));
+
+        let address = "5".repeat(95);
+        let log = |data: String| -> Log {
+            serde_json::from_value(serde_json::json!({
+                "blockNumber": "0x10",
+                "blockHash": "0xabc",
+                "logIndex": "0x3",
+                "transactionHash": "0xdef",
+                "topics": [source.topic, format!("0x{}{}", "00".repeat(12), "44".repeat(20))],
+                "data": data,
+            }))
+            .unwrap()
+        };
+        let data = format!(
+            "0x{:064x}{:064x}{:064x}{}",
+            1_500_000_000_000u64, 64, address.len(), hex::encode(format!("{:\0<96}", address)),
+        );
+
+        let event = BurnLogs::decode(&log(data.clone())).unwrap();
+        assert_eq!((event.block_number, event.log_index, event.tx_hash.as_str()), (16, 3, "0xdef"));
+        assert_eq!(event.from, format!("0x{}", "44".repeat(20)));
+        assert_eq!((event.amount, event.monero_address.as_str()), (1_500_000_000_000, address.as_str()));
+
+        // Amounts beyond u64 and strings running past the data are rejected
+        let too_large = format!("0x01{}", &data[4..]);
+        assert!(BurnLogs::decode(&log(too_large)).is_err());
+        assert!(BurnLogs::decode(&log(data[..200].to_string())).is_err());
+    }
+}
@@ -0,0 +1,501 @@
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use serde::{Deserialize, Serialize};
+use crate::error::Result;
+use async_trait::async_trait;
+use tracing::{debug, info, warn};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MintRequestEvent {
+    pub block_number: u64,
+    pub block_hash: String,
+    pub log_index: u64,
+    pub txid: String,
+    pub tx_key: String,
+    pub amount: u64,
+    pub destination: String,
+}
+
+// WXMR burned on Ethereum, to be paid out in XMR to `monero_address`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BurnEvent {
+    pub block_number: u64,
+    pub block_hash: String,
+    pub log_index: u64,
+    pub tx_hash: String,
+    pub from: String,
+    pub amount: u64,
+    pub monero_address: String,
+}
+
+#[async_trait]
+pub trait EventSource: Send + Sync {
+    type Event: Send;
+
+    async fn latest_block(&self) -> Result<u64>;
+
+    // Inclusive range, as seen on the source's current canonical chain
+    async fn events_in_range(&self, from_block: u64, to_block: u64) -> Result<Vec<Self::Event>>;
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CursorState {
+    // Every event at or below this height has been handed out as final
+    finalized_block: u64,
+}
+
+#[derive(Debug)]
+pub struct CursorPoll<E> {
+    pub finalized: Vec<E>,
+    // Height the finalized events run up to; commit it once they are handled
+    pub finalized_to: u64,
+}
+
+// Finalized events handed out together with the height they run up to
+#[derive(Debug)]
+pub struct EventBatch<E> {
+    pub events: Vec<E>,
+    pub through_block: u64,
+}
+
+// Confirmation-lagged cursor over contract events. Events are only final
+// once they are `finality_depth` blocks deep; the window above that is
+// re-scanned on every poll, so events reorged out of it are never emitted and
+// events reorged into it are picked up.
+//
+// Polling only moves an in-memory mark. The persisted height moves on
+// `commit`, after the caller has handled the events, and `rewind` hands out
+// everything since the last commit again.
+pub struct BlockCursor {
+    path: PathBuf,
+    finality_depth: u64,
+    state: CursorState,
+    delivered_block: u64,
+    // Bumped by every rewind, so a scan can tell it was overtaken by one
+    rewinds: u64,
+}
+
+impl BlockCursor {
+    pub async fn open(path: impl Into<PathBuf>, finality_depth: u64, start_block: u64) -> Result<Self> {
+        let path = path.into();
+
+        let state = match tokio::fs::read_to_string(&path).await {
+            Ok(content) => serde_json::from_str(&content)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => CursorState {
+                finalized_block: start_block,
+            },
+            Err(e) => return Err(e.into()),
+        };
+
+        let delivered_block = state.finalized_block;
+        Ok(Self { path, finality_depth, state, delivered_block, rewinds: 0 })
+    }
+
+    // The persisted height; events above it are handed out again after a restart
+    pub fn finalized_block(&self) -> u64 {
+        self.state.finalized_block
+    }
+
+    pub async fn poll<E: Send>(&mut self, source: &dyn EventSource<Event = E>) -> Result<CursorPoll<E>> {
+        let head = source.latest_block().await?;
+        let safe_block = head.saturating_sub(self.finality_depth);
+        let finalized_to = safe_block.max(self.delivered_block);
+
+        let mut poll = CursorPoll { finalized: vec![], finalized_to };
+
+        if safe_block > self.delivered_block {
+            poll.finalized = source
+                .events_in_range(self.delivered_block + 1, safe_block)
+                .await?;
+        }
+
+        // Only advanced once the range has been read, so a failed fetch is
+        // retried from the same block instead of dropping its events
+        if finalized_to > self.delivered_block {
+            self.delivered_block = finalized_to;
+            debug!("Handed out events up to block {} ({} new)", finalized_to, poll.finalized.len());
+        }
+
+        Ok(poll)
+    }
+
+    // Events above the finality depth, which a reorg may still remove. Only
+    // fetched for callers that look at them.
+    pub async fn tentative<E: Send>(&self, source: &dyn EventSource<Event = E>) -> Result<Vec<E>> {
+        let head = source.latest_block().await?;
+        let from = head.saturating_sub(self.finality_depth).max(self.delivered_block);
+        if head <= from {
+            return Ok(vec![]);
+        }
+        let tentative = source.events_in_range(from + 1, head).await?;
+        debug!("{} tentative events above block {}", tentative.len(), from);
+        Ok(tentative)
+    }
+
+    // Records that every event up to `block` has been handled
+    pub async fn commit(&mut self, block: u64) -> Result<()> {
+        let block = block.min(self.delivered_block);
+        if block > self.state.finalized_block {
+            self.state.finalized_block = block;
+            self.persist().await?;
+            info!("Finalized events up to block {}", block);
+        }
+        Ok(())
+    }
+
+    pub fn rewind(&mut self) {
+        if self.delivered_block > self.state.finalized_block {
+            warn!("Rewinding event cursor from block {} to {}", self.delivered_block, self.state.finalized_block);
+        }
+        self.delivered_block = self.state.finalized_block;
+        self.rewinds += 1;
+    }
+
+    async fn persist(&self) -> Result<()> {
+        let tmp_path = self.path.with_extension("json.tmp");
+        tokio::fs::write(&tmp_path, serde_json::to_string(&self.state)?).await?;
+        tokio::fs::rename(&tmp_path, &self.path).await?;
+        Ok(())
+    }
+}
+
+// A source paired with its cursor, shareable between the node's tasks
+pub struct EventFeed<E> {
+    source: Arc<dyn EventSource<Event = E>>,
+    cursor: Arc<Mutex<BlockCursor>>,
+}
+
+pub type MintEventFeed = EventFeed<MintRequestEvent>;
+pub type BurnEventFeed = EventFeed<BurnEvent>;
+pub type MintEventDiscovery = EventDiscovery<MintRequestEvent>;
+
+impl<E> Clone for EventFeed<E> {
+    fn clone(&self) -> Self {
+        Self {
+            source: self.source.clone(),
+            cursor: self.cursor.clone(),
+        }
+    }
+}
+
+impl<E: Send> EventFeed<E> {
+    pub fn new(source: Arc<dyn EventSource<Event = E>>, cursor: BlockCursor) -> Self {
+        Self {
+            source,
+            cursor: Arc::new(Mutex::new(cursor)),
+        }
+    }
+
+    pub async fn next_finalized(&self) -> Result<EventBatch<E>> {
+        Ok(self.scan().await?.0)
+    }
+
+    // Also returns the cursor's rewind count the batch was read under
+    async fn scan(&self) -> Result<(EventBatch<E>, u64)> {
+        let mut cursor = self.cursor.lock().await;
+        let poll = cursor.poll(self.source.as_ref()).await?;
+        Ok((EventBatch { events: poll.finalized, through_block: poll.finalized_to }, cursor.rewinds))
+    }
+
+    pub async fn commit(&self, through_block: u64) -> Result<()> {
+        self.cursor.lock().await.commit(through_block).await
+    }
+
+    pub async fn rewind(&self) {
+        self.cursor.lock().await.rewind();
+    }
+}
+
+#[async_trait]
+pub trait HeadSubscription: Send {
+    // Waits for the next block header, returning its number
+    async fn next_head(&mut self) -> Result<u64>;
+}
+
+pub struct PushedEvents<E> {
+    batch: Mutex<EventBatch<E>>,
+    subscribed: AtomicBool,
+}
+
+// Where a node's events come from: polled on demand, or scanned as each new
+// head arrives and buffered until collected. Both drive the same cursor, so
+// moving between them, including the fallback to polling when a subscription
+// drops, neither skips nor repeats an event.
+pub enum EventDiscovery<E> {
+    Poll(EventFeed<E>),
+    Push { feed: EventFeed<E>, pushed: Arc<PushedEvents<E>> },
+}
+
+impl<E> Clone for EventDiscovery<E> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Poll(feed) => Self::Poll(feed.clone()),
+            Self::Push { feed, pushed } => Self::Push { feed: feed.clone(), pushed: pushed.clone() },
+        }
+    }
+}
+
+impl<E: Send + 'static> EventDiscovery<E> {
+    pub fn push(feed: EventFeed<E>, mut heads: Box<dyn HeadSubscription>) -> Self {
+        let pushed = Arc::new(PushedEvents {
+            batch: Mutex::new(EventBatch { events: Vec::new(), through_block: 0 }),
+            subscribed: AtomicBool::new(true),
+        });
+
+        let (scan_feed, scan_pushed) = (feed.clone(), pushed.clone());
+        tokio::spawn(async move {
+            loop {
+                let head = match heads.next_head().await {
+                    Ok(head) => head,
+                    Err(e) => {
+                        warn!("Head subscription ended, falling back to polling: {}", e);
+                        break;
+                    }
+                };
+                // A failed scan leaves the cursor where it was; the next head retries it
+                match scan_feed.scan().await {
+                    Ok((scanned, rewinds)) => scan_pushed.extend(&scan_feed, scanned, rewinds).await,
+                    Err(e) => warn!("Event scan at head {} failed: {}", head, e),
+                }
+            }
+            scan_pushed.subscribed.store(false, Ordering::SeqCst);
+        });
+
+        Self::Push { feed, pushed }
+    }
+
+    // Events handed out here are delivered again after a `rewind` or a
+    // restart unless their batch was committed
+    pub async fn next_events(&self) -> Result<EventBatch<E>> {
+        match self {
+            Self::Poll(feed) => feed.next_finalized().await,
+            Self::Push { feed, pushed } => {
+                if !pushed.subscribed.load(Ordering::SeqCst) {
+                    let (polled, rewinds) = feed.scan().await?;
+                    pushed.extend(feed, polled, rewinds).await;
+                }
+                let mut batch = pushed.batch.lock().await;
+                Ok(EventBatch { events: std::mem::take(&mut batch.events), through_block: batch.through_block })
+            }
+        }
+    }
+
+    pub async fn commit(&self, through_block: u64) -> Result<()> {
+        match self {
+            Self::Poll(feed) | Self::Push { feed, .. } => feed.commit(through_block).await,
+        }
+    }
+
+    pub async fn rewind(&self) {
+        match self {
+            Self::Poll(feed) => feed.rewind().await,
+            Self::Push { feed, pushed } => {
+                // Buffered events come back with the rescan
+                let mut batch = pushed.batch.lock().await;
+                feed.rewind().await;
+                batch.events.clear();
+                batch.through_block = 0;
+            }
+        }
+    }
+}
+
+impl<E> PushedEvents<E> {
+    // A scan that a rewind overtook is dropped: the rewind has already
+    // cleared the buffer, and the rescan after it delivers the same events
+    async fn extend(&self, feed: &EventFeed<E>, scanned: EventBatch<E>, rewinds: u64) {
+        let mut batch = self.batch.lock().await;
+        if feed.cursor.lock().await.rewinds != rewinds {
+            debug!("Dropping {} events scanned before a rewind", scanned.events.len());
+            return;
+        }
+        batch.events.extend(scanned.events);
+        batch.through_block = batch.through_block.max(scanned.through_block);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    struct MockChain {
+        head: Mutex<u64>,
+        events: Mutex<Vec<MintRequestEvent>>,
+        fetches: Mutex<usize>,
+    }
+
+    impl MockChain {
+        fn new(head: u64, events: Vec<MintRequestEvent>) -> Self {
+            Self { head: Mutex::new(head), events: Mutex::new(events), fetches: Mutex::new(0) }
+        }
+    }
+
+    #[async_trait]
+    impl EventSource for MockChain {
+        type Event = MintRequestEvent;
+
+        async fn latest_block(&self) -> Result<u64> {
+            Ok(*self.head.lock().unwrap())
+        }
+
+        async fn events_in_range(&self, from_block: u64, to_block: u64) -> Result<Vec<MintRequestEvent>> {
+            *self.fetches.lock().unwrap() += 1;
+            Ok(self.events.lock().unwrap()
+                .iter()
+                .filter(|e| e.block_number >= from_block && e.block_number <= to_block)
+                .cloned()
+                .collect())
+        }
+    }
+
+    fn event(block_number: u64, block_hash: &str, txid: &str) -> MintRequestEvent {
+        MintRequestEvent {
+            block_number,
+            block_hash: block_hash.to_string(),
+            log_index: 0,
+            txid: txid.to_string(),
+            tx_key: "key".to_string(),
+            amount: 1_000_000_000_000,
+            destination: "dest".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reorged_out_event_is_never_finalized() {
+        let dir = tempfile::tempdir().unwrap();
+        let chain = MockChain::new(10, vec![event(3, "0xa3", "early"), event(8, "0xa8", "orphaned")]);
+        let mut cursor = BlockCursor::open(dir.path().join("cursor.json"), 5, 0).await.unwrap();
+
+        let poll = cursor.poll(&chain).await.unwrap();
+        assert_eq!(poll.finalized.iter().map(|e| e.txid.as_str()).collect::<Vec<_>>(), vec!["early"]);
+        assert_eq!(poll.finalized_to, 5);
+        // Polling reads only the finalized range
+        assert_eq!(*chain.fetches.lock().unwrap(), 1);
+        assert_eq!(txids(&cursor.tentative(&chain).await.unwrap()), vec!["orphaned"]);
+
+        // Reorg replaces block 8 and adds a new event at block 9
+        *chain.events.lock().unwrap() = vec![event(3, "0xa3", "early"), event(9, "0xb9", "reorged_in")];
+        *chain.head.lock().unwrap() = 14;
+
+        let poll = cursor.poll(&chain).await.unwrap();
+        assert_eq!(poll.finalized.iter().map(|e| e.txid.as_str()).collect::<Vec<_>>(), vec!["reorged_in"]);
+        assert_eq!(poll.finalized_to, 9);
+        assert!(cursor.tentative(&chain).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_cursor_persists_across_restarts() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cursor.json");
+        let chain = MockChain::new(20, vec![event(4, "0xa4", "first")]);
+
+        let mut cursor = BlockCursor::open(&path, 5, 0).await.unwrap();
+        let poll = cursor.poll(&chain).await.unwrap();
+        assert_eq!(poll.finalized.len(), 1);
+        cursor.commit(poll.finalized_to).await.unwrap();
+
+        let mut reopened = BlockCursor::open(&path, 5, 0).await.unwrap();
+        assert_eq!(reopened.finalized_block(), 15);
+        assert!(reopened.poll(&chain).await.unwrap().finalized.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_uncommitted_events_are_handed_out_again() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cursor.json");
+        let chain = Arc::new(MockChain::new(20, vec![event(4, "0xa4", "first")]));
+        let feed = EventFeed::new(chain.clone(), BlockCursor::open(&path, 5, 0).await.unwrap());
+
+        // Handed out once while it is being processed
+        assert_eq!(txids(&feed.next_finalized().await.unwrap().events), vec!["first"]);
+        assert!(feed.next_finalized().await.unwrap().events.is_empty());
+
+        // Processing failed: the same event comes back, and would after a restart
+        feed.rewind().await;
+        let batch = feed.next_finalized().await.unwrap();
+        assert_eq!(txids(&batch.events), vec!["first"]);
+        let reopened = EventFeed::new(chain.clone(), BlockCursor::open(&path, 5, 0).await.unwrap());
+        assert_eq!(txids(&reopened.next_finalized().await.unwrap().events), vec!["first"]);
+
+        feed.commit(batch.through_block).await.unwrap();
+        let reopened = EventFeed::new(chain, BlockCursor::open(&path, 5, 0).await.unwrap());
+        assert!(reopened.next_finalized().await.unwrap().events.is_empty());
+    }
+
+    struct ChannelHeads(tokio::sync::mpsc::UnboundedReceiver<u64>);
+
+    #[async_trait]
+    impl HeadSubscription for ChannelHeads {
+        async fn next_head(&mut self) -> Result<u64> {
+            self.0.recv().await.ok_or_else(|| crate::error::ValidatorError::Config("subscription closed".to_string()))
+        }
+    }
+
+    fn txids(events: &[MintRequestEvent]) -> Vec<&str> {
+        events.iter().map(|e| e.txid.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_poll_and_push_discover_the_same_events() {
+        let dir = tempfile::tempdir().unwrap();
+        let chain = Arc::new(MockChain::new(10, vec![event(3, "0xa3", "first"), event(5, "0xa5", "second")]));
+
+        let polled = EventDiscovery::Poll(EventFeed::new(chain.clone(), BlockCursor::open(dir.path().join("poll.json"), 5, 0).await.unwrap()));
+        let (heads, head_receiver) = tokio::sync::mpsc::unbounded_channel();
+        let pushed = EventDiscovery::push(
+            EventFeed::new(chain.clone(), BlockCursor::open(dir.path().join("push.json"), 5, 0).await.unwrap()),
+            Box::new(ChannelHeads(head_receiver)),
+        );
+        let EventDiscovery::Push { pushed: ref buffer, .. } = pushed else { unreachable!() };
+
+        // Nothing is scanned until a head arrives
+        assert!(pushed.next_events().await.unwrap().events.is_empty());
+        heads.send(10).unwrap();
+        let mut from_push = Vec::new();
+        while from_push.len() < 2 {
+            from_push.extend(pushed.next_events().await.unwrap().events);
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        assert_eq!(txids(&from_push), txids(&polled.next_events().await.unwrap().events));
+        assert_eq!(txids(&from_push), vec!["first", "second"]);
+
+        // Events landing while the subscription drops are picked up by polling
+        chain.events.lock().unwrap().push(event(8, "0xa8", "third"));
+        *chain.head.lock().unwrap() = 13;
+        drop(heads);
+        while buffer.subscribed.load(Ordering::SeqCst) {
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        assert_eq!(txids(&pushed.next_events().await.unwrap().events), vec!["third"]);
+        assert_eq!(txids(&polled.next_events().await.unwrap().events), vec!["third"]);
+        assert!(pushed.next_events().await.unwrap().events.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_scan_overtaken_by_rewind_is_dropped() {
+        let dir = tempfile::tempdir().unwrap();
+        let chain = Arc::new(MockChain::new(10, vec![event(3, "0xa3", "first")]));
+        let (heads, head_receiver) = tokio::sync::mpsc::unbounded_channel();
+        let discovery = EventDiscovery::push(
+            EventFeed::new(chain, BlockCursor::open(dir.path().join("push.json"), 5, 0).await.unwrap()),
+            Box::new(ChannelHeads(head_receiver)),
+        );
+        let EventDiscovery::Push { ref feed, pushed: ref buffer } = discovery else { unreachable!() };
+
+        // A scan is still in flight when processing fails and the cursor is rewound
+        let (in_flight, rewinds) = feed.scan().await.unwrap();
+        discovery.rewind().await;
+        buffer.extend(feed, in_flight, rewinds).await;
+        assert!(discovery.next_events().await.unwrap().events.is_empty());
+
+        // The rescan after the rewind delivers the event exactly once
+        let (rescanned, rewinds) = feed.scan().await.unwrap();
+        buffer.extend(feed, rescanned, rewinds).await;
+        assert_eq!(txids(&discovery.next_events().await.unwrap().events), vec!["first"]);
+        assert!(discovery.next_events().await.unwrap().events.is_empty());
+        drop(heads);
+    }
+}
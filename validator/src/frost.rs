@@ -0,0 +1,619 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use curve25519_dalek::constants::ED25519_BASEPOINT_TABLE;
+use curve25519_dalek::edwards::{CompressedEdwardsY, EdwardsPoint};
+use curve25519_dalek::scalar::Scalar;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha512};
+use tracing::{info, warn};
+
+use crate::error::{Result, ValidatorError};
+use crate::network::{ConsensusMessage, NetworkState};
+use crate::payout::UnsignedTransaction;
+use crate::transport::TransportKey;
+
+pub const FROST_COMMIT: &str = "FROST_COMMIT";
+pub const FROST_SHARE: &str = "FROST_SHARE";
+
+const CONTEXT: &[u8] = b"FROST-ED25519-SHA512-v1";
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+// A participant's Shamir share of the joint ed25519 spend key. Identifiers
+// are the evaluation points of the sharing polynomial and start at 1.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct FrostKeyShare {
+    pub identifier: u16,
+    pub threshold: usize,
+    pub signing_share: [u8; 32],
+    // s_i*G for every participant, to check their signature shares
+    pub verifying_shares: BTreeMap<u16, [u8; 32]>,
+    pub group_public_key: [u8; 32],
+}
+
+impl std::fmt::Debug for FrostKeyShare {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FrostKeyShare")
+            .field("identifier", &self.identifier)
+            .field("threshold", &self.threshold)
+            .field("signing_share", &crate::redact::redact(&self.signing_share))
+            .field("group_public_key", &hex::encode(self.group_public_key))
+            .finish()
+    }
+}
+
+// Secret nonces from round one; used for exactly one signature and then dropped
+pub struct SigningNonces {
+    hiding: Scalar,
+    binding: Scalar,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SigningCommitment {
+    pub identifier: u16,
+    pub hiding: [u8; 32],
+    pub binding: [u8; 32],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignatureShare {
+    pub identifier: u16,
+    pub z: [u8; 32],
+}
+
+// Ed25519-style Schnorr signature: s*G = R + H(R || A || m)*A
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Signature {
+    pub r: [u8; 32],
+    pub s: [u8; 32],
+}
+
+// Round one: fresh nonces and the commitments to publish
+pub fn commit(share: &FrostKeyShare) -> (SigningNonces, SigningCommitment) {
+    let nonces = SigningNonces { hiding: random_scalar(), binding: random_scalar() };
+    let commitment = SigningCommitment {
+        identifier: share.identifier,
+        hiding: (&nonces.hiding * ED25519_BASEPOINT_TABLE).compress().to_bytes(),
+        binding: (&nonces.binding * ED25519_BASEPOINT_TABLE).compress().to_bytes(),
+    };
+    (nonces, commitment)
+}
+
+// Round two: this participant's share of the signature over `message`,
+// given the commitments of everyone signing
+pub fn sign(share: &FrostKeyShare, nonces: SigningNonces, message: &[u8], commitments: &[SigningCommitment]) -> Result<SignatureShare> {
+    let package = SigningPackage::new(&share.group_public_key, message, commitments, share.threshold)?;
+    if !package.commitments.contains_key(&share.identifier) {
+        return Err(ValidatorError::SignatureVerification(format!(
+            "Participant {} is not part of the signing set", share.identifier
+        )));
+    }
+
+    let signing_share = scalar(&share.signing_share)?;
+    let lambda = package.lagrange_coefficient(share.identifier);
+    let rho = package.binding_factors[&share.identifier];
+    let z = nonces.hiding + nonces.binding * rho + lambda * signing_share * package.challenge;
+
+    Ok(SignatureShare { identifier: share.identifier, z: z.to_bytes() })
+}
+
+// Checks every share against its signer's verifying share, so a misbehaving
+// participant is identified instead of producing an invalid signature
+pub fn aggregate(
+    share: &FrostKeyShare,
+    message: &[u8],
+    commitments: &[SigningCommitment],
+    signature_shares: &[SignatureShare],
+) -> Result<Signature> {
+    let package = SigningPackage::new(&share.group_public_key, message, commitments, share.threshold)?;
+
+    let mut s = Scalar::ZERO;
+    for (identifier, (hiding, binding)) in &package.commitments {
+        let signature_share = signature_shares
+            .iter()
+            .find(|candidate| candidate.identifier == *identifier)
+            .ok_or_else(|| ValidatorError::SignatureVerification(format!("Missing signature share from {}", identifier)))?;
+        let z = scalar(&signature_share.z)?;
+        let verifying_share = share
+            .verifying_shares
+            .get(identifier)
+            .ok_or_else(|| ValidatorError::SignatureVerification(format!("Unknown participant {}", identifier)))
+            .and_then(point)?;
+
+        let expected = hiding + binding * package.binding_factors[identifier]
+            + verifying_share * (package.challenge * package.lagrange_coefficient(*identifier));
+        if &z * ED25519_BASEPOINT_TABLE != expected {
+            return Err(ValidatorError::SignatureVerification(format!("Invalid signature share from {}", identifier)));
+        }
+        s += z;
+    }
+
+    Ok(Signature { r: package.group_commitment.compress().to_bytes(), s: s.to_bytes() })
+}
+
+pub fn verify(public_key: &[u8; 32], message: &[u8], signature: &Signature) -> bool {
+    let (Ok(public), Ok(r), Ok(s)) = (point(public_key), point(&signature.r), scalar(&signature.s)) else {
+        return false;
+    };
+    let challenge = challenge(&r, &public, message);
+    &s * ED25519_BASEPOINT_TABLE == r + public * challenge
+}
+
+// Shamir split of `secret` into `total` shares, any `threshold` of which can
+// sign. The polynomial is derived from the secret, so dealing the same key
+// again hands every participant the share it already holds.
+//
+// Validators only ever deal their own additive share of the spend key; see
+// combine_dealings.
+pub fn deal_shares(secret: &Scalar, threshold: usize, total: usize) -> Result<Vec<FrostKeyShare>> {
+    if threshold == 0 || threshold > total || total > u16::MAX as usize {
+        return Err(ValidatorError::KeyMaterial(format!("Cannot share a key {} of {}", threshold, total)));
+    }
+
+    let mut coefficients = vec![*secret];
+    coefficients.extend((1..threshold).map(|k| hash_to_scalar(&[CONTEXT, b"deal", secret.as_bytes(), &(k as u64).to_le_bytes()])));
+
+    let signing_shares: Vec<(u16, Scalar)> = (1..=total as u16)
+        .map(|identifier| {
+            let x = Scalar::from(identifier);
+            let value = coefficients.iter().rev().fold(Scalar::ZERO, |acc, c| acc * x + c);
+            (identifier, value)
+        })
+        .collect();
+
+    let verifying_shares: BTreeMap<u16, [u8; 32]> = signing_shares
+        .iter()
+        .map(|(identifier, share)| (*identifier, (share * ED25519_BASEPOINT_TABLE).compress().to_bytes()))
+        .collect();
+    let group_public_key = (secret * ED25519_BASEPOINT_TABLE).compress().to_bytes();
+
+    Ok(signing_shares
+        .into_iter()
+        .map(|(identifier, share)| FrostKeyShare {
+            identifier,
+            threshold,
+            signing_share: share.to_bytes(),
+            verifying_shares: verifying_shares.clone(),
+            group_public_key,
+        })
+        .collect())
+}
+
+// Sums the dealings a participant received, one from every validator, into
+// its share of the joint spend key. Each dealing is a sharing of that
+// validator's additive share, so the sum is a sharing of the joint key that
+// no one ever reconstructed.
+pub fn combine_dealings(identifier: u16, dealings: &[FrostKeyShare]) -> Result<FrostKeyShare> {
+    let first = dealings.first().ok_or_else(|| ValidatorError::KeyMaterial("No FROST dealings to combine".to_string()))?;
+
+    let mut signing_share = Scalar::ZERO;
+    let mut verifying_shares: BTreeMap<u16, EdwardsPoint> = BTreeMap::new();
+    let mut group_public_key = EdwardsPoint::default();
+    for (dealer, dealing) in dealings.iter().enumerate() {
+        let malformed = |what: &str| ValidatorError::KeyMaterial(format!("FROST dealing {} for participant {} {}", dealer, identifier, what));
+        if dealing.identifier != identifier || dealing.threshold != first.threshold {
+            return Err(malformed("is addressed to another participant or threshold"));
+        }
+        if dealing.verifying_shares.keys().ne(first.verifying_shares.keys()) {
+            return Err(malformed("covers a different set of participants"));
+        }
+        if !dealing_is_consistent(dealing)? {
+            return Err(malformed("does not lie on a single polynomial of the threshold's degree"));
+        }
+        let share = scalar(&dealing.signing_share)?;
+        if dealing.verifying_shares.get(&identifier) != Some(&(&share * ED25519_BASEPOINT_TABLE).compress().to_bytes()) {
+            return Err(malformed("does not match its own verifying share"));
+        }
+
+        signing_share += share;
+        for (participant, verifying_share) in &dealing.verifying_shares {
+            *verifying_shares.entry(*participant).or_default() += point(verifying_share)?;
+        }
+        group_public_key += point(&dealing.group_public_key)?;
+    }
+
+    Ok(FrostKeyShare {
+        identifier,
+        threshold: first.threshold,
+        signing_share: signing_share.to_bytes(),
+        verifying_shares: verifying_shares.into_iter().map(|(participant, share)| (participant, share.compress().to_bytes())).collect(),
+        group_public_key: group_public_key.compress().to_bytes(),
+    })
+}
+
+// The dealer's public key and every verifying share must interpolate from
+// the first `threshold` of them, or some participants were dealt shares of
+// a different key
+fn dealing_is_consistent(dealing: &FrostKeyShare) -> Result<bool> {
+    let points: Vec<(Scalar, EdwardsPoint)> = dealing
+        .verifying_shares
+        .iter()
+        .map(|(identifier, share)| Ok((Scalar::from(*identifier), point(share)?)))
+        .collect::<Result<_>>()?;
+    if dealing.threshold == 0 || points.len() < dealing.threshold {
+        return Ok(false);
+    }
+
+    let (basis, rest) = points.split_at(dealing.threshold);
+    let interpolate = |x: Scalar| -> EdwardsPoint {
+        basis
+            .iter()
+            .map(|(x_i, y_i)| {
+                let (numerator, denominator) = basis
+                    .iter()
+                    .filter(|(x_j, _)| x_j != x_i)
+                    .fold((Scalar::ONE, Scalar::ONE), |(num, den), (x_j, _)| (num * (x - x_j), den * (x_i - x_j)));
+                y_i * (numerator * denominator.invert())
+            })
+            .sum()
+    };
+
+    Ok(interpolate(Scalar::ZERO) == point(&dealing.group_public_key)?
+        && rest.iter().all(|(x, y)| interpolate(*x) == *y))
+}
+
+// Everything round two derives from the message and the published commitments
+struct SigningPackage {
+    commitments: BTreeMap<u16, (EdwardsPoint, EdwardsPoint)>,
+    binding_factors: BTreeMap<u16, Scalar>,
+    group_commitment: EdwardsPoint,
+    challenge: Scalar,
+}
+
+impl SigningPackage {
+    fn new(group_public_key: &[u8; 32], message: &[u8], commitments: &[SigningCommitment], threshold: usize) -> Result<Self> {
+        let group_public = point(group_public_key)?;
+
+        let mut decoded = BTreeMap::new();
+        for commitment in commitments {
+            let pair = (point(&commitment.hiding)?, point(&commitment.binding)?);
+            if commitment.identifier == 0 || decoded.insert(commitment.identifier, pair).is_some() {
+                return Err(ValidatorError::SignatureVerification(format!(
+                    "Invalid or duplicate commitment from {}", commitment.identifier
+                )));
+            }
+        }
+        if decoded.len() < threshold {
+            return Err(ValidatorError::InsufficientShares { have: decoded.len(), need: threshold });
+        }
+
+        // Binding factors commit to the whole signing set, so a commitment
+        // cannot be reused with a different set of co-signers
+        let mut encoded_commitments = Vec::with_capacity(decoded.len() * 66);
+        for (identifier, (hiding, binding)) in &decoded {
+            encoded_commitments.extend_from_slice(&identifier.to_le_bytes());
+            encoded_commitments.extend_from_slice(hiding.compress().as_bytes());
+            encoded_commitments.extend_from_slice(binding.compress().as_bytes());
+        }
+        let message_hash = Sha512::digest([CONTEXT, b"msg", message].concat());
+        let commitments_hash = Sha512::digest([CONTEXT, b"com", &encoded_commitments].concat());
+
+        let binding_factors: BTreeMap<u16, Scalar> = decoded
+            .keys()
+            .map(|identifier| {
+                let rho = hash_to_scalar(&[CONTEXT, b"rho", group_public_key, &message_hash, &commitments_hash, &identifier.to_le_bytes()]);
+                (*identifier, rho)
+            })
+            .collect();
+
+        let group_commitment = decoded
+            .iter()
+            .map(|(identifier, (hiding, binding))| hiding + binding * binding_factors[identifier])
+            .sum();
+
+        Ok(Self {
+            challenge: challenge(&group_commitment, &group_public, message),
+            commitments: decoded,
+            binding_factors,
+            group_commitment,
+        })
+    }
+
+    // λ_i = Π_{j≠i} j / (j - i), over the participants actually signing
+    fn lagrange_coefficient(&self, identifier: u16) -> Scalar {
+        let x_i = Scalar::from(identifier);
+        let (numerator, denominator) = self
+            .commitments
+            .keys()
+            .filter(|other| **other != identifier)
+            .fold((Scalar::ONE, Scalar::ONE), |(num, den), other| {
+                let x_j = Scalar::from(*other);
+                (num * x_j, den * (x_j - x_i))
+            });
+        numerator * denominator.invert()
+    }
+}
+
+// Same challenge as Ed25519, so the joint signature checks out with any Ed25519 verifier
+fn challenge(r: &EdwardsPoint, public: &EdwardsPoint, message: &[u8]) -> Scalar {
+    hash_to_scalar(&[r.compress().as_bytes(), public.compress().as_bytes(), message])
+}
+
+fn hash_to_scalar(parts: &[&[u8]]) -> Scalar {
+    let mut hasher = Sha512::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    Scalar::from_bytes_mod_order_wide(&hasher.finalize().into())
+}
+
+fn random_scalar() -> Scalar {
+    let mut wide = [0u8; 64];
+    rand::Rng::fill(&mut rand::thread_rng(), &mut wide[..]);
+    Scalar::from_bytes_mod_order_wide(&wide)
+}
+
+fn scalar(bytes: &[u8; 32]) -> Result<Scalar> {
+    Option::from(Scalar::from_canonical_bytes(*bytes))
+        .ok_or_else(|| ValidatorError::SignatureVerification("Not a canonical scalar".to_string()))
+}
+
+fn point(bytes: &[u8; 32]) -> Result<EdwardsPoint> {
+    CompressedEdwardsY(*bytes)
+        .decompress()
+        .ok_or_else(|| ValidatorError::SignatureVerification(format!("{} is not a curve point", hex::encode(bytes))))
+}
+
+// Runs both FROST rounds over the consensus message bus. Every signer in the
+// set broadcasts its commitment, then its signature share, and aggregates once
+// it has everyone's; the signing set is agreed on beforehand.
+pub struct FrostSigner {
+    validator_id: usize,
+    network: NetworkState,
+    transport_key: Arc<TransportKey>,
+    share: FrostKeyShare,
+    round_timeout: Duration,
+}
+
+impl FrostSigner {
+    pub fn new(validator_id: usize, network: NetworkState, transport_key: Arc<TransportKey>, share: FrostKeyShare) -> Self {
+        Self {
+            validator_id,
+            network,
+            transport_key,
+            share,
+            round_timeout: Duration::from_secs(30),
+        }
+    }
+
+    pub fn with_round_timeout(mut self, round_timeout: Duration) -> Self {
+        self.round_timeout = round_timeout;
+        self
+    }
+
+    pub fn threshold(&self) -> usize {
+        self.share.threshold
+    }
+
+    // The committee's approval of a payout: a Schnorr signature over the
+    // transaction prefix under the joint spend key. This is not a Monero
+    // spend authorization; monerod needs a CLSAG per input, which no
+    // threshold signing here produces.
+    pub async fn approve_payout(&self, tx: &UnsignedTransaction, attempt: u32, signers: &[u16]) -> Result<Signature> {
+        self.sign(&tx.prefix_hash(), attempt, signers).await
+    }
+
+    // Each attempt at a message is its own session, so a retry never picks up
+    // commitments or shares left over from an earlier one
+    pub async fn sign(&self, message: &[u8], attempt: u32, signers: &[u16]) -> Result<Signature> {
+        let session = hex::encode(Sha512::digest([CONTEXT, b"session", &attempt.to_le_bytes(), message].concat()));
+        let result = self.run_session(&session, message, signers).await;
+        // The session is over either way; its messages are of no further use
+        self.network.messages.write().await.retain(|m| m.data["session"] != session.as_str());
+        result
+    }
+
+    async fn run_session(&self, session: &str, message: &[u8], signers: &[u16]) -> Result<Signature> {
+
+        let (nonces, own_commitment) = commit(&self.share);
+        self.broadcast(FROST_COMMIT, serde_json::json!({
+            "session": session,
+            "commitment": own_commitment,
+        })).await?;
+        let commitments: Vec<SigningCommitment> = self.collect(FROST_COMMIT, "commitment", session, signers, own_commitment).await?;
+
+        let own_share = sign(&self.share, nonces, message, &commitments)?;
+        self.broadcast(FROST_SHARE, serde_json::json!({
+            "session": session,
+            "share": own_share,
+        })).await?;
+        let shares: Vec<SignatureShare> = self.collect(FROST_SHARE, "share", session, signers, own_share).await?;
+
+        let signature = aggregate(&self.share, message, &commitments, &shares)?;
+        if !verify(&self.share.group_public_key, message, &signature) {
+            return Err(ValidatorError::SignatureVerification("Aggregated FROST signature does not verify".to_string()));
+        }
+        info!("FROST signature for session {} produced by {:?}", &session[..16], signers);
+        Ok(signature)
+    }
+
+    async fn broadcast(&self, msg_type: &str, data: serde_json::Value) -> Result<()> {
+        let mut message = ConsensusMessage {
+            validator_id: self.validator_id,
+            msg_type: msg_type.to_string(),
+            data,
+            signature: vec![],
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_secs(),
+            hops: 0,
+        };
+        self.transport_key.sign_message(&mut message)?;
+        self.network.broadcast_message(message).await
+    }
+
+    // Waits for the item of `msg_type` from every other signer in the set.
+    // Validator ids map to identifiers as id + 1.
+    async fn collect<T>(&self, msg_type: &str, field: &str, session: &str, signers: &[u16], own: T) -> Result<Vec<T>>
+    where
+        T: for<'de> Deserialize<'de> + Identified,
+    {
+        let deadline = tokio::time::Instant::now() + self.round_timeout;
+        loop {
+            let mut collected = BTreeMap::new();
+            for message in self.network.messages.read().await.iter() {
+                if message.msg_type != msg_type || message.data["session"] != session {
+                    continue;
+                }
+                let Ok(item) = serde_json::from_value::<T>(message.data[field].clone()) else {
+                    continue;
+                };
+                // The transport signature binds the message to its sender
+                if item.identifier() as usize == message.validator_id + 1 && signers.contains(&item.identifier()) {
+                    collected.insert(item.identifier(), item);
+                }
+            }
+
+            if collected.len() + 1 >= signers.len() {
+                collected.insert(own.identifier(), own);
+                return Ok(collected.into_values().collect());
+            }
+            if tokio::time::Instant::now() >= deadline {
+                warn!("FROST {} for session {} timed out with {} of {} signers", msg_type, &session[..16], collected.len() + 1, signers.len());
+                return Err(ValidatorError::QuorumNotReached {
+                    msg_type: msg_type.to_string(),
+                    need: signers.len(),
+                    have: collected.len() + 1,
+                });
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+}
+
+trait Identified {
+    fn identifier(&self) -> u16;
+}
+
+impl Identified for SigningCommitment {
+    fn identifier(&self) -> u16 {
+        self.identifier
+    }
+}
+
+impl Identified for SignatureShare {
+    fn identifier(&self) -> u16 {
+        self.identifier
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::serve_network;
+
+    fn sign_with(shares: &[&FrostKeyShare], message: &[u8]) -> Result<Signature> {
+        let rounds: Vec<_> = shares.iter().map(|share| commit(share)).collect();
+        let commitments: Vec<SigningCommitment> = rounds.iter().map(|(_, commitment)| *commitment).collect();
+
+        let mut signature_shares = vec![];
+        for (share, (nonces, _)) in shares.iter().zip(rounds) {
+            signature_shares.push(sign(share, nonces, message, &commitments)?);
+        }
+        aggregate(shares[0], message, &commitments, &signature_shares)
+    }
+
+    #[test]
+    fn test_threshold_signers_produce_signature_under_joint_key() {
+        let secret = random_scalar();
+        let shares = deal_shares(&secret, 4, 7).unwrap();
+        let joint_key = (&secret * ED25519_BASEPOINT_TABLE).compress().to_bytes();
+        let message = b"release 1.5 XMR";
+
+        // Any subset of `threshold` participants works
+        for subset in [[0, 1, 2, 3], [6, 4, 2, 0], [3, 4, 5, 6]] {
+            let signers: Vec<&FrostKeyShare> = subset.iter().map(|i| &shares[*i]).collect();
+            let signature = sign_with(&signers, message).unwrap();
+
+            assert_eq!(shares[0].group_public_key, joint_key);
+            assert!(verify(&joint_key, message, &signature));
+            assert!(!verify(&joint_key, b"release 150 XMR", &signature));
+        }
+
+        let too_few: Vec<&FrostKeyShare> = shares[..3].iter().collect();
+        assert!(matches!(sign_with(&too_few, message), Err(ValidatorError::InsufficientShares { have: 3, need: 4 })));
+    }
+
+    #[test]
+    fn test_bad_signature_share_is_attributed() {
+        let shares = deal_shares(&random_scalar(), 2, 3).unwrap();
+        let message = b"payout";
+
+        let rounds: Vec<_> = shares[..2].iter().map(commit).collect();
+        let commitments: Vec<SigningCommitment> = rounds.iter().map(|(_, commitment)| *commitment).collect();
+        let mut signature_shares: Vec<SignatureShare> = shares[..2]
+            .iter()
+            .zip(rounds)
+            .map(|(share, (nonces, _))| sign(share, nonces, message, &commitments).unwrap())
+            .collect();
+        signature_shares[1].z = (scalar(&signature_shares[1].z).unwrap() + Scalar::ONE).to_bytes();
+
+        let err = aggregate(&shares[0], message, &commitments, &signature_shares).unwrap_err();
+        assert!(err.to_string().contains("Invalid signature share from 2"), "{}", err);
+    }
+
+    #[test]
+    fn test_summed_dealings_sign_under_the_joint_key() {
+        // Every validator deals its own additive share; nobody adds the secrets up
+        let additive: Vec<Scalar> = (0..3).map(|_| random_scalar()).collect();
+        let dealt: Vec<Vec<FrostKeyShare>> = additive.iter().map(|secret| deal_shares(secret, 2, 3).unwrap()).collect();
+        let joint_key = additive.iter().map(|secret| secret * ED25519_BASEPOINT_TABLE).sum::<EdwardsPoint>().compress().to_bytes();
+
+        let received = |participant: usize| -> Vec<FrostKeyShare> { dealt.iter().map(|dealing| dealing[participant].clone()).collect() };
+        let shares: Vec<FrostKeyShare> = (0..3).map(|participant| combine_dealings(participant as u16 + 1, &received(participant)).unwrap()).collect();
+
+        assert!(shares.iter().all(|share| share.group_public_key == joint_key && share.verifying_shares == shares[0].verifying_shares));
+        for subset in [[0, 1], [1, 2], [2, 0]] {
+            let signature = sign_with(&[&shares[subset[0]], &shares[subset[1]]], b"payout").unwrap();
+            assert!(verify(&joint_key, b"payout", &signature));
+        }
+
+        // A dealing that is not a sharing of the dealer's key
+        let mut tampered = received(0);
+        tampered[1].verifying_shares.insert(3, (&Scalar::from(5u64) * ED25519_BASEPOINT_TABLE).compress().to_bytes());
+        assert!(matches!(combine_dealings(1, &tampered), Err(ValidatorError::KeyMaterial(_))));
+
+        // Or one meant for someone else
+        assert!(matches!(combine_dealings(1, &received(1)), Err(ValidatorError::KeyMaterial(_))));
+    }
+
+    #[tokio::test]
+    async fn test_signing_over_network() {
+        let shares = deal_shares(&random_scalar(), 2, 3).unwrap();
+
+        // Validators 0 and 2 sign while validator 1 stays out
+        let nodes: Vec<NetworkState> = (0..3).map(|id| NetworkState::new(id, 0)).collect();
+        let mut urls = vec![];
+        for node in &nodes {
+            urls.push(serve_network(node.clone()).await);
+        }
+        for (id, node) in nodes.iter().enumerate() {
+            for (peer, url) in urls.iter().enumerate().filter(|(peer, _)| *peer != id) {
+                node.add_peer(peer, url.clone()).await;
+            }
+        }
+
+        let signer = |id: usize| {
+            FrostSigner::new(id, nodes[id].clone(), Arc::new(TransportKey::generate()), shares[id].clone())
+                .with_round_timeout(Duration::from_secs(5))
+        };
+        let (first, second) = (signer(0), signer(2));
+        let message = b"spend bridge outputs";
+
+        let (a, b) = tokio::join!(first.sign(message, 0, &[1, 3]), second.sign(message, 0, &[1, 3]));
+        let (a, b) = (a.unwrap(), b.unwrap());
+
+        assert_eq!(a, b);
+        assert!(verify(&shares[0].group_public_key, message, &a));
+
+        // An attempt is a fresh session: the commitments from the first one
+        // are no help to it, and its own leftovers do not spoil the retry
+        let lone = signer(0).with_round_timeout(Duration::from_millis(500));
+        assert!(matches!(lone.sign(message, 1, &[1, 3]).await, Err(ValidatorError::QuorumNotReached { .. })));
+        let (a, b) = tokio::join!(first.sign(message, 2, &[1, 3]), second.sign(message, 2, &[1, 3]));
+        assert!(verify(&shares[0].group_public_key, message, &a.unwrap()));
+        assert!(verify(&shares[0].group_public_key, message, &b.unwrap()));
+    }
+}
@@ -0,0 +1,325 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use curve25519_dalek::edwards::CompressedEdwardsY;
+use curve25519_dalek::scalar::Scalar;
+use serde::Deserialize;
+use tracing::debug;
+
+use crate::config::RpcOperation;
+use crate::decoy::OutputSource;
+use crate::error::{Result, ValidatorError};
+use crate::payout::{amount_commitment, received_mask, OwnedOutput, PayoutFunding, SpendableInput};
+use crate::validation::MoneroValidator;
+use crate::wallet_rpc::{MoneroWalletRpc, WalletOutput};
+
+const TX_EXTRA_TAG_PUBKEY: u8 = 0x01;
+const TX_EXTRA_NONCE: u8 = 0x02;
+const TX_EXTRA_TAG_ADDITIONAL_PUBKEYS: u8 = 0x04;
+
+// Payout inputs from the view-only bridge wallet. The wallet lists its unspent
+// outputs; each one's commitment mask is recovered with the private view key
+// from the transaction that paid it and checked against the chain's commitment.
+// Decoys are left to the caller's ring selection.
+pub struct WalletFunding {
+    wallet: Arc<MoneroWalletRpc>,
+    chain: Arc<MoneroValidator>,
+    view_secret: Scalar,
+}
+
+impl WalletFunding {
+    pub fn new(wallet: Arc<MoneroWalletRpc>, chain: Arc<MoneroValidator>, view_key: &str) -> Result<Self> {
+        let view_key: [u8; 32] = hex::decode(view_key)?
+            .try_into()
+            .map_err(|_| ValidatorError::Config("monero.wallet_rpc.view_key must be 32 bytes".to_string()))?;
+        let view_secret = Option::from(Scalar::from_canonical_bytes(view_key))
+            .ok_or_else(|| ValidatorError::Config("monero.wallet_rpc.view_key is not a canonical scalar".to_string()))?;
+        Ok(Self { wallet, chain, view_secret })
+    }
+
+    async fn owned_output(&self, output: &WalletOutput) -> Result<OwnedOutput> {
+        let public_key = key_bytes(&output.pubkey)?;
+        let on_chain = self.chain.ring_members(&[output.global_index]).await?
+            .into_iter()
+            .find(|member| member.public_key == public_key)
+            .ok_or_else(|| ValidatorError::Payout(format!("Output {} is not spendable on chain yet", output.global_index)))?;
+
+        let funding = self.funding_transaction(&output.tx_hash).await?;
+        let index = funding.vout
+            .iter()
+            .position(|vout| vout.target.key() == Some(output.pubkey.as_str()))
+            .ok_or_else(|| ValidatorError::Payout(format!("Transaction {} has no output {}", output.tx_hash, output.pubkey)))?;
+
+        // Subaddress payments carry one extra key per output next to the main one
+        let (main, additional) = tx_public_keys(&funding.extra);
+        for tx_public in main.iter().chain(additional.get(index)) {
+            let Some(tx_public) = CompressedEdwardsY(*tx_public).decompress() else {
+                continue;
+            };
+            let mask = received_mask(&self.view_secret, &tx_public, index as u64);
+            if amount_commitment(output.amount, &mask) == on_chain.commitment {
+                return Ok(OwnedOutput {
+                    global_index: output.global_index,
+                    public_key,
+                    amount: output.amount,
+                    mask: mask.to_bytes(),
+                });
+            }
+        }
+        Err(ValidatorError::Payout(format!(
+            "Cannot recover the mask of output {}; is view_key the bridge wallet's?", output.global_index
+        )))
+    }
+
+    async fn funding_transaction(&self, tx_hash: &str) -> Result<FundingTransaction> {
+        let response = self.chain.post_to(
+            RpcOperation::GetTransactions,
+            &self.chain.daemon_endpoint("get_transactions")?,
+            &serde_json::json!({ "txs_hashes": [tx_hash], "decode_as_json": true }),
+        ).await?;
+
+        let as_json = response["txs"][0]["as_json"]
+            .as_str()
+            .ok_or_else(|| ValidatorError::MoneroRpc(format!("monerod does not know transaction {}", tx_hash)))?;
+        serde_json::from_str(as_json)
+            .map_err(|e| ValidatorError::MoneroRpc(format!("Unexpected transaction {}: {}", tx_hash, e)))
+    }
+}
+
+#[async_trait]
+impl PayoutFunding for WalletFunding {
+    async fn spendable_inputs(&self, amount: u64, _ring_size: usize) -> Result<Vec<SpendableInput>> {
+        let mut outputs: Vec<WalletOutput> = self.wallet.incoming_transfers().await?
+            .into_iter()
+            .filter(|output| output.unlocked)
+            .collect();
+        // Largest first keeps the number of inputs, and so the fee, down
+        outputs.sort_by_key(|output| std::cmp::Reverse(output.amount));
+
+        let mut selected = vec![];
+        let mut total = 0u64;
+        for output in outputs {
+            if total >= amount {
+                break;
+            }
+            total += output.amount;
+            selected.push(output);
+        }
+        if total < amount {
+            return Err(ValidatorError::Payout(format!(
+                "Bridge wallet has {} unlocked piconero, {} needed", total, amount
+            )));
+        }
+
+        let mut inputs = Vec::with_capacity(selected.len());
+        for output in &selected {
+            inputs.push(SpendableInput { output: self.owned_output(output).await?, decoys: vec![] });
+        }
+        debug!("Funding a {} piconero payout from {} bridge outputs", amount, inputs.len());
+        Ok(inputs)
+    }
+}
+
+#[derive(Deserialize)]
+struct FundingTransaction {
+    vout: Vec<FundingOutput>,
+    extra: Vec<u8>,
+}
+
+#[derive(Deserialize)]
+struct FundingOutput {
+    target: OutputTarget,
+}
+
+// Outputs are `key` before view tags and `tagged_key` after
+#[derive(Deserialize)]
+struct OutputTarget {
+    #[serde(default)]
+    key: Option<String>,
+    #[serde(default)]
+    tagged_key: Option<TaggedKey>,
+}
+
+#[derive(Deserialize)]
+struct TaggedKey {
+    key: String,
+}
+
+impl OutputTarget {
+    fn key(&self) -> Option<&str> {
+        self.tagged_key.as_ref().map(|tagged| tagged.key.as_str()).or(self.key.as_deref())
+    }
+}
+
+// Main and per-output transaction public keys from tx_extra. Parsing stops at
+// padding or an unknown field, as wallet2 does.
+fn tx_public_keys(extra: &[u8]) -> (Vec<[u8; 32]>, Vec<[u8; 32]>) {
+    let (mut main, mut additional) = (vec![], vec![]);
+    let mut rest = extra;
+    while let Some((&tag, tail)) = rest.split_first() {
+        rest = match tag {
+            TX_EXTRA_TAG_PUBKEY => match keys(tail, 1) {
+                Some((mut found, tail)) => {
+                    main.append(&mut found);
+                    tail
+                }
+                None => break,
+            },
+            TX_EXTRA_TAG_ADDITIONAL_PUBKEYS => match read_varint(tail).and_then(|(count, tail)| keys(tail, count as usize)) {
+                Some((found, tail)) => {
+                    additional = found;
+                    tail
+                }
+                None => break,
+            },
+            TX_EXTRA_NONCE => match read_varint(tail) {
+                Some((len, tail)) if tail.len() >= len as usize => &tail[len as usize..],
+                _ => break,
+            },
+            // Padding, or a field that cannot be skipped
+            _ => break,
+        };
+    }
+    (main, additional)
+}
+
+fn keys(bytes: &[u8], count: usize) -> Option<(Vec<[u8; 32]>, &[u8])> {
+    let len = count.checked_mul(32).filter(|len| *len <= bytes.len())?;
+    let found = bytes[..len].chunks_exact(32).map(|chunk| chunk.try_into().unwrap()).collect();
+    Some((found, &bytes[len..]))
+}
+
+fn read_varint(bytes: &[u8]) -> Option<(u64, &[u8])> {
+    let mut value = 0u64;
+    for (i, byte) in bytes.iter().enumerate().take(10) {
+        value |= ((byte & 0x7f) as u64) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Some((value, &bytes[i + 1..]));
+        }
+    }
+    None
+}
+
+fn key_bytes(value: &str) -> Result<[u8; 32]> {
+    hex::decode(value)?
+        .try_into()
+        .map_err(|_| ValidatorError::WalletRpc(format!("{} is not a 32 byte key", value)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::WalletRpcConfig;
+    use crate::payout::{PayoutBuilder, RingMember};
+    use crate::subaddress::encode_address;
+    use crate::test_support::serve;
+    use axum::routing::post;
+    use axum::{Json, Router};
+    use curve25519_dalek::constants::ED25519_BASEPOINT_TABLE;
+    use serde_json::Value;
+
+    const STAGENET: u8 = 24;
+    const FUNDING_TX: &str = "cd";
+    const GLOBAL_INDEX: u64 = 77_000;
+
+    // A transaction paying `amount` to the bridge wallet, built as any sender would
+    fn funding_transaction(bridge: &str, amount: u64) -> crate::payout::UnsignedTransaction {
+        let point = |seed: u64| (&Scalar::from(seed) * ED25519_BASEPOINT_TABLE).compress().to_bytes();
+        let input = SpendableInput {
+            output: OwnedOutput { global_index: 500, public_key: point(500), amount: amount * 2, mask: Scalar::from(9u64).to_bytes() },
+            decoys: (0..15).map(|i| RingMember { global_index: 1_000 + i, public_key: point(3_000 + i), commitment: point(4_000 + i) }).collect(),
+        };
+        // Change goes back to the bridge too, so every output is the bridge's
+        PayoutBuilder::new(bridge).build(bridge, amount, &[input]).unwrap().0
+    }
+
+    fn wallet(seed: u64) -> (Scalar, curve25519_dalek::EdwardsPoint, String) {
+        let view_secret = Scalar::from(seed);
+        let spend_public = &Scalar::from(seed + 1) * ED25519_BASEPOINT_TABLE;
+        let address = encode_address(STAGENET, &spend_public, &(&view_secret * ED25519_BASEPOINT_TABLE));
+        (view_secret, spend_public, address)
+    }
+
+    // monerod serving the funding transaction and its output, and a wallet listing that output
+    async fn funded_bridge(amount: u64) -> (WalletFunding, [u8; 32]) {
+        let (view_secret, _, bridge) = wallet(13);
+        let tx = funding_transaction(&bridge, amount);
+        let output = &tx.outputs[0];
+
+        let as_json = serde_json::json!({
+            "vout": tx.outputs.iter().map(|o| serde_json::json!({
+                "amount": 0,
+                "target": { "tagged_key": { "key": hex::encode(o.public_key), "view_tag": format!("{:02x}", o.view_tag) } },
+            })).collect::<Vec<_>>(),
+            "extra": tx.extra,
+        });
+        let (pubkey, commitment) = (hex::encode(output.public_key), hex::encode(output.commitment));
+        let monerod = serve(Router::new()
+            .route("/get_transactions", post(move |Json(request): Json<Value>| {
+                let as_json = as_json.to_string();
+                async move {
+                    assert_eq!(request["txs_hashes"][0], FUNDING_TX.repeat(32));
+                    Json(serde_json::json!({ "status": "OK", "txs": [{ "as_json": as_json }] }))
+                }
+            }))
+            .route("/get_outs", post(move || {
+                let (key, mask) = (pubkey.clone(), commitment.clone());
+                async move { Json(serde_json::json!({ "status": "OK", "outs": [{ "key": key, "mask": mask, "unlocked": true }] })) }
+            }))).await;
+
+        let listed = hex::encode(output.public_key);
+        let wallet_rpc = serve(Router::new().route("/json_rpc", post(move |Json(request): Json<Value>| {
+            let listed = listed.clone();
+            async move {
+                assert_eq!(request["method"], "incoming_transfers");
+                Json(serde_json::json!({ "id": "0", "jsonrpc": "2.0", "result": { "transfers": [
+                    { "amount": amount, "global_index": GLOBAL_INDEX, "tx_hash": FUNDING_TX.repeat(32), "pubkey": listed, "unlocked": true },
+                    { "amount": amount * 10, "global_index": GLOBAL_INDEX + 1, "tx_hash": "ef".repeat(32), "pubkey": "00".repeat(32), "unlocked": false },
+                ] } }))
+            }
+        }))).await;
+
+        let config = crate::test_support::config(&monerod, "http://127.0.0.1:1");
+        let wallet_rpc = Arc::new(MoneroWalletRpc::new(WalletRpcConfig {
+            url: format!("{}/json_rpc", wallet_rpc),
+            account: 0,
+            rpc_username: None,
+            rpc_password: None,
+            request_timeout_ms: 5_000,
+            view_key: None,
+        }));
+        let funding = WalletFunding::new(wallet_rpc, Arc::new(MoneroValidator::new(config.monero).unwrap()), &hex::encode(view_secret.as_bytes())).unwrap();
+        (funding, output.commitment)
+    }
+
+    #[tokio::test]
+    async fn test_bridge_outputs_fund_a_payout_with_their_masks() {
+        let amount = 2_000_000_000_000;
+        let (funding, commitment) = funded_bridge(amount).await;
+
+        let inputs = funding.spendable_inputs(amount - 100_000_000, 16).await.unwrap();
+        assert_eq!(inputs.len(), 1);
+        let input = &inputs[0];
+        assert_eq!((input.output.global_index, input.output.amount), (GLOBAL_INDEX, amount));
+        assert!(input.decoys.is_empty());
+        // The recovered mask opens the on-chain commitment
+        assert_eq!(amount_commitment(amount, &Scalar::from_canonical_bytes(input.output.mask).unwrap()), commitment);
+
+        // Locked outputs do not count towards what the wallet can pay
+        assert!(matches!(funding.spendable_inputs(amount + 1, 16).await, Err(ValidatorError::Payout(_))));
+    }
+
+    #[test]
+    fn test_extra_keys_are_parsed_past_a_nonce() {
+        let mut extra = vec![TX_EXTRA_NONCE, 3, 9, 9, 9, TX_EXTRA_TAG_PUBKEY];
+        extra.extend([1u8; 32]);
+        extra.extend([TX_EXTRA_TAG_ADDITIONAL_PUBKEYS, 2]);
+        extra.extend([2u8; 32]);
+        extra.extend([3u8; 32]);
+        extra.extend([0, 0, 0]);
+
+        assert_eq!(tx_public_keys(&extra), (vec![[1u8; 32]], vec![[2u8; 32], [3u8; 32]]));
+        // A truncated field ends parsing instead of reading past the end
+        assert_eq!(tx_public_keys(&extra[..20]), (vec![], vec![]));
+    }
+}
@@ -0,0 +1,199 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashSet, VecDeque};
+use tokio::sync::Mutex;
+
+use crate::config::LivenessConfig;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ValidatorLiveness {
+    pub validator_id: usize,
+    pub heartbeats: usize,
+    pub rounds_joined: usize,
+    pub rounds_missed: usize,
+    pub delinquent: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LivenessReport {
+    pub window_secs: u64,
+    pub min_participation: f64,
+    pub validators: Vec<ValidatorLiveness>,
+}
+
+struct CompletedRound {
+    operation_hash: String,
+    timestamp: u64,
+    signers: BTreeSet<usize>,
+}
+
+#[derive(Default)]
+struct Observations {
+    heartbeats: VecDeque<(usize, u64)>,
+    rounds: VecDeque<CompletedRound>,
+    // Operation hashes of `rounds`, so a relayed completion is not counted twice
+    round_hashes: HashSet<String>,
+}
+
+impl Observations {
+    // Drops what has left the window, oldest first. Observations arrive
+    // roughly in time order, so this keeps memory bounded between reports.
+    fn prune_front(&mut self, cutoff: u64) {
+        while self.heartbeats.front().is_some_and(|(_, timestamp)| *timestamp < cutoff) {
+            self.heartbeats.pop_front();
+        }
+        while self.rounds.front().is_some_and(|round| round.timestamp < cutoff) {
+            if let Some(round) = self.rounds.pop_front() {
+                self.round_hashes.remove(&round.operation_hash);
+            }
+        }
+    }
+}
+
+// Per-validator participation over a rolling window: heartbeats seen and
+// signing rounds whose signature set they were part of. Delinquency is the
+// input to any future slashing, so it is only ever reported, never acted on.
+pub struct LivenessTracker {
+    config: LivenessConfig,
+    observations: Mutex<Observations>,
+}
+
+impl LivenessTracker {
+    pub fn new(config: LivenessConfig) -> Self {
+        Self { config, observations: Mutex::new(Observations::default()) }
+    }
+
+    pub async fn record_heartbeat(&self, validator_id: usize, timestamp: u64) {
+        let mut observations = self.observations.lock().await;
+        observations.prune_front(timestamp.saturating_sub(self.config.window_secs));
+        observations.heartbeats.push_back((validator_id, timestamp));
+    }
+
+    // A round is counted once, however many times its completion is relayed
+    pub async fn record_round(&self, operation_hash: &str, signers: impl IntoIterator<Item = usize>, timestamp: u64) {
+        let mut observations = self.observations.lock().await;
+        observations.prune_front(timestamp.saturating_sub(self.config.window_secs));
+        if !observations.round_hashes.insert(operation_hash.to_string()) {
+            return;
+        }
+        observations.rounds.push_back(CompletedRound {
+            operation_hash: operation_hash.to_string(),
+            timestamp,
+            signers: signers.into_iter().collect(),
+        });
+    }
+
+    // Covers `members` plus anyone else observed in the window
+    pub async fn report(&self, members: &[usize], now: u64) -> LivenessReport {
+        let cutoff = now.saturating_sub(self.config.window_secs);
+        let mut observations = self.observations.lock().await;
+        observations.heartbeats.retain(|(_, timestamp)| *timestamp >= cutoff);
+        observations.rounds.retain(|round| round.timestamp >= cutoff);
+        let Observations { rounds, round_hashes, .. } = &mut *observations;
+        round_hashes.retain(|hash| rounds.iter().any(|round| &round.operation_hash == hash));
+
+        let mut validators: BTreeMap<usize, ValidatorLiveness> = BTreeMap::new();
+        let observed = observations.heartbeats.iter().map(|(id, _)| *id)
+            .chain(observations.rounds.iter().flat_map(|round| round.signers.iter().copied()));
+        for validator_id in members.iter().copied().chain(observed) {
+            validators.entry(validator_id).or_insert(ValidatorLiveness {
+                validator_id,
+                heartbeats: 0,
+                rounds_joined: 0,
+                rounds_missed: 0,
+                delinquent: false,
+            });
+        }
+
+        for (validator_id, _) in &observations.heartbeats {
+            validators.get_mut(validator_id).unwrap().heartbeats += 1;
+        }
+        for round in &observations.rounds {
+            for (validator_id, liveness) in validators.iter_mut() {
+                if round.signers.contains(validator_id) {
+                    liveness.rounds_joined += 1;
+                } else {
+                    liveness.rounds_missed += 1;
+                }
+            }
+        }
+
+        for liveness in validators.values_mut() {
+            let rounds = liveness.rounds_joined + liveness.rounds_missed;
+            let participation = if rounds == 0 { 1.0 } else { liveness.rounds_joined as f64 / rounds as f64 };
+            liveness.delinquent = liveness.heartbeats == 0 || participation < self.config.min_participation;
+        }
+
+        LivenessReport {
+            window_secs: self.config.window_secs,
+            min_participation: self.config.min_participation,
+            validators: validators.into_values().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::membership::Committee;
+    use crate::network::NetworkState;
+    use crate::test_support::serve_network;
+
+    #[tokio::test]
+    async fn test_validator_missing_rounds_is_flagged() {
+        let mut config: Config = toml::from_str(include_str!("../config.toml")).unwrap();
+        let generator = crate::tss::TSSKeyGenerator::new(config.mpc.threshold, config.mpc.total_parties);
+        config.mpc.share_public_keys = (0..config.mpc.total_parties)
+            .map(|id| hex::encode(generator.generate_keys(id).unwrap().0.eth_public_key))
+            .collect();
+        let state = NetworkState::new(0, 0)
+            .with_committee(Committee::from_mpc_config(&config.mpc).unwrap())
+            .with_liveness(LivenessConfig { window_secs: 3_600, min_participation: 0.5 });
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        // Validator 2 keeps sending heartbeats but joins only one round in four;
+        // validator 3 joined rounds long ago and has gone quiet since
+        for id in 0..3 {
+            state.liveness.record_heartbeat(id, now - 60).await;
+        }
+        state.liveness.record_heartbeat(3, now - 7_200).await;
+        state.liveness.record_round("old", [0, 1, 3], now - 7_200).await;
+        for round in 0..4 {
+            let signers = if round == 0 { vec![0, 1, 2] } else { vec![0, 1] };
+            state.liveness.record_round(&format!("op-{}", round), signers, now - 30).await;
+        }
+        state.liveness.record_round("op-1", [0, 1, 2], now - 30).await;
+
+        let base_url = serve_network(state).await;
+
+        let report: LivenessReport = reqwest::get(format!("{}/liveness", base_url))
+            .await.unwrap()
+            .json().await.unwrap();
+
+        let by_id: BTreeMap<usize, ValidatorLiveness> = report.validators.into_iter().map(|v| (v.validator_id, v)).collect();
+        assert_eq!(by_id[&0], ValidatorLiveness { validator_id: 0, heartbeats: 1, rounds_joined: 4, rounds_missed: 0, delinquent: false });
+        assert!(!by_id[&1].delinquent);
+        assert_eq!((by_id[&2].rounds_joined, by_id[&2].rounds_missed), (1, 3));
+        assert!(by_id[&2].delinquent);
+        assert_eq!(by_id[&3].heartbeats, 0);
+        assert!(by_id[&3].delinquent);
+        assert_eq!(by_id.len(), 7);
+    }
+
+    #[tokio::test]
+    async fn test_history_is_pruned_when_recording() {
+        let liveness = LivenessTracker::new(LivenessConfig { window_secs: 100, min_participation: 0.5 });
+        for timestamp in 0..1_000 {
+            liveness.record_heartbeat(0, timestamp).await;
+            liveness.record_round(&format!("op-{}", timestamp), [0], timestamp).await;
+        }
+
+        let observations = liveness.observations.lock().await;
+        assert_eq!(observations.heartbeats.len(), 101);
+        assert_eq!(observations.rounds.len(), 101);
+        assert_eq!(observations.round_hashes.len(), 101);
+    }
+}
@@ -9,6 +9,30 @@ mod validation;
 mod network;
 mod tss;
 mod combiner;
+mod membership;
+mod transport;
+mod rate_limit;
+mod event_cursor;
+mod eth_events;
+mod subaddress;
+mod digest_auth;
+mod consensus;
+mod redact;
+mod reservation;
+mod quorum;
+mod payout;
+mod funding;
+mod frost;
+mod decoy;
+mod liveness;
+mod canonical;
+mod share_backup;
+mod party_registry;
+mod wallet_rpc;
+mod broadcast;
+#[cfg(test)]
+mod test_support;
+mod error;
 
 use anyhow::Result;
 use tracing::{info, error};
@@ -22,39 +46,82 @@ struct Args {
     #[arg(long)]
     generate_keys: bool,
     
+    #[arg(long)]
+    force: bool,
+    
+    #[arg(long, env = "WXMR_RECOVERY_MNEMONIC")]
+    recover_from_mnemonic: Option<String>,
+    
+    // Fetch this validator's key file from the share backup store
+    #[arg(long)]
+    restore: bool,
+    
+    // Backup version to restore; the latest if omitted
+    #[arg(long, requires = "restore")]
+    backup_version: Option<u64>,
+    
+    // Release this validator id's party slot in the registry of the node given by --index
+    #[arg(long)]
+    vacate_party: Option<usize>,
+    
     #[arg(long)]
     combine_keys: bool,
     
     #[arg(long)]
     show_bridge: bool,
     
+    #[arg(long, value_enum, default_value_t = combiner::OutputFormat::Text)]
+    output: combiner::OutputFormat,
+    
+    #[arg(long)]
+    output_path: Option<String>,
+    
     #[arg(long)]
     index: Option<usize>,
     
     #[arg(long)]
     port: Option<u16>,
+    
+    // Print tx keys and key shares unmasked; local debugging only
+    #[arg(long)]
+    log_secrets: bool,
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    tracing_subscriber::fmt::init();
+    // Logs go to stderr so --output json can be piped
+    tracing_subscriber::fmt().with_writer(std::io::stderr).init();
     
     let args = Args::parse();
     
-    if args.generate_keys {
+    if args.log_secrets {
+        redact::set_log_secrets(true);
+        tracing::warn!("--log-secrets is set: secret material will appear in logs");
+    }
+    
+    if let Some(phrase) = args.recover_from_mnemonic.as_deref() {
+        info!("Recovering validator keys from mnemonic...");
+        keygen::recover_keys(args.config.to_string_lossy().into_owned(), args.index.unwrap_or(0), phrase, args.force).await?;
+    } else if args.restore {
+        info!("Restoring validator keys from share backup...");
+        keygen::restore_keys(args.config.to_string_lossy().into_owned(), args.index.unwrap_or(0), args.backup_version, args.force).await?;
+    } else if let Some(validator_id) = args.vacate_party {
+        info!("Vacating party slot of validator {}...", validator_id);
+        keygen::vacate_party(args.config.to_string_lossy().into_owned(), args.index.unwrap_or(0), validator_id).await?;
+    } else if args.generate_keys {
         info!("Starting distributed key generation...");
-        keygen::start_keygen(args.config.to_string_lossy().into_owned(), args.index.unwrap_or(0)).await?;
+        keygen::start_keygen(args.config.to_string_lossy().into_owned(), args.index.unwrap_or(0), args.force).await?;
     } else if args.combine_keys {
         info!("Combining validator TSS keys...");
         combiner::KeyCombiner::combine_validator_keys(&args.config.to_string_lossy().into_owned()).await?;
     } else if args.show_bridge {
         info!("Displaying bridge wallet information...");
-        combiner::KeyCombiner::print_bridge_info(&args.config.to_string_lossy().into_owned()).await?;
-    } else if args.index.is_some() {
+        combiner::KeyCombiner::print_bridge_info(&args.config.to_string_lossy().into_owned(), args.output, args.output_path.as_deref()).await?;
+    } else if let Some(index) = args.index {
         info!("Starting validator node...");
-        validator::start_validator(args.config.to_string_lossy().into_owned(), args.port.unwrap_or(8000), args.index.unwrap()).await?;
+        validator::start_validator(args.config.to_string_lossy().into_owned(), args.port.unwrap_or(8000), index).await?;
     } else {
-        error!("Must provide --generate-keys, --combine-keys, --show-bridge, or --index <validator_id>");
+        error!("Must provide --generate-keys, --recover-from-mnemonic, --restore, --vacate-party, --combine-keys, --show-bridge, or --index <validator_id>");
     }
     
     Ok(())
@@ -0,0 +1,282 @@
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use crate::error::{Result, ValidatorError};
+use k256::ecdsa::{Signature, SigningKey, VerifyingKey};
+use k256::ecdsa::signature::{Signer, Verifier};
+
+use crate::config::MPCConfig;
+use crate::network::ConsensusMessage;
+use crate::transport::{self, TransportKey};
+use crate::tss::{TSSKeyGenerator, TSSKeyShare};
+
+pub const HEARTBEAT_INTERVAL_SECS: u64 = 30;
+
+// Heartbeats from the previous or next epoch are still accepted to tolerate clock skew
+const EPOCH_TOLERANCE: u64 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MembershipAttestation {
+    pub validator_id: usize,
+    pub public_key: String,
+    pub eth_address: String,
+    pub transport_public_key: String,
+    // Share-key signature certifying the transport key for this validator
+    pub transport_binding: String,
+    pub epoch: u64,
+}
+
+fn transport_binding_message(validator_id: usize, transport_public_key: &str) -> Vec<u8> {
+    let mut message = b"wxmr_transport_binding_".to_vec();
+    message.extend_from_slice(&(validator_id as u64).to_be_bytes());
+    message.extend_from_slice(transport_public_key.as_bytes());
+    message
+}
+
+pub fn epoch_for(timestamp: u64) -> u64 {
+    timestamp / HEARTBEAT_INTERVAL_SECS
+}
+
+pub fn build_heartbeat(share: &TSSKeyShare, transport_key: &TransportKey, timestamp: u64) -> Result<ConsensusMessage> {
+    let transport_public_key = hex::encode(transport_key.public_key());
+    
+    let share_key = SigningKey::from_slice(&share.eth_private_share)?;
+    let binding: Signature = share_key.sign(&transport_binding_message(share.validator_id, &transport_public_key));
+
+    let attestation = MembershipAttestation {
+        validator_id: share.validator_id,
+        public_key: hex::encode(&share.eth_public_key),
+        eth_address: TSSKeyGenerator::derive_eth_address(&share.eth_public_key),
+        transport_public_key,
+        transport_binding: hex::encode(binding.to_bytes()),
+        epoch: epoch_for(timestamp),
+    };
+
+    let mut message = ConsensusMessage {
+        validator_id: share.validator_id,
+        msg_type: "HEARTBEAT".to_string(),
+        data: serde_json::to_value(&attestation)?,
+        signature: vec![],
+        timestamp,
+        hops: 0,
+    };
+    transport_key.sign_message(&mut message)?;
+
+    Ok(message)
+}
+
+#[derive(Debug, Clone)]
+pub struct Committee {
+    members: HashMap<usize, Vec<u8>>,
+}
+
+impl Committee {
+    // Members are the share public keys published after keygen. Shares are
+    // derived from each validator's own mnemonic, so nothing public can stand in.
+    pub fn from_mpc_config(mpc: &MPCConfig) -> Result<Self> {
+        if mpc.share_public_keys.len() != mpc.total_parties {
+            return Err(ValidatorError::Config(format!(
+                "mpc.share_public_keys must list {} share public keys, found {}",
+                mpc.total_parties,
+                mpc.share_public_keys.len()
+            )));
+        }
+
+        let mut members = HashMap::new();
+        for (validator_id, key) in mpc.share_public_keys.iter().enumerate() {
+            members.insert(validator_id, hex::decode(key.trim_start_matches("0x"))?);
+        }
+
+        Ok(Self { members })
+    }
+
+    // The joint Ethereum key every share contributes to, which combined
+    // signatures are checked against
+    pub fn joint_public_key(&self) -> Result<Vec<u8>> {
+        TSSKeyGenerator::combine_eth_public_keys(&self.members.values().collect::<Vec<_>>())
+    }
+
+    pub fn members(&self) -> Vec<usize> {
+        let mut members: Vec<usize> = self.members.keys().copied().collect();
+        members.sort_unstable();
+        members
+    }
+
+    pub fn verify_heartbeat(&self, message: &ConsensusMessage, now: u64) -> Result<MembershipAttestation> {
+        let attestation: MembershipAttestation = serde_json::from_value(message.data.clone())?;
+
+        if attestation.validator_id != message.validator_id {
+            return Err(ValidatorError::SignatureVerification(format!(
+                "Heartbeat sender {} does not match attested validator {}",
+                message.validator_id,
+                attestation.validator_id
+            )));
+        }
+
+        let expected_key = self.members
+            .get(&attestation.validator_id)
+            .ok_or(ValidatorError::NotCommitteeMember(attestation.validator_id))?;
+
+        let public_key = hex::decode(&attestation.public_key)?;
+        if &public_key != expected_key {
+            return Err(ValidatorError::NotCommitteeMember(attestation.validator_id));
+        }
+
+        if attestation.eth_address != TSSKeyGenerator::derive_eth_address(&public_key) {
+            return Err(ValidatorError::SignatureVerification(
+                "Attested address does not derive from public key".to_string(),
+            ));
+        }
+
+        let current_epoch = epoch_for(now);
+        if attestation.epoch + EPOCH_TOLERANCE < current_epoch || attestation.epoch > current_epoch + EPOCH_TOLERANCE {
+            return Err(ValidatorError::SignatureVerification(format!(
+                "Stale heartbeat epoch {} (current {})",
+                attestation.epoch,
+                current_epoch
+            )));
+        }
+
+        let share_key = VerifyingKey::from_sec1_bytes(&public_key)?;
+        let binding = Signature::from_slice(&hex::decode(&attestation.transport_binding)?)?;
+        share_key.verify(
+            &transport_binding_message(attestation.validator_id, &attestation.transport_public_key),
+            &binding,
+        )?;
+
+        transport::verify_message(message, &hex::decode(&attestation.transport_public_key)?)?;
+
+        Ok(attestation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Committee of the position-derived test shares
+    fn mpc_config() -> MPCConfig {
+        let generator = TSSKeyGenerator::new(4, 7);
+        MPCConfig {
+            threshold: 4,
+            total_parties: 7,
+            keygen_timeout_secs: 300,
+            signing_timeout_secs: 60,
+            key_gen_output_path: "./keys".to_string(),
+            key_backup_retention: 3,
+            max_round_attempts: 3,
+            share_public_keys: (0..7).map(|id| hex::encode(generator.generate_keys(id).unwrap().0.eth_public_key)).collect(),
+            quorum: None,
+            share_backup: None,
+        }
+    }
+
+    #[test]
+    fn test_member_heartbeat_verifies() {
+        let committee = Committee::from_mpc_config(&mpc_config()).unwrap();
+        let (share, _) = TSSKeyGenerator::new(4, 7).generate_keys(2).unwrap();
+        let now = 1_700_000_000;
+
+        let transport_key = TransportKey::generate();
+
+        let heartbeat = build_heartbeat(&share, &transport_key, now).unwrap();
+        let attestation = committee.verify_heartbeat(&heartbeat, now).unwrap();
+
+        assert_eq!(attestation.validator_id, 2);
+        assert_eq!(attestation.transport_public_key, hex::encode(transport_key.public_key()));
+        assert_eq!(attestation.eth_address, TSSKeyGenerator::derive_eth_address(&share.eth_public_key));
+    }
+
+    #[test]
+    fn test_published_share_keys_define_committee() {
+        let generator = TSSKeyGenerator::new(4, 7);
+        let mnemonic = bip39::Mnemonic::from_entropy(&[42u8; 32]).unwrap();
+        let (share, _) = generator.generate_keys_from_mnemonic(2, &mnemonic).unwrap();
+        let now = 1_700_000_000;
+
+        let mut mpc = mpc_config();
+        mpc.share_public_keys = (0..7)
+            .map(|i| {
+                let (s, _) = generator.generate_keys_from_mnemonic(i, &mnemonic).unwrap();
+                hex::encode(s.eth_public_key)
+            })
+            .collect();
+        let committee = Committee::from_mpc_config(&mpc).unwrap();
+
+        let heartbeat = build_heartbeat(&share, &TransportKey::generate(), now).unwrap();
+        assert!(committee.verify_heartbeat(&heartbeat, now).is_ok());
+
+        // The position-derived share is no longer a member
+        let (legacy, _) = generator.generate_keys(2).unwrap();
+        let heartbeat = build_heartbeat(&legacy, &TransportKey::generate(), now).unwrap();
+        assert!(matches!(
+            committee.verify_heartbeat(&heartbeat, now),
+            Err(ValidatorError::NotCommitteeMember(2))
+        ));
+
+        mpc.share_public_keys.pop();
+        assert!(matches!(Committee::from_mpc_config(&mpc), Err(ValidatorError::Config(_))));
+
+        // Without published keys there is no committee to fall back to
+        mpc.share_public_keys.clear();
+        assert!(matches!(Committee::from_mpc_config(&mpc), Err(ValidatorError::Config(_))));
+    }
+
+    #[test]
+    fn test_joint_public_key_is_the_aggregate_of_member_shares() {
+        let committee = Committee::from_mpc_config(&mpc_config()).unwrap();
+        let generator = TSSKeyGenerator::new(4, 7);
+        let shares: Vec<TSSKeyShare> = (0..7).map(|id| generator.generate_keys(id).unwrap().0).collect();
+
+        let joint_keys = generator.combine_shares(&shares).unwrap();
+        assert_eq!(committee.joint_public_key().unwrap(), joint_keys.eth_public_key);
+        // Not any single validator's share key
+        assert_ne!(committee.joint_public_key().unwrap(), shares[0].eth_public_key);
+    }
+
+    #[test]
+    fn test_non_member_heartbeat_rejected() {
+        let committee = Committee::from_mpc_config(&mpc_config()).unwrap();
+        // A share from a differently parameterised DKG is not part of this committee
+        let (outsider, _) = TSSKeyGenerator::new(2, 3).generate_keys(1).unwrap();
+        let now = 1_700_000_000;
+
+        let heartbeat = build_heartbeat(&outsider, &TransportKey::generate(), now).unwrap();
+        assert!(matches!(
+            committee.verify_heartbeat(&heartbeat, now),
+            Err(ValidatorError::NotCommitteeMember(1))
+        ));
+
+        let (unknown_id, _) = TSSKeyGenerator::new(4, 7).generate_keys(9).unwrap();
+        let heartbeat = build_heartbeat(&unknown_id, &TransportKey::generate(), now).unwrap();
+        assert!(matches!(
+            committee.verify_heartbeat(&heartbeat, now),
+            Err(ValidatorError::NotCommitteeMember(9))
+        ));
+    }
+
+    #[test]
+    fn test_tampered_or_stale_heartbeat_rejected() {
+        let committee = Committee::from_mpc_config(&mpc_config()).unwrap();
+        let (share, _) = TSSKeyGenerator::new(4, 7).generate_keys(0).unwrap();
+        let now = 1_700_000_000;
+
+        let transport_key = TransportKey::generate();
+
+        let mut heartbeat = build_heartbeat(&share, &transport_key, now).unwrap();
+        heartbeat.signature[10] ^= 0xff;
+        assert!(matches!(
+            committee.verify_heartbeat(&heartbeat, now),
+            Err(ValidatorError::SignatureVerification(_))
+        ));
+
+        // A transport key the share holder never certified is rejected
+        let mut heartbeat = build_heartbeat(&share, &transport_key, now).unwrap();
+        let impostor = TransportKey::generate();
+        heartbeat.data["transport_public_key"] = serde_json::json!(hex::encode(impostor.public_key()));
+        impostor.sign_message(&mut heartbeat).unwrap();
+        assert!(committee.verify_heartbeat(&heartbeat, now).is_err());
+
+        let heartbeat = build_heartbeat(&share, &transport_key, now).unwrap();
+        assert!(committee.verify_heartbeat(&heartbeat, now + 10 * HEARTBEAT_INTERVAL_SECS).is_err());
+    }
+}
@@ -1,20 +1,63 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::net::SocketAddr;
 use std::sync::Arc;
 use tokio::sync::RwLock;
-use tracing::{debug, error, info};
-use anyhow::Result;
+use sha2::{Digest, Sha256};
+use tracing::{debug, error, info, warn};
+use crate::error::{Result, ValidatorError};
 
 use axum::{
-    extract::{State, Json},
+    extract::{ConnectInfo, State, Json},
     routing::{get, post},
     Router,
 };
 
+use crate::config::{LivenessConfig, RateLimitConfig};
+use crate::liveness::{LivenessReport, LivenessTracker};
+use crate::membership::Committee;
+use crate::party_registry::PartyRegistry;
+use crate::rate_limit::RateLimiter;
+use crate::reservation::{MintReservations, ReserveAction, ReserveRequest, ReserveResponse};
+use crate::signing::{verify_threshold_signature, SigningResult};
+use crate::canonical;
+use crate::transport::{self, TransportKey};
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct PartySignupRequest {
     pub validator_id: usize,
     pub intent: String,
+    // Hex transport public key; the party index is tied to it
+    pub identity: String,
+    // Hex signature by that transport key over the validator id and intent,
+    // so a slot can only be claimed for a key the caller holds
+    pub signature: String,
+}
+
+fn party_signup_message(validator_id: usize, intent: &str) -> Vec<u8> {
+    let mut message = b"wxmr_party_signup_".to_vec();
+    message.extend_from_slice(&(validator_id as u64).to_be_bytes());
+    message.extend_from_slice(intent.as_bytes());
+    message
+}
+
+impl PartySignupRequest {
+    pub fn signed(validator_id: usize, intent: &str, transport_key: &TransportKey) -> Self {
+        Self {
+            validator_id,
+            intent: intent.to_string(),
+            identity: hex::encode(transport_key.public_key()),
+            signature: hex::encode(transport_key.sign_bytes(&party_signup_message(validator_id, intent))),
+        }
+    }
+
+    pub fn verify(&self) -> Result<()> {
+        transport::verify_bytes(
+            &party_signup_message(self.validator_id, &self.intent),
+            &hex::decode(&self.signature)?,
+            &hex::decode(&self.identity)?,
+        )
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -30,8 +73,18 @@ pub struct ConsensusMessage {
     pub data: serde_json::Value,
     pub signature: Vec<u8>,
     pub timestamp: u64,
+    // Relay count, not covered by the transport signature so peers can bump it
+    #[serde(default)]
+    pub hops: u8,
 }
 
+// Enough for a committee of 7 to reach every member over a line topology
+pub const MAX_GOSSIP_HOPS: u8 = 6;
+const SEEN_MESSAGES_CAPACITY: usize = 4096;
+// Messages further than this from our clock are dropped, so a replay cannot
+// outlive its id in the bounded dedup set
+const MESSAGE_MAX_AGE_SECS: u64 = 120;
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SignatureRequest {
     pub tx_hash: String,
@@ -48,10 +101,61 @@ pub struct SignatureResponse {
     pub validator_id: usize,
 }
 
+// Bounded dedup set of message ids; the oldest ids are evicted first
+#[derive(Default)]
+pub struct SeenMessages {
+    ids: HashSet<[u8; 32]>,
+    order: VecDeque<[u8; 32]>,
+}
+
+impl SeenMessages {
+    pub fn contains(&self, id: &[u8; 32]) -> bool {
+        self.ids.contains(id)
+    }
+
+    // Returns false if the id was already present
+    pub fn insert(&mut self, id: [u8; 32]) -> bool {
+        if !self.ids.insert(id) {
+            return false;
+        }
+        
+        self.order.push_back(id);
+        if self.order.len() > SEEN_MESSAGES_CAPACITY {
+            if let Some(oldest) = self.order.pop_front() {
+                self.ids.remove(&oldest);
+            }
+        }
+        true
+    }
+}
+
+pub fn message_id(message: &ConsensusMessage) -> [u8; 32] {
+    Sha256::digest(canonical::message_bytes(message)).into()
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VerifySignatureRequest {
+    pub operation_hash: String,
+    pub r: [u8; 32],
+    pub s: [u8; 32],
+    pub v: u8,
+}
+
 #[derive(Clone)]
 pub struct NetworkState {
     pub peers: Arc<RwLock<HashMap<usize, String>>>,
     pub messages: Arc<RwLock<Vec<ConsensusMessage>>>,
+    pub seen_messages: Arc<RwLock<SeenMessages>>,
+    pub live_validators: Arc<RwLock<HashMap<usize, u64>>>,
+    pub transport_keys: Arc<RwLock<HashMap<usize, Vec<u8>>>>,
+    pub committee: Option<Arc<Committee>>,
+    pub joint_public_key: Option<Arc<Vec<u8>>>,
+    pub reservations: Option<Arc<MintReservations>>,
+    // Transport keys besides the committee's allowed to reserve, e.g. the relay
+    pub reservation_clients: Arc<Vec<Vec<u8>>>,
+    pub party_registry: Option<Arc<PartyRegistry>>,
+    pub rate_limiter: Arc<RateLimiter>,
+    pub liveness: Arc<LivenessTracker>,
     pub validator_id: usize,
     pub port: u16,
 }
@@ -61,21 +165,100 @@ impl NetworkState {
         Self {
             peers: Arc::new(RwLock::new(HashMap::new())),
             messages: Arc::new(RwLock::new(Vec::new())),
+            seen_messages: Arc::new(RwLock::new(SeenMessages::default())),
+            live_validators: Arc::new(RwLock::new(HashMap::new())),
+            transport_keys: Arc::new(RwLock::new(HashMap::new())),
+            committee: None,
+            joint_public_key: None,
+            reservations: None,
+            reservation_clients: Arc::new(Vec::new()),
+            party_registry: None,
+            rate_limiter: Arc::new(RateLimiter::new(RateLimitConfig::default())),
+            liveness: Arc::new(LivenessTracker::new(LivenessConfig::default())),
             validator_id,
             port,
         }
     }
     
+    pub fn with_committee(mut self, committee: Committee) -> Self {
+        self.committee = Some(Arc::new(committee));
+        self
+    }
+    
+    pub fn with_reservations(mut self, reservations: Arc<MintReservations>, clients: Vec<Vec<u8>>) -> Self {
+        self.reservations = Some(reservations);
+        self.reservation_clients = Arc::new(clients);
+        self
+    }
+    
+    // Committee members, by the transport keys their heartbeats certified,
+    // and the configured reservation clients
+    async fn may_reserve(&self, signer: &[u8]) -> bool {
+        self.reservation_clients.iter().any(|key| key == signer)
+            || self.transport_keys.read().await.values().any(|key| key == signer)
+    }
+    
+    pub fn with_party_registry(mut self, party_registry: Arc<PartyRegistry>) -> Self {
+        self.party_registry = Some(party_registry);
+        self
+    }
+    
+    // Without a registry every validator is party validator_id + 1
+    pub async fn assign_party(&self, request: &PartySignupRequest) -> Result<PartySignupResponse> {
+        request.verify()?;
+        let number = match self.party_registry {
+            Some(ref registry) => registry.signup(request.validator_id, &request.identity).await?,
+            None => request.validator_id + 1,
+        };
+        
+        Ok(PartySignupResponse { number, ready: true })
+    }
+    
+    pub fn with_joint_public_key(mut self, joint_public_key: Vec<u8>) -> Self {
+        self.joint_public_key = Some(Arc::new(joint_public_key));
+        self
+    }
+    
+    pub fn with_rate_limit(mut self, config: RateLimitConfig) -> Self {
+        self.rate_limiter = Arc::new(RateLimiter::new(config));
+        self
+    }
+    
+    pub fn with_liveness(mut self, config: LivenessConfig) -> Self {
+        self.liveness = Arc::new(LivenessTracker::new(config));
+        self
+    }
+    
     pub async fn add_peer(&self, id: usize, address: String) {
         let mut peers = self.peers.write().await;
         peers.insert(id, address);
     }
     
     pub async fn broadcast_message(&self, msg: ConsensusMessage) -> Result<()> {
+        // Our own message must not be accepted again when peers relay it back
+        self.seen_messages.write().await.insert(message_id(&msg));
+        self.send_to_peers(msg, None).await;
+        Ok(())
+    }
+    
+    // Re-broadcasts a newly accepted message so it reaches validators that are
+    // not direct peers of its sender
+    pub async fn relay_message(&self, mut msg: ConsensusMessage) {
+        if msg.hops >= MAX_GOSSIP_HOPS {
+            debug!("Not relaying {} from validator {}: hop limit reached", msg.msg_type, msg.validator_id);
+            return;
+        }
+        
+        msg.hops += 1;
+        let origin = msg.validator_id;
+        self.send_to_peers(msg, Some(origin)).await;
+    }
+    
+    async fn send_to_peers(&self, msg: ConsensusMessage, skip: Option<usize>) {
         let peers = self.peers.read().await;
         
         let mut handles = vec![];
-        for (_, peer_url) in peers.iter() {
+        for (_, peer_url) in peers.iter().filter(|(id, _)| Some(**id) != skip) {
             let msg_clone = msg.clone();
             let peer_url = peer_url.clone();
             
@@ -87,8 +270,6 @@ impl NetworkState {
         }
         
         futures::future::join_all(handles).await;
-        
-        Ok(())
     }
 }
 
@@ -100,8 +281,9 @@ async fn send_message_to_peer(peer_url: &str, msg: &ConsensusMessage) -> Result<
         .post(&url)
         .json(msg)
         .send()
-        .await?
-        .error_for_status()?;
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|source| ValidatorError::PeerUnreachable { peer: peer_url.to_string(), source })?;
     
     Ok(())
 }
@@ -112,45 +294,32 @@ pub struct NetworkClient {
 }
 
 impl NetworkClient {
-    pub fn new(network_config: crate::config::NetworkConfig) -> Self {
-        let state = NetworkState::new(
-            0, // placeholder
-            network_config.bind_address.port(),
-        );
-        
+    pub fn with_state(state: NetworkState) -> Self {
         Self { state }
     }
     
-    pub fn with_state(state: NetworkState) -> Self {
-        Self { state }
+    pub fn state(&self) -> &NetworkState {
+        &self.state
     }
     
     pub async fn signup(&self, request: PartySignupRequest) -> Result<PartySignupResponse> {
-        let response = PartySignupResponse {
-            number: request.validator_id + 1,
-            ready: true,
-        };
+        let response = self.state.assign_party(&request).await?;
         
         info!("Assigned party number {} to validator {}", response.number, request.validator_id);
         Ok(response)
     }
     
     pub async fn start_server(&self) -> Result<()> {
-        let state = self.state.clone();
-        
-        let app = Router::new()
-            .route("/health", get(handler_health))
-            .route("/party", post(handler_party_signup))
-            .route("/sign", post(handler_signature_request))
-            .route("/message", post(handler_message))
-            .with_state(state);
+        let app = router(self.state.clone());
         
         let listener = tokio::net::TcpListener::bind(format!("0.0.0.0:{}", self.state.port))
             .await
             .expect("Failed to bind server");
             
         info!("Starting validator server on port {}", self.state.port);
-        axum::serve(listener, app).await.expect("Server error");
+        axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
+            .await
+            .expect("Server error");
         
         Ok(())
     }
@@ -158,25 +327,19 @@ impl NetworkClient {
     pub async fn broadcast(&self, message: ConsensusMessage) -> Result<()> {
         self.state.broadcast_message(message).await
     }
-    
-    pub async fn wait_for_quorum(&self, msg_type: &str, required_quorum: usize) -> Result<Vec<ConsensusMessage>> {
-        let messages = self.state.messages.read().await;
-        let relevant_messages: Vec<_> = messages
-            .iter()
-            .filter(|m| m.msg_type == msg_type)
-            .cloned()
-            .collect();
-            
-        if relevant_messages.len() >= required_quorum {
-            Ok(relevant_messages)
-        } else {
-            Err(anyhow::anyhow!(
-                "Insufficient messages. Need {}, have {}",
-                required_quorum,
-                relevant_messages.len()
-            ))
-        }
-    }
+}
+
+pub(crate) fn router(state: NetworkState) -> Router {
+    Router::new()
+        .route("/health", get(handler_health))
+        .route("/party", post(handler_party_signup))
+        .route("/sign", post(handler_signature_request))
+        .route("/message", post(handler_message))
+        .route("/verify", post(handler_verify_signature))
+        .route("/reserve", post(handler_reserve_mint))
+        .route("/release", post(handler_release_mint))
+        .route("/liveness", get(handler_liveness))
+        .with_state(state)
 }
 
 async fn handler_health(State(state): State<NetworkState>) -> axum::response::Json<serde_json::Value> {
@@ -187,22 +350,57 @@ async fn handler_health(State(state): State<NetworkState>) -> axum::response::Js
     }))
 }
 
+async fn handler_liveness(State(state): State<NetworkState>) -> axum::Json<LivenessReport> {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    let members = state.committee.as_ref().map(|committee| committee.members()).unwrap_or_default();
+    
+    axum::Json(state.liveness.report(&members, now).await)
+}
+
 async fn handler_party_signup(
     State(state): State<NetworkState>,
+    remote: Option<ConnectInfo<SocketAddr>>,
     Json(request): Json<PartySignupRequest>,
-) -> Result<axum::Json<PartySignupResponse>, axum::http::StatusCode> {
-    let response = PartySignupResponse {
-        number: request.validator_id + 1,
-        ready: true,
-    };
+) -> std::result::Result<axum::Json<PartySignupResponse>, axum::http::StatusCode> {
+    let source = remote
+        .map(|ConnectInfo(addr)| format!("addr:{}", addr.ip()))
+        .unwrap_or_else(|| "addr:unknown".to_string());
+    
+    if !state.rate_limiter.check(&source) {
+        warn!("Rate limit exceeded for party signups from {}", source);
+        return Err(axum::http::StatusCode::TOO_MANY_REQUESTS);
+    }
+    
+    let response = state.assign_party(&request).await.map_err(|e| match e {
+        ValidatorError::PartyConflict { .. } => axum::http::StatusCode::CONFLICT,
+        ValidatorError::NotCommitteeMember(_) => axum::http::StatusCode::FORBIDDEN,
+        ValidatorError::SignatureVerification(_) | ValidatorError::Encoding(_) => axum::http::StatusCode::UNAUTHORIZED,
+        e => {
+            error!("Failed to assign party for validator {}: {}", request.validator_id, e);
+            axum::http::StatusCode::INTERNAL_SERVER_ERROR
+        }
+    })?;
     
     Ok(axum::Json(response))
 }
 
 async fn handler_signature_request(
-    State(_state): State<NetworkState>,
+    State(state): State<NetworkState>,
+    remote: Option<ConnectInfo<SocketAddr>>,
     Json(_request): Json<SignatureRequest>,
-) -> Result<axum::Json<SignatureResponse>, axum::http::StatusCode> {
+) -> std::result::Result<axum::Json<SignatureResponse>, axum::http::StatusCode> {
+    let source = remote
+        .map(|ConnectInfo(addr)| format!("addr:{}", addr.ip()))
+        .unwrap_or_else(|| "addr:unknown".to_string());
+    
+    if !state.rate_limiter.check(&source) {
+        warn!("Rate limit exceeded for signing requests from {}", source);
+        return Err(axum::http::StatusCode::TOO_MANY_REQUESTS);
+    }
+    
     let response = SignatureResponse {
         r: [0u8; 32],
         s: [0u8; 32],
@@ -213,15 +411,374 @@ async fn handler_signature_request(
     Ok(axum::Json(response))
 }
 
+async fn authorize_reservation(
+    state: &NetworkState,
+    remote: Option<ConnectInfo<SocketAddr>>,
+    request: &ReserveRequest,
+    action: ReserveAction,
+) -> std::result::Result<Arc<MintReservations>, axum::http::StatusCode> {
+    let source = remote
+        .map(|ConnectInfo(addr)| format!("addr:{}", addr.ip()))
+        .unwrap_or_else(|| "addr:unknown".to_string());
+    
+    if !state.rate_limiter.check(&source) {
+        warn!("Rate limit exceeded for mint reservations from {}", source);
+        return Err(axum::http::StatusCode::TOO_MANY_REQUESTS);
+    }
+    
+    // Only the node acting as reservation authority hosts the store
+    let reservations = state.reservations.clone().ok_or(axum::http::StatusCode::SERVICE_UNAVAILABLE)?;
+    
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    if let Err(e) = request.verify(action, now) {
+        warn!("Rejected reservation request for {} from {}: {}", request.key, source, e);
+        return Err(axum::http::StatusCode::UNAUTHORIZED);
+    }
+    let signer = hex::decode(&request.signer).map_err(|_| axum::http::StatusCode::UNAUTHORIZED)?;
+    if !state.may_reserve(&signer).await {
+        warn!("Reservation request for {} from unknown key {}", request.key, request.signer);
+        return Err(axum::http::StatusCode::FORBIDDEN);
+    }
+    
+    Ok(reservations)
+}
+
+async fn handler_reserve_mint(
+    State(state): State<NetworkState>,
+    remote: Option<ConnectInfo<SocketAddr>>,
+    Json(request): Json<ReserveRequest>,
+) -> std::result::Result<axum::Json<ReserveResponse>, axum::http::StatusCode> {
+    let reservations = authorize_reservation(&state, remote, &request, ReserveAction::Reserve).await?;
+    let owner = reservations
+        .reserve(&request.key, &request.signer)
+        .await
+        .map_err(|e| {
+            error!("Failed to record mint reservation: {}", e);
+            axum::http::StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    
+    Ok(axum::Json(ReserveResponse { reserved: owner == request.signer, owner }))
+}
+
+async fn handler_release_mint(
+    State(state): State<NetworkState>,
+    remote: Option<ConnectInfo<SocketAddr>>,
+    Json(request): Json<ReserveRequest>,
+) -> std::result::Result<axum::Json<serde_json::Value>, axum::http::StatusCode> {
+    let reservations = authorize_reservation(&state, remote, &request, ReserveAction::Release).await?;
+    let released = reservations
+        .release(&request.key, &request.signer)
+        .await
+        .map_err(|e| {
+            error!("Failed to release mint reservation: {}", e);
+            axum::http::StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    
+    Ok(axum::Json(serde_json::json!({ "released": released })))
+}
+
+async fn handler_verify_signature(
+    State(state): State<NetworkState>,
+    Json(request): Json<VerifySignatureRequest>,
+) -> std::result::Result<axum::Json<serde_json::Value>, axum::http::StatusCode> {
+    let joint_public_key = state.joint_public_key
+        .as_ref()
+        .ok_or(axum::http::StatusCode::SERVICE_UNAVAILABLE)?;
+    
+    let operation_hash: [u8; 32] = hex::decode(request.operation_hash.trim_start_matches("0x"))
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or(axum::http::StatusCode::BAD_REQUEST)?;
+    
+    let signature = SigningResult {
+        r: request.r,
+        s: request.s,
+        v: request.v,
+        validator_id: state.validator_id,
+    };
+    let valid = verify_threshold_signature(&operation_hash, &signature, joint_public_key);
+    
+    Ok(axum::Json(serde_json::json!({ "valid": valid })))
+}
+
 async fn handler_message(
     State(state): State<NetworkState>,
+    remote: Option<ConnectInfo<SocketAddr>>,
     Json(message): Json<ConsensusMessage>,
-) -> Result<axum::Json<serde_json::Value>, axum::http::StatusCode> {
+) -> std::result::Result<axum::Json<serde_json::Value>, axum::http::StatusCode> {
     let validator_id = message.validator_id;
-    let mut messages = state.messages.write().await;
-    messages.push(message.clone());
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    
+    let mut attestation = None;
+    if let Some(ref committee) = state.committee {
+        if message.msg_type == "HEARTBEAT" {
+            match committee.verify_heartbeat(&message, now) {
+                Ok(verified) => attestation = Some(verified),
+                Err(e) => {
+                    warn!("Rejected heartbeat from validator {}: {}", validator_id, e);
+                    return Err(axum::http::StatusCode::FORBIDDEN);
+                }
+            }
+        } else {
+            // Other messages must be signed by the transport key certified in the sender's heartbeat
+            let transport_keys = state.transport_keys.read().await;
+            let verified = transport_keys
+                .get(&validator_id)
+                .map(|key| transport::verify_message(&message, key).is_ok())
+                .unwrap_or(false);
+            
+            if !verified {
+                warn!("Rejected unauthenticated {} message from validator {}", message.msg_type, validator_id);
+                return Err(axum::http::StatusCode::FORBIDDEN);
+            }
+        }
+    }
+    
+    // Stale messages and ones we already have are dropped before anything is
+    // charged: replays, and the copies honest peers relay, must not eat into
+    // anyone's budget
+    if now.abs_diff(message.timestamp) > MESSAGE_MAX_AGE_SECS {
+        debug!("Dropping stale {} message from validator {}", message.msg_type, validator_id);
+        return Ok(axum::Json(serde_json::json!({"status": "stale"})));
+    }
+    let id = message_id(&message);
+    if state.seen_messages.read().await.contains(&id) {
+        debug!("Dropping duplicate {} message from validator {}", message.msg_type, validator_id);
+        return Ok(axum::Json(serde_json::json!({"status": "duplicate"})));
+    }
+    
+    // The budget belongs to the connecting peer. Relayed messages name their
+    // origin, so charging the claimed sender would let any relay, or anyone
+    // replaying its traffic, throttle that validator everywhere.
+    let source = remote
+        .map(|ConnectInfo(addr)| format!("addr:{}", addr.ip()))
+        .unwrap_or_else(|| "addr:unknown".to_string());
+    if !state.rate_limiter.check(&source) {
+        warn!("Rate limit exceeded for messages from {}", source);
+        return Err(axum::http::StatusCode::TOO_MANY_REQUESTS);
+    }
+    
+    if !state.seen_messages.write().await.insert(id) {
+        return Ok(axum::Json(serde_json::json!({"status": "duplicate"})));
+    }
+    
+    if let Some(attestation) = attestation {
+        let transport_key = hex::decode(&attestation.transport_public_key)
+            .map_err(|_| axum::http::StatusCode::BAD_REQUEST)?;
+        state.transport_keys.write().await.insert(validator_id, transport_key);
+        state.live_validators.write().await.insert(validator_id, message.timestamp);
+        state.liveness.record_heartbeat(validator_id, message.timestamp).await;
+    }
+    
+    state.messages.write().await.push(message.clone());
     
     debug!("Received message from validator {}", validator_id);
     
+    let relay_state = state.clone();
+    tokio::spawn(async move { relay_state.relay_message(message).await });
+    
     Ok(axum::Json(serde_json::json!({"status": "received"})))
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::serve_network;
+    use axum::http::StatusCode;
+    
+    fn now() -> u64 {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+    }
+    
+    async fn send_message(base_url: &str, validator_id: usize, round: u64, timestamp: u64) -> (StatusCode, serde_json::Value) {
+        let message = ConsensusMessage {
+            validator_id,
+            msg_type: "SIGN_ROUND".to_string(),
+            data: serde_json::json!({ "round": round }),
+            signature: vec![],
+            timestamp,
+            hops: 0,
+        };
+        
+        let response = reqwest::Client::new()
+            .post(format!("{}/message", base_url))
+            .json(&message)
+            .send()
+            .await
+            .unwrap();
+        let status = StatusCode::from_u16(response.status().as_u16()).unwrap();
+        (status, response.json().await.unwrap_or_default())
+    }
+    
+    #[tokio::test]
+    async fn test_message_rate_limit_returns_429() {
+        let state = NetworkState::new(0, 0)
+            .with_rate_limit(RateLimitConfig { burst: 5, per_second: 0.001 });
+        let base_url = serve_network(state).await;
+        
+        let sent_at = now();
+        
+        // A signing round's burst from one peer is accepted
+        for round in 0..5 {
+            assert_eq!(send_message(&base_url, 1, round, sent_at).await.0, StatusCode::OK);
+        }
+        
+        // Copies of what we already have and stale replays are dropped without
+        // being charged, so even an exhausted peer gets them acknowledged
+        assert_eq!(send_message(&base_url, 1, 0, sent_at).await.1["status"], "duplicate");
+        assert_eq!(send_message(&base_url, 1, 9, sent_at - 3600).await.1["status"], "stale");
+        
+        // Sustained traffic beyond the budget is throttled, whatever id it claims
+        assert_eq!(send_message(&base_url, 1, 5, sent_at).await.0, StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(send_message(&base_url, 2, 6, sent_at).await.0, StatusCode::TOO_MANY_REQUESTS);
+    }
+    
+    #[tokio::test]
+    async fn test_spoofed_messages_do_not_drain_a_validators_budget() {
+        use crate::membership::{build_heartbeat, Committee};
+        use crate::tss::TSSKeyGenerator;
+        
+        let generator = TSSKeyGenerator::new(4, 7);
+        let shares: Vec<_> = (0..7).map(|id| generator.generate_keys(id).unwrap().0).collect();
+        let mut config: crate::config::Config = toml::from_str(include_str!("../config.toml")).unwrap();
+        config.mpc.share_public_keys = shares.iter().map(|share| hex::encode(&share.eth_public_key)).collect();
+        
+        let state = NetworkState::new(0, 0)
+            .with_committee(Committee::from_mpc_config(&config.mpc).unwrap())
+            .with_rate_limit(RateLimitConfig { burst: 3, per_second: 0.001 });
+        let base_url = serve_network(state).await;
+        let client = reqwest::Client::new();
+        let post = |message: ConsensusMessage| client.post(format!("{}/message", base_url)).json(&message).send();
+        
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let transport_key = TransportKey::generate();
+        let heartbeat = build_heartbeat(&shares[1], &transport_key, now).unwrap();
+        assert_eq!(post(heartbeat).await.unwrap().status(), reqwest::StatusCode::OK);
+        
+        // Forged traffic in validator 1's name, from unknown ids too, is refused
+        // before it is counted against anyone
+        for id in [1, 1, 1, 1, 99] {
+            let mut forged = gossip_message(0);
+            forged.validator_id = id;
+            TransportKey::generate().sign_message(&mut forged).unwrap();
+            assert_eq!(post(forged).await.unwrap().status(), reqwest::StatusCode::FORBIDDEN);
+        }
+        
+        // The rest of validator 1's budget is untouched
+        for round in 0..2 {
+            let mut genuine = gossip_message(0);
+            genuine.validator_id = 1;
+            genuine.data = serde_json::json!({ "round": round });
+            transport_key.sign_message(&mut genuine).unwrap();
+            assert_eq!(post(genuine).await.unwrap().status(), reqwest::StatusCode::OK);
+        }
+    }
+    
+    async fn wait_for_message(state: &NetworkState, msg_type: &str) -> bool {
+        for _ in 0..50 {
+            if state.messages.read().await.iter().any(|m| m.msg_type == msg_type) {
+                return true;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        false
+    }
+    
+    fn gossip_message(hops: u8) -> ConsensusMessage {
+        ConsensusMessage {
+            validator_id: 0,
+            msg_type: "SIGN_ROUND".to_string(),
+            data: serde_json::json!({ "round": 1 }),
+            signature: vec![],
+            timestamp: now(),
+            hops,
+        }
+    }
+    
+    // A - B - C, where A and C are not peers of each other
+    async fn line_topology() -> (NetworkState, NetworkState, NetworkState) {
+        let a = NetworkState::new(0, 0);
+        let b = NetworkState::new(1, 0);
+        let c = NetworkState::new(2, 0);
+        
+        let a_url = serve_network(a.clone()).await;
+        let b_url = serve_network(b.clone()).await;
+        let c_url = serve_network(c.clone()).await;
+        
+        a.add_peer(1, b_url.clone()).await;
+        b.add_peer(0, a_url).await;
+        b.add_peer(2, c_url).await;
+        c.add_peer(1, b_url).await;
+        
+        (a, b, c)
+    }
+    
+    #[tokio::test]
+    async fn test_gossip_reaches_non_adjacent_peer() {
+        let (a, b, c) = line_topology().await;
+        
+        a.broadcast_message(gossip_message(0)).await.unwrap();
+        
+        assert!(wait_for_message(&c, "SIGN_ROUND").await);
+        let received = c.messages.read().await.clone();
+        assert_eq!(received.len(), 1);
+        assert_eq!(received[0].validator_id, 0);
+        assert_eq!(received[0].hops, 1);
+        
+        // Nobody stores a second copy, and the origin never takes its own message back
+        assert_eq!(b.messages.read().await.len(), 1);
+        assert!(a.messages.read().await.is_empty());
+    }
+    
+    #[tokio::test]
+    async fn test_gossip_stops_at_hop_limit() {
+        let (a, b, c) = line_topology().await;
+        
+        a.broadcast_message(gossip_message(MAX_GOSSIP_HOPS)).await.unwrap();
+        
+        assert!(wait_for_message(&b, "SIGN_ROUND").await);
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        assert!(c.messages.read().await.is_empty());
+    }
+    
+    #[tokio::test]
+    async fn test_verify_endpoint_checks_against_joint_key() {
+        let joint_key = k256::ecdsa::SigningKey::random(&mut rand::thread_rng());
+        let joint_public_key = joint_key.verifying_key().to_encoded_point(false).as_bytes().to_vec();
+        let base_url = serve_network(NetworkState::new(0, 0).with_joint_public_key(joint_public_key)).await;
+        
+        let operation_hash = [3u8; 32];
+        let (sig, recovery_id) = joint_key.sign_prehash_recoverable(&operation_hash).unwrap();
+        let (r, s) = sig.split_bytes();
+        let mut request = VerifySignatureRequest {
+            operation_hash: hex::encode(operation_hash),
+            r: r.into(),
+            s: s.into(),
+            v: 27 + recovery_id.to_byte(),
+        };
+        
+        let client = reqwest::Client::new();
+        let verify = |request: &VerifySignatureRequest| {
+            client.post(format!("{}/verify", base_url)).json(request).send()
+        };
+        
+        let body: serde_json::Value = verify(&request).await.unwrap().json().await.unwrap();
+        assert_eq!(body["valid"], true);
+        
+        request.s[31] ^= 0x01;
+        let body: serde_json::Value = verify(&request).await.unwrap().json().await.unwrap();
+        assert_eq!(body["valid"], false);
+    }
+}
@@ -0,0 +1,154 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+use crate::error::{Result, ValidatorError};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PartyAssignment {
+    pub party_index: usize,
+    // Hex transport public key of the node that signed up for the slot
+    pub identity: String,
+    pub assigned_at: u64,
+}
+
+// Party indices handed out on /party, keyed by validator id. An index stays
+// with the identity that first signed up for it, across restarts and repeated
+// signups, until an operator vacates the slot.
+pub struct PartyRegistry {
+    store_path: PathBuf,
+    total_parties: usize,
+    assignments: Mutex<BTreeMap<usize, PartyAssignment>>,
+}
+
+impl PartyRegistry {
+    pub async fn open(store_path: impl Into<PathBuf>, total_parties: usize) -> Result<Self> {
+        let store_path = store_path.into();
+
+        let assignments = match tokio::fs::read_to_string(&store_path).await {
+            Ok(content) => serde_json::from_str(&content)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => return Err(e.into()),
+        };
+
+        Ok(Self {
+            store_path,
+            total_parties,
+            assignments: Mutex::new(assignments),
+        })
+    }
+
+    // Same identity gets its existing index back; anyone else claiming the
+    // validator's slot is refused. The index is always validator_id + 1,
+    // which is the party the validator's key files are loaded under.
+    pub async fn signup(&self, validator_id: usize, identity: &str) -> Result<usize> {
+        // Only committee positions get a slot, which also bounds the store
+        if validator_id >= self.total_parties {
+            return Err(ValidatorError::NotCommitteeMember(validator_id));
+        }
+
+        let mut assignments = self.assignments.lock().await;
+
+        if let Some(existing) = assignments.get(&validator_id) {
+            if existing.identity == identity {
+                return Ok(existing.party_index);
+            }
+            warn!("Refusing signup for validator {}: party {} is held by another identity", validator_id, existing.party_index);
+            return Err(ValidatorError::PartyConflict { validator_id, party_index: existing.party_index });
+        }
+
+        let party_index = validator_id + 1;
+
+        assignments.insert(validator_id, PartyAssignment {
+            party_index,
+            identity: identity.to_string(),
+            assigned_at: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_secs(),
+        });
+        self.persist(&assignments).await?;
+        info!("Assigned party {} to validator {}", party_index, validator_id);
+
+        Ok(party_index)
+    }
+
+    // Releases a slot once its holder is confirmed gone, so a replacement node can sign up
+    pub async fn vacate(&self, validator_id: usize) -> Result<Option<usize>> {
+        let mut assignments = self.assignments.lock().await;
+        let Some(released) = assignments.remove(&validator_id) else {
+            return Ok(None);
+        };
+        self.persist(&assignments).await?;
+        info!("Vacated party {} held by validator {}", released.party_index, validator_id);
+
+        Ok(Some(released.party_index))
+    }
+
+    async fn persist(&self, assignments: &BTreeMap<usize, PartyAssignment>) -> Result<()> {
+        let tmp_path = self.store_path.with_extension("json.tmp");
+        tokio::fs::write(&tmp_path, serde_json::to_string_pretty(assignments)?).await?;
+        tokio::fs::rename(&tmp_path, &self.store_path).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::{NetworkState, PartySignupRequest, PartySignupResponse};
+    use crate::test_support::serve_network;
+    use crate::transport::TransportKey;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_resignup_keeps_index_and_conflicts_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let store_path = dir.path().join("party_registry.json");
+        let registry = Arc::new(PartyRegistry::open(&store_path, 7).await.unwrap());
+
+        let base_url = serve_network(NetworkState::new(0, 0).with_party_registry(registry.clone())).await;
+
+        let client = reqwest::Client::new();
+        let signup = |request: PartySignupRequest| client.post(format!("{}/party", base_url)).json(&request).send();
+        let (holder, other) = (TransportKey::generate(), TransportKey::generate());
+
+        let first: PartySignupResponse = signup(PartySignupRequest::signed(2, "keygen", &holder)).await.unwrap().json().await.unwrap();
+        assert_eq!(first.number, 3);
+        let again: PartySignupResponse = signup(PartySignupRequest::signed(2, "keygen", &holder)).await.unwrap().json().await.unwrap();
+        assert_eq!(again.number, first.number);
+
+        let conflict = signup(PartySignupRequest::signed(2, "keygen", &other)).await.unwrap();
+        assert_eq!(conflict.status(), reqwest::StatusCode::CONFLICT);
+
+        // Claiming a key without holding it, or no key at all, is refused
+        let forged = PartySignupRequest { identity: hex::encode(holder.public_key()), ..PartySignupRequest::signed(4, "keygen", &other) };
+        assert_eq!(signup(forged).await.unwrap().status(), reqwest::StatusCode::UNAUTHORIZED);
+        let anonymous = PartySignupRequest { identity: String::new(), ..PartySignupRequest::signed(4, "keygen", &other) };
+        assert_eq!(signup(anonymous).await.unwrap().status(), reqwest::StatusCode::UNAUTHORIZED);
+
+        // Positions outside the committee get no slot
+        let outsider = signup(PartySignupRequest::signed(7, "keygen", &other)).await.unwrap();
+        assert_eq!(outsider.status(), reqwest::StatusCode::FORBIDDEN);
+
+        // The assignment survives a restart of the coordinator
+        let (holder, other) = (hex::encode(holder.public_key()), hex::encode(other.public_key()));
+        let reopened = PartyRegistry::open(&store_path, 7).await.unwrap();
+        assert_eq!(reopened.signup(2, &holder).await.unwrap(), 3);
+        assert!(matches!(
+            reopened.signup(2, &other).await,
+            Err(ValidatorError::PartyConflict { validator_id: 2, party_index: 3 })
+        ));
+
+        // Only once the slot is vacated can a replacement node take it
+        assert_eq!(reopened.vacate(2).await.unwrap(), Some(3));
+        assert_eq!(reopened.signup(2, &other).await.unwrap(), 3);
+        assert_eq!(reopened.vacate(5).await.unwrap(), None);
+
+        // Every validator gets its own id + 1, whatever order they sign up in
+        assert_eq!(reopened.signup(6, &holder).await.unwrap(), 7);
+        assert_eq!(reopened.signup(0, &holder).await.unwrap(), 1);
+    }
+}
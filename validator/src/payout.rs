@@ -0,0 +1,646 @@
+use curve25519_dalek::constants::ED25519_BASEPOINT_TABLE;
+use curve25519_dalek::edwards::{CompressedEdwardsY, EdwardsPoint};
+use curve25519_dalek::scalar::Scalar;
+use async_trait::async_trait;
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Keccak256};
+
+use crate::error::{Result, ValidatorError};
+use crate::subaddress::decode_address;
+
+pub const DEFAULT_RING_SIZE: usize = 16;
+
+// RCTTypeBulletproofPlus, the only type current nodes accept
+const RCT_TYPE_BULLETPROOF_PLUS: u8 = 6;
+const TX_EXTRA_TAG_PUBKEY: u8 = 0x01;
+const TXIN_TO_KEY: u8 = 0x02;
+const TXOUT_TO_TAGGED_KEY: u8 = 0x03;
+
+// Pedersen commitment generator H from rctTypes.h
+const H_BYTES: [u8; 32] = [
+    0x8b, 0x65, 0x59, 0x70, 0x15, 0x37, 0x99, 0xaf, 0x2a, 0xea, 0xdc, 0x9f, 0xf1, 0xad, 0xd0, 0xea,
+    0x6c, 0x72, 0x51, 0xd5, 0x41, 0x54, 0xcf, 0xa9, 0x2c, 0x17, 0x3a, 0x0d, 0xd3, 0x9c, 0x1f, 0x94,
+];
+
+// Standard address prefixes for mainnet, stagenet and testnet
+const STANDARD_PREFIXES: [u8; 3] = [18, 24, 53];
+
+// The round leader's payout for a burn, gossiped so that every signer
+// approves the same transaction rather than one of its own
+pub const PAYOUT_PROPOSAL: &str = "PAYOUT_PROPOSAL";
+
+// An output of the bridge wallet that can be spent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OwnedOutput {
+    pub global_index: u64,
+    pub public_key: [u8; 32],
+    pub amount: u64,
+    // Blinding factor of the output's amount commitment
+    pub mask: [u8; 32],
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RingMember {
+    pub global_index: u64,
+    pub public_key: [u8; 32],
+    pub commitment: [u8; 32],
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpendableInput {
+    pub output: OwnedOutput,
+    pub decoys: Vec<RingMember>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnsignedInput {
+    // Ring in global index order; key_offsets are the deltas between them
+    pub ring: Vec<RingMember>,
+    pub key_offsets: Vec<u64>,
+    pub real_index: usize,
+    pub pseudo_output: [u8; 32],
+    // Difference between the spent output's mask and the pseudo output's,
+    // which the CLSAG signers need for the commitment key
+    pub commitment_key: [u8; 32],
+    // Filled in by the threshold signers
+    pub key_image: Option<[u8; 32]>,
+    pub clsag: Option<Vec<u8>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TxOutput {
+    pub public_key: [u8; 32],
+    pub view_tag: u8,
+    pub commitment: [u8; 32],
+    pub encrypted_amount: [u8; 8],
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnsignedTransaction {
+    pub version: u8,
+    pub unlock_time: u64,
+    pub rct_type: u8,
+    pub inputs: Vec<UnsignedInput>,
+    pub outputs: Vec<TxOutput>,
+    pub extra: Vec<u8>,
+    pub fee: u64,
+    // Filled in once the range proof has been generated
+    pub bulletproof_plus: Option<Vec<u8>>,
+}
+
+impl UnsignedTransaction {
+    // Keccak of the serialized transaction prefix, which the spend
+    // authorization signs. Key images are zero until the signers fill them in.
+    pub fn prefix_hash(&self) -> [u8; 32] {
+        Keccak256::digest(self.prefix_bytes()).into()
+    }
+
+    // The transaction as monerod takes it, once every input has its key image
+    // and CLSAG and the range proof is attached; None until then
+    pub fn signed(&self) -> Option<SignedTransaction> {
+        let mut key_images = Vec::with_capacity(self.inputs.len());
+        let mut clsags = Vec::with_capacity(self.inputs.len());
+        for input in &self.inputs {
+            key_images.push(input.key_image?);
+            clsags.push(input.clsag.as_ref()?);
+        }
+        let bulletproof_plus = self.bulletproof_plus.as_ref()?;
+
+        let prefix = self.prefix_bytes();
+
+        let mut base = vec![self.rct_type];
+        base.extend(varint(self.fee));
+        for output in &self.outputs {
+            base.extend_from_slice(&output.encrypted_amount);
+        }
+        for output in &self.outputs {
+            base.extend_from_slice(&output.commitment);
+        }
+
+        // One aggregate range proof, then per-input CLSAGs and pseudo outputs
+        let mut prunable = varint(1);
+        prunable.extend_from_slice(bulletproof_plus);
+        for clsag in clsags {
+            prunable.extend_from_slice(clsag);
+        }
+        for input in &self.inputs {
+            prunable.extend_from_slice(&input.pseudo_output);
+        }
+
+        // v2 txid: hash of the hashes of the prefix, rct base and prunable parts
+        let mut hashes = Vec::with_capacity(96);
+        hashes.extend_from_slice(&Keccak256::digest(&prefix));
+        hashes.extend_from_slice(&Keccak256::digest(&base));
+        hashes.extend_from_slice(&Keccak256::digest(&prunable));
+
+        Some(SignedTransaction {
+            blob: [prefix, base, prunable].concat(),
+            txid: Keccak256::digest(hashes).into(),
+            key_images,
+        })
+    }
+
+    fn prefix_bytes(&self) -> Vec<u8> {
+        let mut prefix = varint(self.version as u64);
+        prefix.extend(varint(self.unlock_time));
+
+        prefix.extend(varint(self.inputs.len() as u64));
+        for input in &self.inputs {
+            prefix.push(TXIN_TO_KEY);
+            prefix.extend(varint(0));
+            prefix.extend(varint(input.key_offsets.len() as u64));
+            for offset in &input.key_offsets {
+                prefix.extend(varint(*offset));
+            }
+            prefix.extend_from_slice(&input.key_image.unwrap_or([0u8; 32]));
+        }
+
+        prefix.extend(varint(self.outputs.len() as u64));
+        for output in &self.outputs {
+            prefix.extend(varint(0));
+            prefix.push(TXOUT_TO_TAGGED_KEY);
+            prefix.extend_from_slice(&output.public_key);
+            prefix.push(output.view_tag);
+        }
+
+        prefix.extend(varint(self.extra.len() as u64));
+        prefix.extend_from_slice(&self.extra);
+        prefix
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PayoutProposal {
+    pub operation_hash: String,
+    pub attempt: u32,
+    pub transaction: UnsignedTransaction,
+    pub tx_key: [u8; 32],
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignedTransaction {
+    pub blob: Vec<u8>,
+    pub txid: [u8; 32],
+    pub key_images: Vec<[u8; 32]>,
+}
+
+// Where the bridge wallet's spendable outputs, and decoys for their rings, come from
+#[async_trait]
+pub trait PayoutFunding: Send + Sync {
+    async fn spendable_inputs(&self, amount: u64, ring_size: usize) -> Result<Vec<SpendableInput>>;
+}
+
+// Builds payout transactions from the bridge wallet. The destination gets
+// `amount`; whatever is left after the fee goes back to the bridge address.
+#[derive(Clone)]
+pub struct PayoutBuilder {
+    bridge_address: String,
+    fee: u64,
+    ring_size: usize,
+}
+
+impl PayoutBuilder {
+    pub fn new(bridge_address: impl Into<String>) -> Self {
+        Self {
+            bridge_address: bridge_address.into(),
+            fee: 0,
+            ring_size: DEFAULT_RING_SIZE,
+        }
+    }
+
+    pub fn with_fee(mut self, fee: u64) -> Self {
+        self.fee = fee;
+        self
+    }
+
+    pub fn with_ring_size(mut self, ring_size: usize) -> Self {
+        self.ring_size = ring_size;
+        self
+    }
+
+    pub fn ring_size(&self) -> usize {
+        self.ring_size
+    }
+
+    // Returns the transaction and its secret tx key, which proves the payment
+    // to the recipient via check_tx_key
+    pub fn build(&self, destination: &str, amount: u64, inputs: &[SpendableInput]) -> Result<(UnsignedTransaction, Scalar)> {
+        let tx_key = random_scalar();
+        let tx = self.build_with_tx_key(destination, amount, inputs, &tx_key)?;
+        Ok((tx, tx_key))
+    }
+
+    // Checks a payout someone else built for this burn: it must pay exactly
+    // `amount` to the destination, return the rest less our fee to the
+    // bridge, and balance. Where the inputs come from is left to the CLSAGs.
+    pub fn check(&self, tx: &UnsignedTransaction, tx_key: &Scalar, destination: &str, amount: u64) -> Result<()> {
+        let reject = |reason: &str| Err(ValidatorError::Payout(format!("Proposed payout to {} {}", destination, reason)));
+        let ((spend, view), (bridge_spend, bridge_view)) = self.recipients(destination)?;
+
+        if (tx.version, tx.unlock_time, tx.rct_type, tx.fee) != (2, 0, RCT_TYPE_BULLETPROOF_PLUS, self.fee) {
+            return reject("does not match our transaction parameters or fee");
+        }
+        let mut extra = vec![TX_EXTRA_TAG_PUBKEY];
+        extra.extend_from_slice((tx_key * ED25519_BASEPOINT_TABLE).compress().as_bytes());
+        if tx.extra != extra {
+            return reject("does not carry the public key of its tx key");
+        }
+
+        // One output pays the destination, the other is change to the bridge
+        if tx.outputs.len() != 2 {
+            return reject("does not have exactly two outputs");
+        }
+        let paid = (0..2).find(|&index| build_output(tx_key, index as u64, amount, &spend, &view).0 == tx.outputs[index]);
+        let Some(paid) = paid else {
+            return reject("does not pay the burned amount");
+        };
+        let change_index = 1 - paid;
+        let change = decrypt_amount(tx_key, change_index as u64, &bridge_view, &tx.outputs[change_index].encrypted_amount);
+        if build_output(tx_key, change_index as u64, change, &bridge_spend, &bridge_view).0 != tx.outputs[change_index] {
+            return reject("does not send its change to the bridge");
+        }
+
+        for input in &tx.inputs {
+            let offsets_match = input.ring.iter()
+                .scan(0u64, |previous, member| {
+                    let offset = member.global_index.checked_sub(*previous);
+                    *previous = member.global_index;
+                    Some(offset)
+                })
+                .eq(input.key_offsets.iter().map(|offset| Some(*offset)));
+            if input.ring.len() != self.ring_size || !offsets_match || input.real_index >= input.ring.len() {
+                return reject("has a malformed ring");
+            }
+        }
+
+        // sum(pseudo outputs) = sum(outputs) + fee*H, so nothing else is spent
+        let decompress = |bytes: &[u8; 32]| CompressedEdwardsY(*bytes).decompress();
+        let pseudo: Option<EdwardsPoint> = tx.inputs.iter().map(|input| decompress(&input.pseudo_output)).sum();
+        let outputs: Option<EdwardsPoint> = tx.outputs.iter().map(|output| decompress(&output.commitment)).sum();
+        match (pseudo, outputs) {
+            (Some(pseudo), Some(outputs)) if !tx.inputs.is_empty() && pseudo == outputs + Scalar::from(self.fee) * generator_h() => Ok(()),
+            _ => reject("does not balance"),
+        }
+    }
+
+    // Spend and view keys of the destination and of the bridge
+    fn recipients(&self, destination: &str) -> Result<((EdwardsPoint, EdwardsPoint), (EdwardsPoint, EdwardsPoint))> {
+        let (bridge_prefix, bridge_spend, bridge_view) = decode_address(&self.bridge_address)?;
+        let (prefix, spend, view) = decode_address(destination)?;
+        if !STANDARD_PREFIXES.contains(&prefix) {
+            return Err(ValidatorError::Payout(format!("Only standard addresses are supported as payout destinations, got {}", destination)));
+        }
+        if prefix != bridge_prefix {
+            return Err(ValidatorError::Payout(format!("Destination {} is on a different network than the bridge", destination)));
+        }
+        Ok(((spend, view), (bridge_spend, bridge_view)))
+    }
+
+    fn build_with_tx_key(&self, destination: &str, amount: u64, inputs: &[SpendableInput], tx_key: &Scalar) -> Result<UnsignedTransaction> {
+        let ((spend, view), (bridge_spend, bridge_view)) = self.recipients(destination)?;
+        if inputs.is_empty() {
+            return Err(ValidatorError::Payout("No inputs to spend".to_string()));
+        }
+
+        let available = inputs.iter().try_fold(0u64, |sum, input| sum.checked_add(input.output.amount));
+        let needed = amount.checked_add(self.fee);
+        let change = match (available, needed) {
+            (Some(available), Some(needed)) if available >= needed => available - needed,
+            _ => {
+                return Err(ValidatorError::Payout(format!(
+                    "Inputs of {} piconero cannot cover {} plus a fee of {}",
+                    available.unwrap_or(u64::MAX), amount, self.fee
+                )))
+            }
+        };
+
+        // Monero requires at least two outputs, so change is always present.
+        // Its position must not be inferable from the output order.
+        let mut recipients = [(amount, spend, view), (change, bridge_spend, bridge_view)];
+        recipients.shuffle(&mut rand::thread_rng());
+        let mut outputs = Vec::with_capacity(recipients.len());
+        let mut output_masks = Scalar::ZERO;
+        for (index, (value, spend, view)) in recipients.iter().enumerate() {
+            let (output, mask) = build_output(tx_key, index as u64, *value, spend, view);
+            output_masks += mask;
+            outputs.push(output);
+        }
+
+        let mut unsigned_inputs = Vec::with_capacity(inputs.len());
+        let mut pseudo_masks = Scalar::ZERO;
+        for (position, input) in inputs.iter().enumerate() {
+            // The last pseudo output's mask balances the commitments
+            let pseudo_mask = if position + 1 == inputs.len() {
+                output_masks - pseudo_masks
+            } else {
+                random_scalar()
+            };
+            pseudo_masks += pseudo_mask;
+            unsigned_inputs.push(self.build_input(input, &pseudo_mask)?);
+        }
+
+        let mut extra = vec![TX_EXTRA_TAG_PUBKEY];
+        extra.extend_from_slice((tx_key * ED25519_BASEPOINT_TABLE).compress().as_bytes());
+
+        Ok(UnsignedTransaction {
+            version: 2,
+            unlock_time: 0,
+            rct_type: RCT_TYPE_BULLETPROOF_PLUS,
+            inputs: unsigned_inputs,
+            outputs,
+            extra,
+            fee: self.fee,
+            bulletproof_plus: None,
+        })
+    }
+
+    fn build_input(&self, input: &SpendableInput, pseudo_mask: &Scalar) -> Result<UnsignedInput> {
+        let real = RingMember {
+            global_index: input.output.global_index,
+            public_key: input.output.public_key,
+            commitment: commit(input.output.amount, &scalar_from(&input.output.mask)?).compress().to_bytes(),
+        };
+
+        let mut ring = input.decoys.clone();
+        ring.push(real);
+        ring.sort_by_key(|member| member.global_index);
+        ring.dedup_by_key(|member| member.global_index);
+        if ring.len() != self.ring_size {
+            return Err(ValidatorError::Payout(format!(
+                "Ring for output {} has {} distinct members, expected {}",
+                input.output.global_index, ring.len(), self.ring_size
+            )));
+        }
+
+        let real_index = ring.iter().position(|member| member.global_index == input.output.global_index).unwrap();
+        let key_offsets = ring
+            .iter()
+            .scan(0u64, |previous, member| {
+                let offset = member.global_index - *previous;
+                *previous = member.global_index;
+                Some(offset)
+            })
+            .collect();
+
+        Ok(UnsignedInput {
+            ring,
+            key_offsets,
+            real_index,
+            pseudo_output: commit(input.output.amount, pseudo_mask).compress().to_bytes(),
+            commitment_key: (scalar_from(&input.output.mask)? - pseudo_mask).to_bytes(),
+            key_image: None,
+            clsag: None,
+        })
+    }
+}
+
+// One-time output for a standard address: P = Hs(8rA || i)G + B
+fn build_output(tx_key: &Scalar, index: u64, amount: u64, spend: &EdwardsPoint, view: &EdwardsPoint) -> (TxOutput, Scalar) {
+    let derivation = (tx_key * view).mul_by_cofactor().compress();
+    let shared = derivation_scalar(&derivation, index);
+
+    let mut view_tag = Keccak256::new();
+    view_tag.update(b"view_tag");
+    view_tag.update(derivation.as_bytes());
+    view_tag.update(varint(index));
+
+    let mask = hash_to_scalar(&[b"commitment_mask", shared.as_bytes()]);
+    let encrypted_amount = xor_amount(&shared, amount.to_le_bytes());
+
+    let output = TxOutput {
+        public_key: (&shared * ED25519_BASEPOINT_TABLE + spend).compress().to_bytes(),
+        view_tag: view_tag.finalize()[0],
+        commitment: commit(amount, &mask).compress().to_bytes(),
+        encrypted_amount,
+    };
+    (output, mask)
+}
+
+// The amount of the `index`th output, read with the sender's tx key
+fn decrypt_amount(tx_key: &Scalar, index: u64, view: &EdwardsPoint, encrypted_amount: &[u8; 8]) -> u64 {
+    let derivation = (tx_key * view).mul_by_cofactor().compress();
+    u64::from_le_bytes(xor_amount(&derivation_scalar(&derivation, index), *encrypted_amount))
+}
+
+fn xor_amount(shared: &Scalar, mut amount: [u8; 8]) -> [u8; 8] {
+    let amount_key = Keccak256::digest([b"amount".as_slice(), shared.as_bytes()].concat());
+    for (byte, key) in amount.iter_mut().zip(amount_key.iter()) {
+        *byte ^= key;
+    }
+    amount
+}
+
+// Mask of the `index`th output of a transaction the bridge wallet received,
+// recovered from the transaction public key with the private view key
+pub fn received_mask(view_secret: &Scalar, tx_public: &EdwardsPoint, index: u64) -> Scalar {
+    let derivation = (view_secret * tx_public).mul_by_cofactor().compress();
+    hash_to_scalar(&[b"commitment_mask", derivation_scalar(&derivation, index).as_bytes()])
+}
+
+pub fn amount_commitment(amount: u64, mask: &Scalar) -> [u8; 32] {
+    commit(amount, mask).compress().to_bytes()
+}
+
+fn derivation_scalar(derivation: &CompressedEdwardsY, index: u64) -> Scalar {
+    hash_to_scalar(&[derivation.as_bytes(), &varint(index)])
+}
+
+fn hash_to_scalar(parts: &[&[u8]]) -> Scalar {
+    let mut hasher = Keccak256::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    Scalar::from_bytes_mod_order(hasher.finalize().into())
+}
+
+fn random_scalar() -> Scalar {
+    let mut wide = [0u8; 64];
+    rand::Rng::fill(&mut rand::thread_rng(), &mut wide[..]);
+    Scalar::from_bytes_mod_order_wide(&wide)
+}
+
+fn generator_h() -> EdwardsPoint {
+    CompressedEdwardsY(H_BYTES).decompress().expect("H is a valid point")
+}
+
+// C = mask*G + amount*H
+fn commit(amount: u64, mask: &Scalar) -> EdwardsPoint {
+    mask * ED25519_BASEPOINT_TABLE + Scalar::from(amount) * generator_h()
+}
+
+fn scalar_from(bytes: &[u8; 32]) -> Result<Scalar> {
+    Option::from(Scalar::from_canonical_bytes(*bytes))
+        .ok_or_else(|| ValidatorError::Payout("Commitment mask is not a canonical scalar".to_string()))
+}
+
+fn varint(mut value: u64) -> Vec<u8> {
+    let mut out = Vec::new();
+    while value >= 0x80 {
+        out.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::subaddress::encode_address;
+    use curve25519_dalek::traits::Identity;
+
+    const STAGENET: u8 = 24;
+
+    fn wallet(seed: u64) -> (Scalar, EdwardsPoint, String) {
+        let view_secret = Scalar::from(seed);
+        let spend_public = &Scalar::from(seed + 1) * ED25519_BASEPOINT_TABLE;
+        let address = encode_address(STAGENET, &spend_public, &(&view_secret * ED25519_BASEPOINT_TABLE));
+        (view_secret, spend_public, address)
+    }
+
+    fn random_member(global_index: u64) -> RingMember {
+        let point = |seed: u64| (&Scalar::from(seed) * ED25519_BASEPOINT_TABLE).compress().to_bytes();
+        RingMember { global_index, public_key: point(global_index + 1000), commitment: point(global_index + 2000) }
+    }
+
+    fn spendable(global_index: u64, amount: u64, decoy_base: u64) -> SpendableInput {
+        SpendableInput {
+            output: OwnedOutput {
+                global_index,
+                public_key: (&Scalar::from(global_index) * ED25519_BASEPOINT_TABLE).compress().to_bytes(),
+                amount,
+                mask: Scalar::from(global_index + 7).to_bytes(),
+            },
+            decoys: (0..15).map(|i| random_member(decoy_base + i * 37)).collect(),
+        }
+    }
+
+    #[test]
+    fn test_builds_structurally_valid_payout() {
+        let (_, _, bridge) = wallet(3);
+        let (view_secret, spend_public, destination) = wallet(11);
+        let inputs = [spendable(5_000, 3_000_000_000_000, 100), spendable(9_000, 1_000_000_000_000, 20_000)];
+        let (amount, fee) = (2_500_000_000_000, 60_000_000);
+
+        let (tx, _) = PayoutBuilder::new(bridge).with_fee(fee).build(&destination, amount, &inputs).unwrap();
+
+        assert_eq!((tx.version, tx.rct_type, tx.fee), (2, RCT_TYPE_BULLETPROOF_PLUS, fee));
+        assert_eq!(tx.outputs.len(), 2);
+        assert_eq!(tx.inputs.len(), 2);
+        for (input, spent) in tx.inputs.iter().zip(&inputs) {
+            assert_eq!(input.ring.len(), DEFAULT_RING_SIZE);
+            assert_eq!(input.ring[input.real_index].global_index, spent.output.global_index);
+            assert_eq!(input.key_offsets.iter().sum::<u64>(), input.ring.last().unwrap().global_index);
+            assert!(input.key_image.is_none() && input.clsag.is_none());
+        }
+
+        // Commitments balance: sum(pseudo outputs) = sum(outputs) + fee*H
+        let decompress = |bytes: &[u8; 32]| CompressedEdwardsY(*bytes).decompress().unwrap();
+        let pseudo: EdwardsPoint = tx.inputs.iter().map(|i| decompress(&i.pseudo_output)).sum();
+        let outputs: EdwardsPoint = tx.outputs.iter().map(|o| decompress(&o.commitment)).sum();
+        assert_eq!(pseudo, outputs + Scalar::from(fee) * generator_h());
+        assert_ne!(pseudo, EdwardsPoint::identity());
+
+        // The recipient finds exactly one output paying them the amount
+        let tx_public = decompress(tx.extra[1..33].try_into().unwrap());
+        let derivation = (view_secret * tx_public).mul_by_cofactor().compress();
+        let received: Vec<u64> = tx.outputs.iter().enumerate().filter_map(|(index, output)| {
+            let shared = derivation_scalar(&derivation, index as u64);
+            let expected = (&shared * ED25519_BASEPOINT_TABLE + spend_public).compress().to_bytes();
+            (expected == output.public_key).then(|| {
+                let key = Keccak256::digest([b"amount".as_slice(), shared.as_bytes()].concat());
+                let mut decrypted = output.encrypted_amount;
+                decrypted.iter_mut().zip(key.iter()).for_each(|(b, k)| *b ^= k);
+                let value = u64::from_le_bytes(decrypted);
+                let mask = hash_to_scalar(&[b"commitment_mask", shared.as_bytes()]);
+                assert_eq!(commit(value, &mask).compress().to_bytes(), output.commitment);
+                value
+            })
+        }).collect();
+        assert_eq!(received, vec![amount]);
+    }
+
+    #[test]
+    fn test_signed_serialization_needs_every_signature() {
+        let (_, _, bridge) = wallet(3);
+        let (_, _, destination) = wallet(11);
+        let inputs = [spendable(5_000, 3_000_000_000_000, 100), spendable(9_000, 1_000_000_000_000, 20_000)];
+        let (mut tx, _) = PayoutBuilder::new(bridge).build(&destination, 1_000_000_000_000, &inputs).unwrap();
+        assert!(tx.signed().is_none());
+
+        tx.bulletproof_plus = Some(vec![9u8; 40]);
+        for (i, input) in tx.inputs.iter_mut().enumerate() {
+            input.key_image = Some([i as u8 + 1; 32]);
+        }
+        assert!(tx.signed().is_none());
+        for input in tx.inputs.iter_mut() {
+            input.clsag = Some(vec![5u8; 32 * (DEFAULT_RING_SIZE + 2)]);
+        }
+
+        let signed = tx.signed().unwrap();
+        assert_eq!(signed.key_images, vec![[1u8; 32], [2u8; 32]]);
+        assert!(signed.blob.starts_with(&tx.prefix_bytes()));
+        assert!(signed.blob.ends_with(&tx.inputs[1].pseudo_output));
+
+        // The txid commits to the signatures, not just the prefix
+        tx.inputs[0].clsag = Some(vec![6u8; 32 * (DEFAULT_RING_SIZE + 2)]);
+        assert_ne!(tx.signed().unwrap().txid, signed.txid);
+    }
+
+    #[test]
+    fn test_checks_a_proposed_payout() {
+        let (_, _, bridge) = wallet(3);
+        let (_, _, destination) = wallet(11);
+        let (_, _, other) = wallet(21);
+        let inputs = [spendable(5_000, 3_000_000_000_000, 100), spendable(9_000, 1_000_000_000_000, 20_000)];
+        let (amount, fee) = (2_500_000_000_000, 60_000_000);
+        let builder = PayoutBuilder::new(bridge).with_fee(fee);
+
+        let (tx, tx_key) = builder.build(&destination, amount, &inputs).unwrap();
+        builder.check(&tx, &tx_key, &destination, amount).unwrap();
+
+        // Wrong amount, wrong recipient, wrong fee or a different tx key
+        assert!(matches!(builder.check(&tx, &tx_key, &destination, amount - 1), Err(ValidatorError::Payout(_))));
+        assert!(builder.check(&tx, &tx_key, &other, amount).is_err());
+        assert!(builder.clone().with_fee(fee + 1).check(&tx, &tx_key, &destination, amount).is_err());
+        assert!(builder.check(&tx, &random_scalar(), &destination, amount).is_err());
+
+        // Change skimmed off to somebody else
+        let ((spend, view), (_, bridge_view)) = builder.recipients(&destination).unwrap();
+        let (_, other_spend, other_view) = decode_address(&other).unwrap();
+        let paid = (0..2).find(|&i| build_output(&tx_key, i as u64, amount, &spend, &view).0 == tx.outputs[i]).unwrap();
+        let change_index = 1 - paid;
+        let change = decrypt_amount(&tx_key, change_index as u64, &bridge_view, &tx.outputs[change_index].encrypted_amount);
+        assert_eq!(change, 4_000_000_000_000 - amount - fee);
+        let mut skimmed = tx.clone();
+        skimmed.outputs[change_index] = build_output(&tx_key, change_index as u64, change, &other_spend, &other_view).0;
+        assert!(builder.check(&skimmed, &tx_key, &destination, amount).is_err());
+
+        // Inputs that do not balance the outputs
+        let mut unbalanced = tx.clone();
+        unbalanced.inputs.pop();
+        assert!(builder.check(&unbalanced, &tx_key, &destination, amount).is_err());
+    }
+
+    #[test]
+    fn test_rejects_unbuildable_payouts() {
+        let (_, _, bridge) = wallet(3);
+        let (_, _, destination) = wallet(11);
+        let builder = PayoutBuilder::new(bridge.clone()).with_fee(1_000);
+
+        // Not enough funds
+        let inputs = [spendable(5_000, 1_000_000, 100)];
+        assert!(matches!(builder.build(&destination, 1_000_000, &inputs), Err(ValidatorError::Payout(_))));
+
+        // Short ring
+        let mut short = spendable(5_000, 10_000_000, 100);
+        short.decoys.truncate(10);
+        assert!(builder.build(&destination, 1_000_000, &[short]).is_err());
+
+        // Mainnet destination for a stagenet bridge
+        let spend = &Scalar::from(12u64) * ED25519_BASEPOINT_TABLE;
+        let mainnet = encode_address(18, &spend, &(&Scalar::from(11u64) * ED25519_BASEPOINT_TABLE));
+        assert!(builder.build(&mainnet, 1_000_000, &[spendable(5_000, 10_000_000, 100)]).is_err());
+    }
+}
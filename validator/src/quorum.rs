@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Result, ValidatorError};
+
+// How many of the committee must take part before the validators act: for
+// collecting consensus messages, deciding the committee is live, and signing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum QuorumPolicy {
+    // A fixed number of validators
+    Threshold { count: usize },
+    // At least this percentage of the committee, rounded up
+    Supermajority { percent: u8 },
+}
+
+impl QuorumPolicy {
+    pub fn required(&self, total: usize) -> usize {
+        match *self {
+            Self::Threshold { count } => count,
+            Self::Supermajority { percent } => (total * percent as usize).div_ceil(100),
+        }
+    }
+
+    pub fn is_met(&self, have: usize, total: usize) -> bool {
+        have >= self.required(total)
+    }
+
+    // A quorum must be reachable by the committee, and large enough that the
+    // participants hold at least `signing_threshold` shares between them
+    pub fn validate(&self, total: usize, signing_threshold: usize) -> Result<()> {
+        if let Self::Supermajority { percent } = *self {
+            if percent == 0 || percent > 100 {
+                return Err(ValidatorError::Config(format!("Supermajority of {}% is not a valid quorum", percent)));
+            }
+        }
+
+        let required = self.required(total);
+        if required == 0 || required > total {
+            return Err(ValidatorError::Config(format!(
+                "Quorum of {} can never be reached by {} validators",
+                required, total
+            )));
+        }
+        if required < signing_threshold {
+            return Err(ValidatorError::Config(format!(
+                "Quorum of {} is below the signing threshold of {}",
+                required, signing_threshold
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_threshold_and_supermajority_on_same_committee() {
+        let threshold = QuorumPolicy::Threshold { count: 4 };
+        let supermajority = QuorumPolicy::Supermajority { percent: 67 };
+
+        assert_eq!(threshold.required(7), 4);
+        assert_eq!(supermajority.required(7), 5);
+
+        assert!(threshold.is_met(4, 7));
+        assert!(!supermajority.is_met(4, 7));
+        assert!(supermajority.is_met(5, 7));
+
+        assert!(threshold.validate(7, 4).is_ok());
+        assert!(supermajority.validate(7, 4).is_ok());
+    }
+
+    #[test]
+    fn test_unsatisfiable_policies_rejected() {
+        let invalid = [
+            QuorumPolicy::Threshold { count: 8 },
+            QuorumPolicy::Threshold { count: 0 },
+            // 3 of 7 cannot produce a 4-of-7 signature
+            QuorumPolicy::Supermajority { percent: 40 },
+            QuorumPolicy::Supermajority { percent: 101 },
+        ];
+
+        for policy in invalid {
+            assert!(matches!(policy.validate(7, 4), Err(ValidatorError::Config(_))), "{:?}", policy);
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use crate::config::RateLimitConfig;
+
+// Token bucket per source: `burst` absorbs a signing round's worth of traffic,
+// while `per_second` bounds what a single source can sustain
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+struct Buckets {
+    by_source: HashMap<String, TokenBucket>,
+    last_sweep: Instant,
+}
+
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    buckets: Mutex<Buckets>,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            config,
+            buckets: Mutex::new(Buckets { by_source: HashMap::new(), last_sweep: Instant::now() }),
+        }
+    }
+
+    pub fn check(&self, source: &str) -> bool {
+        self.check_at(source, Instant::now())
+    }
+
+    fn check_at(&self, source: &str, now: Instant) -> bool {
+        let capacity = self.config.burst as f64;
+        let mut buckets = self.buckets.lock().unwrap();
+
+        // A bucket that has refilled is no different from a missing one, so
+        // sources that went quiet are dropped once a full refill has passed
+        let refill_time = capacity / self.config.per_second;
+        if now.saturating_duration_since(buckets.last_sweep).as_secs_f64() >= refill_time {
+            buckets.by_source.retain(|_, bucket| {
+                let elapsed = now.saturating_duration_since(bucket.last_refill).as_secs_f64();
+                bucket.tokens + elapsed * self.config.per_second < capacity
+            });
+            buckets.last_sweep = now;
+        }
+
+        let bucket = buckets.by_source.entry(source.to_string()).or_insert(TokenBucket {
+            tokens: capacity,
+            last_refill: now,
+        });
+
+        let elapsed = now.saturating_duration_since(bucket.last_refill).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.config.per_second).min(capacity);
+        bucket.last_refill = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_burst_then_refill() {
+        let limiter = RateLimiter::new(RateLimitConfig { burst: 3, per_second: 1.0 });
+        let start = Instant::now();
+
+        for _ in 0..3 {
+            assert!(limiter.check_at("validator:1", start));
+        }
+        assert!(!limiter.check_at("validator:1", start));
+
+        // Other sources have their own budget
+        assert!(limiter.check_at("validator:2", start));
+
+        assert!(limiter.check_at("validator:1", start + Duration::from_secs(1)));
+        assert!(!limiter.check_at("validator:1", start + Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn test_refilled_buckets_are_dropped() {
+        let limiter = RateLimiter::new(RateLimitConfig { burst: 2, per_second: 1.0 });
+        let start = Instant::now();
+
+        for peer in 0..100 {
+            assert!(limiter.check_at(&format!("addr:{}", peer), start));
+        }
+        assert!(limiter.check_at("addr:busy", start + Duration::from_millis(1500)));
+        assert!(limiter.check_at("addr:busy", start + Duration::from_millis(1500)));
+        assert_eq!(limiter.buckets.lock().unwrap().by_source.len(), 101);
+
+        // Two seconds refill every bucket but the one still in use
+        assert!(limiter.check_at("addr:new", start + Duration::from_secs(2)));
+        let mut sources: Vec<String> = limiter.buckets.lock().unwrap().by_source.keys().cloned().collect();
+        sources.sort();
+        assert_eq!(sources, ["addr:busy", "addr:new"]);
+    }
+}
@@ -0,0 +1,98 @@
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// Characters of a secret kept in logs so entries can still be correlated
+const VISIBLE_PREFIX: usize = 6;
+
+static LOG_SECRETS: AtomicBool = AtomicBool::new(false);
+
+// Only meant for local debugging; set once at startup from --log-secrets
+pub fn set_log_secrets(enabled: bool) {
+    LOG_SECRETS.store(enabled, Ordering::Relaxed);
+}
+
+// Wraps a hex string or byte slice so Display and Debug print only a prefix
+pub struct Redacted<'a>(&'a [u8]);
+
+pub fn redact<T: AsRef<[u8]> + ?Sized>(value: &T) -> Redacted<'_> {
+    Redacted(value.as_ref())
+}
+
+fn mask(value: &[u8], reveal: bool) -> String {
+    // Hex strings are shown as-is; raw bytes are hex encoded first
+    let text = match std::str::from_utf8(value) {
+        Ok(text) => text.to_string(),
+        Err(_) => hex::encode(value),
+    };
+
+    if reveal {
+        return text;
+    }
+    match text.get(..VISIBLE_PREFIX) {
+        Some(prefix) if text.len() > VISIBLE_PREFIX => format!("{}…[redacted]", prefix),
+        _ => "[redacted]".to_string(),
+    }
+}
+
+impl fmt::Display for Redacted<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&mask(self.0, LOG_SECRETS.load(Ordering::Relaxed)))
+    }
+}
+
+impl fmt::Debug for Redacted<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "\"{}\"", self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::sync::{Arc, Mutex};
+
+    use crate::validation::MoneroTransaction;
+
+    #[derive(Clone, Default)]
+    struct Capture(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Capture {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_tx_key_masked_in_log_line() {
+        let tx_key = "4b1d2c3e5f6a7b8c9d0e1f2a3b4c5d6e7f8091a2b3c4d5e6f708192a3b4c5d6e";
+        let tx = MoneroTransaction { tx_key: tx_key.to_string(), ..MoneroTransaction::mock() };
+
+        let capture = Capture::default();
+        let writer = capture.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::DEBUG)
+            .with_writer(move || writer.clone())
+            .finish();
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::debug!("Monero transaction: {:#?}", tx);
+            tracing::info!("Checking tx key {}", redact(&tx.tx_key));
+        });
+
+        let logged = String::from_utf8(capture.0.lock().unwrap().clone()).unwrap();
+        assert!(logged.contains("4b1d2c…[redacted]"));
+        assert!(!logged.contains(tx_key));
+    }
+
+    #[test]
+    fn test_mask_keeps_short_prefix() {
+        assert_eq!(mask(b"abcdef0123", false), "abcdef…[redacted]");
+        assert_eq!(mask(b"abc", false), "[redacted]");
+        assert_eq!(mask(&[0xde, 0xad, 0xbe, 0xef, 0xff], false), "deadbe…[redacted]");
+        assert_eq!(mask(b"abcdef0123", true), "abcdef0123");
+    }
+}
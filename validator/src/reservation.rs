@@ -0,0 +1,292 @@
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+use url::Url;
+
+use crate::error::{Result, ValidatorError};
+use crate::transport::{self, TransportKey};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Reservation {
+    pub owner: String,
+    pub reserved_at: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReserveRequest {
+    // Monero deposit txid the mint is for; the relay and validators must key on the same value
+    pub key: String,
+    // Hex transport public key of the submitter; it becomes the owner
+    pub signer: String,
+    pub timestamp: u64,
+    // Hex transport signature over the action, key and timestamp
+    pub signature: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReserveResponse {
+    pub reserved: bool,
+    pub owner: String,
+}
+
+// Signed requests older than this are refused, so they cannot be replayed later
+pub const REQUEST_MAX_AGE_SECS: u64 = 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReserveAction {
+    Reserve,
+    Release,
+}
+
+fn request_message(action: ReserveAction, key: &str, timestamp: u64) -> Vec<u8> {
+    let mut message = match action {
+        ReserveAction::Reserve => b"wxmr_reserve_".to_vec(),
+        ReserveAction::Release => b"wxmr_release_".to_vec(),
+    };
+    message.extend_from_slice(&timestamp.to_be_bytes());
+    message.extend_from_slice(key.to_lowercase().as_bytes());
+    message
+}
+
+fn now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+impl ReserveRequest {
+    pub fn signed(action: ReserveAction, key: &str, transport_key: &TransportKey) -> Self {
+        let timestamp = now();
+        Self {
+            key: key.to_string(),
+            signer: hex::encode(transport_key.public_key()),
+            timestamp,
+            signature: hex::encode(transport_key.sign_bytes(&request_message(action, key, timestamp))),
+        }
+    }
+
+    pub fn verify(&self, action: ReserveAction, now: u64) -> Result<()> {
+        if self.timestamp + REQUEST_MAX_AGE_SECS < now || self.timestamp > now + REQUEST_MAX_AGE_SECS {
+            return Err(ValidatorError::SignatureVerification(format!("Stale reservation request from {}", self.timestamp)));
+        }
+        transport::verify_bytes(
+            &request_message(action, &self.key, self.timestamp),
+            &hex::decode(&self.signature)?,
+            &hex::decode(&self.signer)?,
+        )
+    }
+}
+
+// First-come reservation of a mint, so the relay and the validators cannot
+// both submit one for the same deposit. Persisted like the signed operations
+// store so a restart does not reopen a reservation. A reservation lapses after
+// `ttl`, so a winner that crashed before submitting does not block the transfer.
+pub struct MintReservations {
+    store_path: PathBuf,
+    ttl: Duration,
+    reservations: Mutex<HashMap<String, Reservation>>,
+}
+
+impl MintReservations {
+    pub async fn open(store_path: impl Into<PathBuf>, ttl: Duration) -> Result<Self> {
+        let store_path = store_path.into();
+
+        let reservations = match tokio::fs::read_to_string(&store_path).await {
+            Ok(content) => serde_json::from_str(&content)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => HashMap::new(),
+            Err(e) => return Err(e.into()),
+        };
+
+        Ok(Self {
+            store_path,
+            ttl,
+            reservations: Mutex::new(reservations),
+        })
+    }
+
+    // Returns the owner holding the reservation; the caller won if it is itself.
+    // Re-reserving as the same owner is idempotent so retries are safe.
+    pub async fn reserve(&self, key: &str, owner: &str) -> Result<String> {
+        let key = key.to_lowercase();
+        let mut reservations = self.reservations.lock().await;
+        let now = now();
+
+        if let Some(existing) = reservations.get(&key) {
+            if existing.owner == owner || now < existing.reserved_at + self.ttl.as_secs() {
+                return Ok(existing.owner.clone());
+            }
+            warn!("Reservation of {} by {} lapsed, reassigning", key, existing.owner);
+        }
+
+        reservations.insert(key.clone(), Reservation {
+            owner: owner.to_string(),
+            reserved_at: now,
+        });
+        self.persist(&reservations).await?;
+        info!("Reserved mint for {} to {}", key, owner);
+
+        Ok(owner.to_string())
+    }
+
+    // Gives the transfer back after a failed submission; only its owner can
+    pub async fn release(&self, key: &str, owner: &str) -> Result<bool> {
+        let key = key.to_lowercase();
+        let mut reservations = self.reservations.lock().await;
+
+        if reservations.get(&key).map(|existing| existing.owner != owner).unwrap_or(true) {
+            return Ok(false);
+        }
+        reservations.remove(&key);
+        self.persist(&reservations).await?;
+        info!("Released reservation of {} held by {}", key, owner);
+
+        Ok(true)
+    }
+
+    async fn persist(&self, reservations: &HashMap<String, Reservation>) -> Result<()> {
+        let tmp_path = self.store_path.with_extension("json.tmp");
+        tokio::fs::write(&tmp_path, serde_json::to_string_pretty(reservations)?).await?;
+        tokio::fs::rename(&tmp_path, &self.store_path).await?;
+        Ok(())
+    }
+}
+
+// The validator configured as `validators.reservation_authority` hosts the
+// store; every other validator signs its requests to it with its transport key
+#[derive(Clone)]
+pub enum ReservationClient {
+    Local { store: Arc<MintReservations>, owner: String },
+    Remote { url: Url, client: reqwest::Client, transport_key: Arc<TransportKey> },
+}
+
+impl ReservationClient {
+    pub fn local(store: Arc<MintReservations>, transport_key: &TransportKey) -> Self {
+        Self::Local { store, owner: hex::encode(transport_key.public_key()) }
+    }
+
+    pub fn remote(url: Url, transport_key: Arc<TransportKey>) -> Self {
+        Self::Remote { url, client: reqwest::Client::new(), transport_key }
+    }
+
+    pub async fn reserve(&self, key: &str) -> Result<bool> {
+        match self {
+            Self::Local { store, owner } => Ok(store.reserve(key, owner).await? == *owner),
+            Self::Remote { url, client, transport_key } => {
+                let request = ReserveRequest::signed(ReserveAction::Reserve, key, transport_key);
+                let response: ReserveResponse = post(client, url, "reserve", &request).await?;
+                Ok(response.reserved && response.owner == request.signer)
+            }
+        }
+    }
+
+    pub async fn release(&self, key: &str) -> Result<()> {
+        match self {
+            Self::Local { store, owner } => store.release(key, owner).await.map(|_| ()),
+            Self::Remote { url, client, transport_key } => {
+                let request = ReserveRequest::signed(ReserveAction::Release, key, transport_key);
+                post::<serde_json::Value>(client, url, "release", &request).await.map(|_| ())
+            }
+        }
+    }
+}
+
+async fn post<T: DeserializeOwned>(client: &reqwest::Client, url: &Url, path: &str, request: &ReserveRequest) -> Result<T> {
+    let endpoint = url.join(path).map_err(|e| ValidatorError::Config(e.to_string()))?;
+    client
+        .post(endpoint)
+        .json(request)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|source| ValidatorError::PeerUnreachable { peer: url.to_string(), source })?
+        .json()
+        .await
+        .map_err(|source| ValidatorError::PeerUnreachable { peer: url.to_string(), source })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::NetworkState;
+    use crate::test_support::serve_network;
+
+    async fn serve(state: NetworkState) -> Url {
+        format!("{}/", serve_network(state).await).parse().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_relay_and_validator_exactly_one_wins() {
+        let dir = tempfile::tempdir().unwrap();
+        let ttl = Duration::from_secs(600);
+        let store = Arc::new(MintReservations::open(dir.path().join("reservations.json"), ttl).await.unwrap());
+
+        // The validator hosting the store reserves locally, the relay goes over HTTP
+        let (relay_key, validator_key) = (Arc::new(TransportKey::generate()), TransportKey::generate());
+        let url = serve(NetworkState::new(0, 0).with_reservations(store.clone(), vec![relay_key.public_key()])).await;
+
+        let txid = "ab".repeat(32);
+        let relay = ReservationClient::remote(url, relay_key.clone());
+        let validator = ReservationClient::local(store.clone(), &validator_key);
+
+        let (relay_won, validator_won) = tokio::join!(relay.reserve(&txid), validator.reserve(&txid));
+        let (relay_won, validator_won) = (relay_won.unwrap(), validator_won.unwrap());
+        assert!(relay_won ^ validator_won);
+
+        // Retrying as the winner still succeeds, and the outcome survives a restart
+        let (winner, loser) = if relay_won { (&relay, &validator) } else { (&validator, &relay) };
+        assert!(winner.reserve(&txid.to_uppercase()).await.unwrap());
+        assert!(!loser.reserve(&txid).await.unwrap());
+
+        let reopened = MintReservations::open(dir.path().join("reservations.json"), ttl).await.unwrap();
+        assert_ne!(reopened.reserve(&txid, "someone-else").await.unwrap(), "someone-else");
+
+        // A failed submitter hands the transfer back; only the loser's release is ignored
+        loser.release(&txid).await.unwrap();
+        assert!(!loser.reserve(&txid).await.unwrap());
+        winner.release(&txid).await.unwrap();
+        assert!(loser.reserve(&txid).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_reservation_requests_must_be_signed_by_a_known_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Arc::new(MintReservations::open(dir.path().join("reservations.json"), Duration::from_secs(600)).await.unwrap());
+        let url = serve(NetworkState::new(0, 0).with_reservations(store, vec![])).await;
+        let client = reqwest::Client::new();
+        let txid = "cd".repeat(32);
+
+        // A well-signed request from a key that is neither a member nor a client
+        let stranger = Arc::new(TransportKey::generate());
+        assert!(matches!(
+            ReservationClient::remote(url.clone(), stranger.clone()).reserve(&txid).await,
+            Err(ValidatorError::PeerUnreachable { .. })
+        ));
+
+        // Claiming someone else's key, or replaying an old request, is refused
+        let mut forged = ReserveRequest::signed(ReserveAction::Reserve, &txid, &stranger);
+        forged.signer = hex::encode(TransportKey::generate().public_key());
+        let response = client.post(url.join("reserve").unwrap()).json(&forged).send().await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+        let stale = ReserveRequest::signed(ReserveAction::Reserve, &txid, &stranger);
+        assert!(stale.verify(ReserveAction::Reserve, stale.timestamp).is_ok());
+        assert!(stale.verify(ReserveAction::Reserve, stale.timestamp + 2 * REQUEST_MAX_AGE_SECS).is_err());
+        // A reserve signature does not authorize a release
+        assert!(stale.verify(ReserveAction::Release, stale.timestamp).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_reservation_lapses_after_ttl() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = MintReservations::open(dir.path().join("reservations.json"), Duration::ZERO).await.unwrap();
+
+        assert_eq!(store.reserve("tx", "crashed").await.unwrap(), "crashed");
+        assert_eq!(store.reserve("tx", "next").await.unwrap(), "next");
+    }
+}
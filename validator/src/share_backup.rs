@@ -0,0 +1,345 @@
+use argon2::Argon2;
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::info;
+
+use crate::config::ShareBackupConfig;
+use crate::error::{Result, ValidatorError};
+use crate::keygen::ValidatorKeys;
+
+const BACKUP_VERSION: u32 = 1;
+const BACKUP_DOMAIN_TAG: &[u8] = b"wxmr_share_backup_v1";
+
+// What leaves the host: the key file sealed with XChaCha20-Poly1305 under an
+// Argon2id key from the operator's passphrase. The validator id is bound as
+// associated data so one validator's backup cannot be restored as another's.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EncryptedShare {
+    pub version: u32,
+    pub validator_id: usize,
+    pub salt: String,
+    pub nonce: String,
+    pub ciphertext: String,
+}
+
+fn associated_data(validator_id: usize) -> Vec<u8> {
+    let mut aad = BACKUP_DOMAIN_TAG.to_vec();
+    aad.extend_from_slice(&(validator_id as u64).to_be_bytes());
+    aad
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<XChaCha20Poly1305> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| ValidatorError::KeyMaterial(format!("backup key derivation failed: {}", e)))?;
+    Ok(XChaCha20Poly1305::new(&key.into()))
+}
+
+pub fn encrypt_keys(keys: &ValidatorKeys, passphrase: &str) -> Result<EncryptedShare> {
+    let salt: [u8; 16] = rand::random();
+    let nonce: [u8; 24] = rand::random();
+    let plaintext = serde_json::to_vec(keys)?;
+
+    let ciphertext = derive_key(passphrase, &salt)?
+        .encrypt(&XNonce::from(nonce), Payload { msg: &plaintext, aad: &associated_data(keys.validator_id) })
+        .map_err(|_| ValidatorError::KeyMaterial("failed to encrypt share backup".to_string()))?;
+
+    Ok(EncryptedShare {
+        version: BACKUP_VERSION,
+        validator_id: keys.validator_id,
+        salt: hex::encode(salt),
+        nonce: hex::encode(nonce),
+        ciphertext: hex::encode(ciphertext),
+    })
+}
+
+pub fn decrypt_keys(backup: &EncryptedShare, passphrase: &str) -> Result<ValidatorKeys> {
+    if backup.version != BACKUP_VERSION {
+        return Err(ValidatorError::Backup(format!("unsupported backup version {}", backup.version)));
+    }
+    let nonce: [u8; 24] = hex::decode(&backup.nonce)?
+        .try_into()
+        .map_err(|_| ValidatorError::Backup("malformed backup nonce".to_string()))?;
+
+    let plaintext = derive_key(passphrase, &hex::decode(&backup.salt)?)?
+        .decrypt(
+            &XNonce::from(nonce),
+            Payload { msg: &hex::decode(&backup.ciphertext)?, aad: &associated_data(backup.validator_id) },
+        )
+        .map_err(|_| ValidatorError::KeyMaterial("cannot decrypt share backup: wrong passphrase or corrupted blob".to_string()))?;
+
+    let keys: ValidatorKeys = serde_json::from_slice(&plaintext)?;
+    if keys.validator_id != backup.validator_id {
+        return Err(ValidatorError::KeyMaterial(format!(
+            "backup labelled validator {} holds the keys of validator {}",
+            backup.validator_id, keys.validator_id
+        )));
+    }
+    Ok(keys)
+}
+
+// A place encrypted backups can be written to and read back from. Stores keep
+// their own version history; `put` returns the version it created.
+#[async_trait::async_trait]
+pub trait SecretStore: Send + Sync {
+    async fn put(&self, path: &str, backup: &EncryptedShare) -> Result<u64>;
+    async fn get(&self, path: &str, version: Option<u64>) -> Result<(EncryptedShare, u64)>;
+}
+
+pub struct VaultStore {
+    client: reqwest::Client,
+    base_url: url::Url,
+    mount: String,
+    token: String,
+}
+
+impl VaultStore {
+    pub fn new(base_url: url::Url, mount: impl Into<String>, token: impl Into<String>) -> Self {
+        Self { client: reqwest::Client::new(), base_url, mount: mount.into(), token: token.into() }
+    }
+
+    fn data_url(&self, path: &str) -> Result<url::Url> {
+        self.base_url
+            .join(&format!("v1/{}/data/{}", self.mount, path))
+            .map_err(|e| ValidatorError::Config(format!("invalid vault path {}: {}", path, e)))
+    }
+
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<Value> {
+        let response = request
+            .header("X-Vault-Token", &self.token)
+            .send()
+            .await
+            .map_err(|source| ValidatorError::PeerUnreachable { peer: self.base_url.to_string(), source })?;
+
+        let status = response.status();
+        if !status.is_success() {
+            return Err(ValidatorError::Backup(format!("vault returned {}", status)));
+        }
+        response.json().await.map_err(|e| ValidatorError::Backup(format!("unreadable vault response: {}", e)))
+    }
+}
+
+#[async_trait::async_trait]
+impl SecretStore for VaultStore {
+    async fn put(&self, path: &str, backup: &EncryptedShare) -> Result<u64> {
+        let body = serde_json::json!({ "data": backup });
+        let response = self.send(self.client.post(self.data_url(path)?).json(&body)).await?;
+
+        response["data"]["version"]
+            .as_u64()
+            .ok_or_else(|| ValidatorError::Backup("vault response has no version".to_string()))
+    }
+
+    async fn get(&self, path: &str, version: Option<u64>) -> Result<(EncryptedShare, u64)> {
+        let mut url = self.data_url(path)?;
+        if let Some(version) = version {
+            url.query_pairs_mut().append_pair("version", &version.to_string());
+        }
+        let response = self.send(self.client.get(url)).await?;
+
+        let version = response["data"]["metadata"]["version"]
+            .as_u64()
+            .ok_or_else(|| ValidatorError::Backup("vault response has no version".to_string()))?;
+        Ok((serde_json::from_value(response["data"]["data"].clone())?, version))
+    }
+}
+
+pub struct ShareBackup {
+    store: Box<dyn SecretStore>,
+    path_prefix: String,
+    passphrase: String,
+}
+
+impl ShareBackup {
+    pub fn new(store: Box<dyn SecretStore>, path_prefix: impl Into<String>, passphrase: impl Into<String>) -> Self {
+        Self { store, path_prefix: path_prefix.into(), passphrase: passphrase.into() }
+    }
+
+    // Fails up front if the token or passphrase is missing, before any keys are generated
+    pub fn from_config(config: &ShareBackupConfig) -> Result<Self> {
+        let env = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .filter(|value| !value.is_empty())
+                .ok_or_else(|| ValidatorError::Config(format!("share backup is enabled but {} is not set", name)))
+        };
+        let store = VaultStore::new(config.vault_url.clone(), &config.mount, env(&config.token_env)?);
+        Ok(Self::new(Box::new(store), &config.path_prefix, env(&config.passphrase_env)?))
+    }
+
+    fn path(&self, validator_id: usize) -> String {
+        format!("{}/{}", self.path_prefix.trim_end_matches('/'), validator_id)
+    }
+
+    pub async fn upload(&self, keys: &ValidatorKeys) -> Result<u64> {
+        let backup = encrypt_keys(keys, &self.passphrase)?;
+        let path = self.path(keys.validator_id);
+        let version = self.store.put(&path, &backup).await?;
+        info!("Backed up encrypted keys for validator {} to {} (version {})", keys.validator_id, path, version);
+        Ok(version)
+    }
+
+    // Latest version unless one is given
+    pub async fn restore(&self, validator_id: usize, version: Option<u64>) -> Result<ValidatorKeys> {
+        let path = self.path(validator_id);
+        let (backup, version) = self.store.get(&path, version).await?;
+        if backup.validator_id != validator_id {
+            return Err(ValidatorError::KeyMaterial(format!(
+                "backup at {} belongs to validator {}",
+                path, backup.validator_id
+            )));
+        }
+        info!("Restoring keys for validator {} from {} (version {})", validator_id, path, version);
+        decrypt_keys(&backup, &self.passphrase)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::keygen::{key_file_path, load_validator_keys, KeygenCoordinator};
+    use axum::extract::{Path, Query, State};
+    use axum::http::{HeaderMap, StatusCode};
+    use axum::routing::get;
+    use axum::{Json, Router};
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    type Versions = Arc<Mutex<HashMap<String, Vec<Value>>>>;
+
+    const TOKEN: &str = "test-token";
+
+    fn authorized(headers: &HeaderMap) -> bool {
+        headers.get("X-Vault-Token").and_then(|v| v.to_str().ok()) == Some(TOKEN)
+    }
+
+    // Just enough of the KV v2 data endpoint: versioned writes and reads
+    async fn mock_vault() -> (url::Url, Versions) {
+        async fn write(
+            State(store): State<Versions>,
+            Path((_, path)): Path<(String, String)>,
+            headers: HeaderMap,
+            Json(body): Json<Value>,
+        ) -> std::result::Result<Json<Value>, StatusCode> {
+            if !authorized(&headers) {
+                return Err(StatusCode::FORBIDDEN);
+            }
+            let mut store = store.lock().unwrap();
+            let versions = store.entry(path).or_default();
+            versions.push(body["data"].clone());
+            Ok(Json(serde_json::json!({ "data": { "version": versions.len() } })))
+        }
+
+        async fn read(
+            State(store): State<Versions>,
+            Path((_, path)): Path<(String, String)>,
+            Query(query): Query<HashMap<String, u64>>,
+            headers: HeaderMap,
+        ) -> std::result::Result<Json<Value>, StatusCode> {
+            if !authorized(&headers) {
+                return Err(StatusCode::FORBIDDEN);
+            }
+            let store = store.lock().unwrap();
+            let versions = store.get(&path).ok_or(StatusCode::NOT_FOUND)?;
+            let version = query.get("version").copied().unwrap_or(versions.len() as u64);
+            let data = versions.get((version as usize).wrapping_sub(1)).ok_or(StatusCode::NOT_FOUND)?;
+            Ok(Json(serde_json::json!({ "data": { "data": data, "metadata": { "version": version } } })))
+        }
+
+        let versions = Versions::default();
+        let app = Router::new()
+            .route("/v1/:mount/data/*path", get(read).post(write))
+            .with_state(versions.clone());
+        (format!("{}/", crate::test_support::serve(app).await).parse().unwrap(), versions)
+    }
+
+    fn test_config(dir: &std::path::Path) -> Config {
+        let mut config: Config = toml::from_str(include_str!("../config.toml")).unwrap();
+        config.mpc.key_gen_output_path = dir.to_string_lossy().into_owned();
+        config
+    }
+
+    fn backup(url: &url::Url, token: &str, passphrase: &str) -> ShareBackup {
+        ShareBackup::new(Box::new(VaultStore::new(url.clone(), "secret", token)), "wxmr/validators", passphrase)
+    }
+
+    #[tokio::test]
+    async fn test_backup_then_restore_reproduces_share() {
+        let (url, versions) = mock_vault().await;
+        let dir = tempfile::tempdir().unwrap();
+        let config = test_config(dir.path());
+
+        let coordinator = KeygenCoordinator::new(config.clone(), 3, false)
+            .await
+            .unwrap()
+            .with_share_backup(backup(&url, TOKEN, "correct horse battery staple"));
+        coordinator.run(3).await.unwrap();
+        let key_file = key_file_path(&config.mpc.key_gen_output_path, 3, 4);
+        let original = std::fs::read_to_string(&key_file).unwrap();
+        let original_keys = load_validator_keys(&config, 3).await.unwrap();
+
+        // Only ciphertext reaches the store
+        let stored = serde_json::to_string(&versions.lock().unwrap()["wxmr/validators/3"]).unwrap();
+        assert!(!stored.contains(&hex::encode(&original_keys.key_share.eth_private_share)));
+        assert!(!stored.contains("eth_private_share"));
+        assert!(!stored.contains(&original_keys.addresses.eth_address));
+
+        // The disk is lost; restoring writes the identical key file back
+        std::fs::remove_dir_all(dir.path().join("3")).unwrap();
+        KeygenCoordinator::new(config.clone(), 3, false)
+            .await
+            .unwrap()
+            .with_share_backup(backup(&url, TOKEN, "correct horse battery staple"))
+            .restore(3, None)
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read_to_string(&key_file).unwrap(), original);
+
+        // Older versions stay retrievable after a re-run
+        KeygenCoordinator::new(config.clone(), 3, true)
+            .await
+            .unwrap()
+            .with_share_backup(backup(&url, TOKEN, "correct horse battery staple"))
+            .run(3)
+            .await
+            .unwrap();
+        let first = backup(&url, TOKEN, "correct horse battery staple").restore(3, Some(1)).await.unwrap();
+        let latest = backup(&url, TOKEN, "correct horse battery staple").restore(3, None).await.unwrap();
+        assert_eq!(first.key_share.eth_private_share, original_keys.key_share.eth_private_share);
+        assert_ne!(latest.key_share.eth_private_share, original_keys.key_share.eth_private_share);
+    }
+
+    #[tokio::test]
+    async fn test_restore_rejects_wrong_passphrase_and_foreign_backups() {
+        let (url, _) = mock_vault().await;
+        let dir = tempfile::tempdir().unwrap();
+        let config = test_config(dir.path());
+
+        KeygenCoordinator::new(config.clone(), 1, false)
+            .await
+            .unwrap()
+            .with_share_backup(backup(&url, TOKEN, "passphrase one"))
+            .run(1)
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            backup(&url, TOKEN, "passphrase two").restore(1, None).await,
+            Err(ValidatorError::KeyMaterial(_))
+        ));
+        assert!(matches!(
+            backup(&url, "wrong-token", "passphrase one").restore(1, None).await,
+            Err(ValidatorError::Backup(_))
+        ));
+
+        // Relabelling a blob for another validator breaks its authentication
+        let keys = load_validator_keys(&config, 1).await.unwrap();
+        let mut relabelled = encrypt_keys(&keys, "passphrase one").unwrap();
+        relabelled.validator_id = 2;
+        assert!(decrypt_keys(&relabelled, "passphrase one").is_err());
+    }
+}
@@ -1,19 +1,284 @@
 use serde::{Deserialize, Serialize};
-use anyhow::Result;
+use crate::error::{Result, ValidatorError};
+use crate::event_cursor::BurnEvent;
+use crate::frost;
+use crate::payout::UnsignedTransaction;
+use crate::redact::redact;
+use crate::subaddress::decode_address;
+use k256::ecdsa::{RecoveryId, Signature, VerifyingKey};
+use k256::ecdsa::signature::hazmat::PrehashVerifier;
+use sha2::{Sha256, Digest};
+use sha3::Keccak256;
 use std::collections::HashMap;
+use std::fmt;
+use std::path::PathBuf;
+use tokio::sync::Mutex;
+use tracing::info;
+
+// Mint: XMR deposited, WXMR minted. Burn: WXMR burned, XMR paid out.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    #[default]
+    Mint,
+    Burn,
+}
 
 // Mock signing structures for demonstration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct SigningRequest {
+    #[serde(default)]
+    pub direction: Direction,
     pub tx_secret: Vec<u8>,
     pub amount: u64,
     pub operation_hash: [u8; 32],
     pub timestamp: u64,
     pub nonce: [u8; 32],
-    pub monero_tx: super::validation::MoneroTransaction,
+    // The deposit backing a mint
+    #[serde(default)]
+    pub monero_tx: Option<super::validation::MoneroTransaction>,
+    // The release backing a burn
+    #[serde(default)]
+    pub payout: Option<PayoutOperation>,
+}
+
+impl SigningRequest {
+    pub fn for_payout(event: &BurnEvent, timestamp: u64) -> Result<Self> {
+        // Refuse to sign a payout that could never be sent
+        decode_address(&event.monero_address)?;
+
+        let mut payout = PayoutOperation {
+            burn_tx_hash: event.tx_hash.to_lowercase(),
+            log_index: event.log_index,
+            amount: event.amount,
+            monero_address: event.monero_address.clone(),
+            nonce: [0u8; 32],
+            transaction: None,
+            prefix_approval: None,
+        };
+        payout.nonce = payout.derive_nonce();
+        let nonce = payout.nonce;
+
+        Ok(Self {
+            direction: Direction::Burn,
+            tx_secret: vec![],
+            amount: event.amount,
+            operation_hash: payout.operation_hash(),
+            timestamp,
+            nonce,
+            monero_tx: None,
+            payout: Some(payout),
+        })
+    }
+
+    // Identifies the bridge transfer for mint reservations: the Monero
+    // deposit for mints, the burn log for payouts
+    pub fn transfer_id(&self) -> String {
+        match (self.direction, &self.monero_tx, &self.payout) {
+            (Direction::Burn, _, Some(payout)) => format!("burn:{}:{}", payout.burn_tx_hash, payout.log_index),
+            (_, Some(tx), _) => tx.txid.clone(),
+            _ => hex::encode(self.operation_hash),
+        }
+    }
+}
+
+impl fmt::Debug for SigningRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SigningRequest")
+            .field("direction", &self.direction)
+            .field("tx_secret", &redact(&self.tx_secret))
+            .field("amount", &self.amount)
+            .field("operation_hash", &hex::encode(self.operation_hash))
+            .field("timestamp", &self.timestamp)
+            .field("nonce", &hex::encode(self.nonce))
+            .field("monero_tx", &self.monero_tx)
+            .field("payout", &self.payout)
+            .finish()
+    }
+}
+
+// Domain tag binding operation hashes to this bridge and message version
+pub const OPERATION_DOMAIN_TAG: &[u8] = b"wxmr_bridge_mint_v1";
+
+// Domain tag for nonces derived from the transfer they authorize
+pub const NONCE_DOMAIN_TAG: &[u8] = b"wxmr_bridge_nonce_v1";
+
+// Every field that identifies a single mint on a single chain
+#[derive(Debug, Clone)]
+pub struct MintOperation {
+    pub txid: String,
+    pub amount: u64,
+    pub destination: String,
+    pub nonce: [u8; 32],
+    pub chain_id: u64,
+    pub contract_address: String,
+}
+
+impl MintOperation {
+    pub fn new(txid: String, amount: u64, destination: String, chain_id: u64, contract_address: String) -> Self {
+        let mut operation = Self { txid, amount, destination, nonce: [0u8; 32], chain_id, contract_address };
+        operation.nonce = operation.derive_nonce();
+        operation
+    }
+
+    // Every validator, and every re-poll of the same deposit, has to arrive at
+    // the same operation hash, so the nonce comes from the mint itself
+    pub fn derive_nonce(&self) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(NONCE_DOMAIN_TAG);
+        hasher.update(self.domain_separator());
+        update_with_length(&mut hasher, self.txid.to_lowercase().as_bytes());
+        update_with_length(&mut hasher, self.destination.to_lowercase().as_bytes());
+        hasher.update(self.amount.to_be_bytes());
+        hasher.finalize().into()
+    }
+
+    pub fn domain_separator(&self) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(OPERATION_DOMAIN_TAG);
+        hasher.update(self.chain_id.to_be_bytes());
+        update_with_length(&mut hasher, self.contract_address.to_lowercase().as_bytes());
+        hasher.finalize().into()
+    }
+    
+    // Hex fields are hashed lowercased, as in the nonce, so a txid or
+    // destination reported in a different case is still the same mint
+    pub fn struct_hash(&self) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        update_with_length(&mut hasher, self.txid.to_lowercase().as_bytes());
+        hasher.update(self.amount.to_be_bytes());
+        update_with_length(&mut hasher, self.destination.to_lowercase().as_bytes());
+        hasher.update(self.nonce);
+        hasher.finalize().into()
+    }
+    
+    // Mirrors the EIP-712 layout: 0x19 0x01 || domainSeparator || structHash
+    pub fn operation_hash(&self) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update([0x19, 0x01]);
+        hasher.update(self.domain_separator());
+        hasher.update(self.struct_hash());
+        hasher.finalize().into()
+    }
+}
+
+// EIP-712 domain of the WrappedMonero contract; must match its EIP712 constructor
+pub const EIP712_DOMAIN_NAME: &str = "Wrapped Monero";
+pub const EIP712_DOMAIN_VERSION: &str = "1";
+
+const EIP712_DOMAIN_TYPE: &[u8] = b"EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)";
+const MINT_AUTHORIZATION_TYPE: &[u8] = b"MintAuthorization(bytes32 txId,address receiver,uint64 amount,bytes32 nonce)";
+
+impl MintOperation {
+    // The digest Solidity's _hashTypedDataV4 produces for the same
+    // MintAuthorization, so the joint signature can be checked with ecrecover
+    pub fn typed_data_hash(&self) -> Result<[u8; 32]> {
+        let domain = eip712_domain_separator(
+            EIP712_DOMAIN_NAME,
+            EIP712_DOMAIN_VERSION,
+            self.chain_id,
+            &parse_address(&self.contract_address)?,
+        );
+        Ok(eip712_digest(&domain, &self.typed_struct_hash()?))
+    }
+
+    fn typed_struct_hash(&self) -> Result<[u8; 32]> {
+        let tx_id: [u8; 32] = hex::decode(self.txid.trim_start_matches("0x"))?
+            .try_into()
+            .map_err(|_| ValidatorError::Config(format!("Monero txid {} is not 32 bytes", self.txid)))?;
+
+        let mut encoded = Keccak256::digest(MINT_AUTHORIZATION_TYPE).to_vec();
+        encoded.extend_from_slice(&tx_id);
+        encoded.extend_from_slice(&abi_word(&parse_address(&self.destination)?));
+        encoded.extend_from_slice(&abi_word(&self.amount.to_be_bytes()));
+        encoded.extend_from_slice(&self.nonce);
+        Ok(Keccak256::digest(&encoded).into())
+    }
+}
+
+pub fn eip712_domain_separator(name: &str, version: &str, chain_id: u64, verifying_contract: &[u8; 20]) -> [u8; 32] {
+    let mut encoded = Keccak256::digest(EIP712_DOMAIN_TYPE).to_vec();
+    encoded.extend_from_slice(&Keccak256::digest(name.as_bytes()));
+    encoded.extend_from_slice(&Keccak256::digest(version.as_bytes()));
+    encoded.extend_from_slice(&abi_word(&chain_id.to_be_bytes()));
+    encoded.extend_from_slice(&abi_word(verifying_contract));
+    Keccak256::digest(&encoded).into()
 }
 
+// keccak256(0x19 0x01 || domainSeparator || structHash)
+pub fn eip712_digest(domain_separator: &[u8; 32], struct_hash: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Keccak256::new();
+    hasher.update([0x19, 0x01]);
+    hasher.update(domain_separator);
+    hasher.update(struct_hash);
+    hasher.finalize().into()
+}
+
+// Left-pads a static value to a 32 byte ABI word
+fn abi_word(bytes: &[u8]) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    word[32 - bytes.len()..].copy_from_slice(bytes);
+    word
+}
+
+fn parse_address(address: &str) -> Result<[u8; 20]> {
+    hex::decode(address.trim_start_matches("0x"))?
+        .try_into()
+        .map_err(|_| ValidatorError::Config(format!("Invalid EVM address {}", address)))
+}
+
+// Domain tag for payouts, so a payout hash can never collide with a mint's
+pub const PAYOUT_DOMAIN_TAG: &[u8] = b"wxmr_bridge_payout_v1";
+
+// Every field that identifies a single release of XMR for a burn
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PayoutOperation {
+    pub burn_tx_hash: String,
+    pub log_index: u64,
+    pub amount: u64,
+    pub monero_address: String,
+    pub nonce: [u8; 32],
+    // Unsigned release transaction, once the bridge wallet's inputs are known
+    #[serde(default)]
+    pub transaction: Option<UnsignedTransaction>,
+    // FROST approval of the transaction prefix under the joint spend key;
+    // not a substitute for the per-input CLSAGs a broadcast needs
+    #[serde(default)]
+    pub prefix_approval: Option<frost::Signature>,
+}
+
+impl PayoutOperation {
+    // A burn log is paid out once, so it fixes the nonce like a deposit does for mints
+    pub fn derive_nonce(&self) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(NONCE_DOMAIN_TAG);
+        hasher.update(PAYOUT_DOMAIN_TAG);
+        update_with_length(&mut hasher, self.burn_tx_hash.as_bytes());
+        hasher.update(self.log_index.to_be_bytes());
+        hasher.update(self.amount.to_be_bytes());
+        update_with_length(&mut hasher, self.monero_address.as_bytes());
+        hasher.finalize().into()
+    }
+
+    pub fn operation_hash(&self) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(PAYOUT_DOMAIN_TAG);
+        update_with_length(&mut hasher, self.burn_tx_hash.as_bytes());
+        hasher.update(self.log_index.to_be_bytes());
+        hasher.update(self.amount.to_be_bytes());
+        update_with_length(&mut hasher, self.monero_address.as_bytes());
+        hasher.update(self.nonce);
+        hasher.finalize().into()
+    }
+}
+
+// Length prefixes keep variable-size fields from sliding into each other
+fn update_with_length(hasher: &mut Sha256, bytes: &[u8]) {
+    hasher.update((bytes.len() as u64).to_be_bytes());
+    hasher.update(bytes);
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SigningResult {
     pub r: [u8; 32],
     pub s: [u8; 32],
@@ -21,28 +286,330 @@ pub struct SigningResult {
     pub validator_id: usize,
 }
 
-// Placeholder structures for demonstration
-#[derive(Debug, Serialize, Deserialize)]
-struct Keys; // Placeholder
+// Checks a combined signature against the joint key before it is submitted
+// on-chain. v must also recover to the joint key, since that is what the
+// contract's ecrecover checks.
+pub fn verify_threshold_signature(
+    operation_hash: &[u8; 32],
+    signature: &SigningResult,
+    joint_public_key: &[u8],
+) -> bool {
+    let Ok(joint_key) = VerifyingKey::from_sec1_bytes(joint_public_key) else {
+        return false;
+    };
+    let Ok(sig) = Signature::from_scalars(signature.r, signature.s) else {
+        return false;
+    };
+    
+    if joint_key.verify_prehash(operation_hash, &sig).is_err() {
+        return false;
+    }
+    
+    let Some(recovery_id) = signature.v.checked_sub(27).and_then(RecoveryId::from_byte) else {
+        return false;
+    };
+    VerifyingKey::recover_from_prehash(operation_hash, &sig, recovery_id)
+        .map(|recovered| recovered == joint_key)
+        .unwrap_or(false)
+}
 
 pub struct SigningCoordinator {
-    // Placeholder for signing coordinator
+    validator_id: usize,
+    store_path: PathBuf,
+    // Completed signatures keyed by hex operation hash, persisted so a
+    // restart cannot be used to re-authorize the same mint
+    completed: Mutex<HashMap<String, SigningResult>>,
 }
 
 impl SigningCoordinator {
-    pub fn new() -> Self {
-        SigningCoordinator {}
+    pub async fn open(validator_id: usize, store_path: impl Into<PathBuf>) -> Result<Self> {
+        let store_path = store_path.into();
+        
+        let completed = match tokio::fs::read_to_string(&store_path).await {
+            Ok(content) => serde_json::from_str(&content)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => HashMap::new(),
+            Err(e) => return Err(e.into()),
+        };
+        
+        Ok(Self {
+            validator_id,
+            store_path,
+            completed: Mutex::new(completed),
+        })
     }
     
     pub async fn sign_operation(&self, request: SigningRequest) -> Result<SigningResult> {
+        let key = hex::encode(request.operation_hash);
+        let mut completed = self.completed.lock().await;
+        
+        if let Some(existing) = completed.get(&key) {
+            info!("Operation {} already signed, returning existing signature", key);
+            return Ok(existing.clone());
+        }
+        
         // Mock signing implementation
         let result = SigningResult {
             r: rand::random(),
             s: rand::random(),
             v: 27,
-            validator_id: 1, // Placeholder
+            validator_id: self.validator_id,
         };
         
+        completed.insert(key, result.clone());
+        self.persist(&completed).await?;
+        
         Ok(result)
     }
-}
\ No newline at end of file
+    
+    async fn persist(&self, completed: &HashMap<String, SigningResult>) -> Result<()> {
+        let tmp_path = self.store_path.with_extension("json.tmp");
+        tokio::fs::write(&tmp_path, serde_json::to_string_pretty(completed)?).await?;
+        tokio::fs::rename(&tmp_path, &self.store_path).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn operation() -> MintOperation {
+        MintOperation {
+            txid: "a1b2c3".to_string(),
+            amount: 1_000_000_000_000,
+            destination: "0x00000000000000000000000000000000000000aa".to_string(),
+            nonce: [7u8; 32],
+            chain_id: 11155111,
+            contract_address: "0x34c209a799b47A4ba5753E17A1Dbf2F5a612fd23".to_string(),
+        }
+    }
+    
+    #[test]
+    fn test_operation_hash_is_deterministic() {
+        assert_eq!(operation().operation_hash(), operation().operation_hash());
+    }
+    
+    #[test]
+    fn test_same_mint_gets_the_same_nonce() {
+        let mint = || MintOperation::new(
+            "a1b2c3".to_string(),
+            1_000_000_000_000,
+            "0x00000000000000000000000000000000000000aa".to_string(),
+            11155111,
+            "0x34c209a799b47A4ba5753E17A1Dbf2F5a612fd23".to_string(),
+        );
+        assert_eq!(mint().nonce, mint().nonce);
+        assert_eq!(mint().operation_hash(), mint().operation_hash());
+        
+        // A txid or destination reported in another case is the same mint
+        let mut shouted = mint();
+        shouted.txid = shouted.txid.to_uppercase();
+        shouted.destination = shouted.destination.to_uppercase();
+        shouted.nonce = shouted.derive_nonce();
+        assert_eq!(shouted.operation_hash(), mint().operation_hash());
+        
+        let mut other = mint();
+        other.amount += 1;
+        assert_ne!(other.derive_nonce(), mint().nonce);
+        let mut other = mint();
+        other.chain_id = 1;
+        assert_ne!(other.derive_nonce(), mint().nonce);
+    }
+    
+    #[test]
+    fn test_operation_hash_binds_every_field() {
+        let base = operation().operation_hash();
+        
+        let variants: [fn(&mut MintOperation); 6] = [
+            |op| op.txid.push('0'),
+            |op| op.amount += 1,
+            |op| op.destination = "0x00000000000000000000000000000000000000bb".to_string(),
+            |op| op.nonce[0] ^= 1,
+            |op| op.chain_id = 1,
+            |op| op.contract_address = "0x0000000000000000000000000000000000000001".to_string(),
+        ];
+        
+        for mutate in variants {
+            let mut op = operation();
+            mutate(&mut op);
+            assert_ne!(op.operation_hash(), base);
+        }
+    }
+    
+    #[test]
+    fn test_operation_hash_fields_do_not_alias() {
+        let mut shifted = operation();
+        shifted.txid = "a1b2c30x".to_string();
+        shifted.destination = "00000000000000000000000000000000000000aa".to_string();
+        
+        assert_ne!(shifted.operation_hash(), operation().operation_hash());
+    }
+    
+    // The Mail example from the EIP-712 specification
+    #[test]
+    fn test_eip712_reference_vector() {
+        let contract: [u8; 20] = hex::decode("CcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC").unwrap().try_into().unwrap();
+        let domain = eip712_domain_separator("Ether Mail", "1", 1, &contract);
+        assert_eq!(hex::encode(domain), "f2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f");
+
+        let person_type = Keccak256::digest(b"Person(string name,address wallet)");
+        let person = |name: &str, wallet: &str| -> [u8; 32] {
+            let mut encoded = person_type.to_vec();
+            encoded.extend_from_slice(&Keccak256::digest(name.as_bytes()));
+            encoded.extend_from_slice(&abi_word(&parse_address(wallet).unwrap()));
+            Keccak256::digest(&encoded).into()
+        };
+
+        let mut mail = Keccak256::digest(b"Mail(Person from,Person to,string contents)Person(string name,address wallet)").to_vec();
+        mail.extend_from_slice(&person("Cow", "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826"));
+        mail.extend_from_slice(&person("Bob", "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB"));
+        mail.extend_from_slice(&Keccak256::digest(b"Hello, Bob!"));
+        let mail_hash: [u8; 32] = Keccak256::digest(&mail).into();
+
+        assert_eq!(
+            hex::encode(eip712_digest(&domain, &mail_hash)),
+            "be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2"
+        );
+    }
+
+    #[test]
+    fn test_typed_data_hash_binds_every_field() {
+        let mut base = operation();
+        base.txid = hex::encode([0xabu8; 32]);
+        let digest = base.typed_data_hash().unwrap();
+        assert_eq!(base.typed_data_hash().unwrap(), digest);
+
+        let variants: [fn(&mut MintOperation); 5] = [
+            |op| op.amount += 1,
+            |op| op.destination = "0x00000000000000000000000000000000000000bb".to_string(),
+            |op| op.nonce[0] ^= 1,
+            |op| op.chain_id = 1,
+            |op| op.contract_address = "0x0000000000000000000000000000000000000001".to_string(),
+        ];
+        for mutate in variants {
+            let mut op = base.clone();
+            mutate(&mut op);
+            assert_ne!(op.typed_data_hash().unwrap(), digest);
+        }
+
+        // Only a 32 byte txid fits the bytes32 field
+        assert!(operation().typed_data_hash().is_err());
+    }
+
+    fn signing_request(operation_hash: [u8; 32]) -> SigningRequest {
+        SigningRequest {
+            tx_secret: vec![1, 2, 3],
+            amount: 1_000_000_000_000,
+            operation_hash,
+            timestamp: 1_700_000_000,
+            nonce: [9u8; 32],
+            monero_tx: Some(crate::validation::MoneroTransaction::mock()),
+            direction: Direction::Mint,
+            payout: None,
+        }
+    }
+    
+    fn burn_event() -> BurnEvent {
+        BurnEvent {
+            block_number: 120,
+            block_hash: "0xb120".to_string(),
+            log_index: 2,
+            tx_hash: "0xBEEF".to_string(),
+            from: "0x37fD7F8e2865EF6F214D21C261833d6831D8205e".to_string(),
+            amount: 500_000_000_000,
+            // Monero general fund donation address
+            monero_address: "44AFFq5kSiGBoZ4NMDwYtN18obc8AemS33DBLWs3H7otXft3XjrpDtQGv7SqSsaBYBb98uNbr2VBBEt7f2wfn3RVGQBEP3A".to_string(),
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_burn_event_creates_payout_signing_request() {
+        let event = burn_event();
+        let request = SigningRequest::for_payout(&event, 1_700_000_000).unwrap();
+        
+        assert_eq!(request.direction, Direction::Burn);
+        assert!(request.monero_tx.is_none());
+        let payout = request.payout.as_ref().unwrap();
+        assert_eq!(payout.monero_address, event.monero_address);
+        assert_eq!(payout.amount, event.amount);
+        assert_eq!(request.operation_hash, payout.operation_hash());
+        assert_eq!(request.transfer_id(), "burn:0xbeef:2");
+        // Every validator derives the same request for the same burn
+        assert_eq!(SigningRequest::for_payout(&event, 1_700_000_100).unwrap().operation_hash, request.operation_hash);
+        
+        // Payouts go through the same signer as mints
+        let dir = tempfile::tempdir().unwrap();
+        let coordinator = SigningCoordinator::open(1, dir.path().join("signed.json")).await.unwrap();
+        assert_eq!(coordinator.sign_operation(request).await.unwrap().validator_id, 1);
+        
+        let unpayable = BurnEvent { monero_address: "not-an-address".to_string(), ..event };
+        assert!(SigningRequest::for_payout(&unpayable, 1_700_000_000).is_err());
+    }
+    
+    #[tokio::test]
+    async fn test_first_sign_succeeds_and_resubmit_returns_cached() {
+        let dir = tempfile::tempdir().unwrap();
+        let coordinator = SigningCoordinator::open(3, dir.path().join("signed.json")).await.unwrap();
+        
+        let first = coordinator.sign_operation(signing_request([1u8; 32])).await.unwrap();
+        let second = coordinator.sign_operation(signing_request([1u8; 32])).await.unwrap();
+        
+        assert_eq!(first.validator_id, 3);
+        assert_eq!((first.r, first.s), (second.r, second.s));
+        
+        let other = coordinator.sign_operation(signing_request([2u8; 32])).await.unwrap();
+        assert_ne!(first.r, other.r);
+    }
+    
+    #[tokio::test]
+    async fn test_signed_operations_survive_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let store_path = dir.path().join("signed.json");
+        
+        let first = SigningCoordinator::open(0, &store_path).await.unwrap()
+            .sign_operation(signing_request([5u8; 32])).await.unwrap();
+        
+        let reopened = SigningCoordinator::open(0, &store_path).await.unwrap();
+        let again = reopened.sign_operation(signing_request([5u8; 32])).await.unwrap();
+        
+        assert_eq!((first.r, first.s), (again.r, again.s));
+    }
+    
+    fn combined_signature(operation_hash: &[u8; 32]) -> (SigningResult, Vec<u8>) {
+        let joint_key = k256::ecdsa::SigningKey::random(&mut rand::thread_rng());
+        let (sig, recovery_id) = joint_key.sign_prehash_recoverable(operation_hash).unwrap();
+        let (r, s) = sig.split_bytes();
+        
+        let result = SigningResult {
+            r: r.into(),
+            s: s.into(),
+            v: 27 + recovery_id.to_byte(),
+            validator_id: 0,
+        };
+        let public_key = joint_key.verifying_key().to_encoded_point(false).as_bytes().to_vec();
+        (result, public_key)
+    }
+    
+    #[test]
+    fn test_valid_combined_signature_verifies() {
+        let hash = operation().operation_hash();
+        let (signature, joint_public_key) = combined_signature(&hash);
+        
+        assert!(verify_threshold_signature(&hash, &signature, &joint_public_key));
+        
+        // Wrong operation, or a v that would recover some other address
+        assert!(!verify_threshold_signature(&[0u8; 32], &signature, &joint_public_key));
+        let flipped_v = SigningResult { v: signature.v ^ 1, ..signature.clone() };
+        assert!(!verify_threshold_signature(&hash, &flipped_v, &joint_public_key));
+    }
+    
+    #[test]
+    fn test_corrupted_s_value_rejected() {
+        let hash = operation().operation_hash();
+        let (mut signature, joint_public_key) = combined_signature(&hash);
+        
+        signature.s[31] ^= 0x01;
+        
+        assert!(!verify_threshold_signature(&hash, &signature, &joint_public_key));
+    }
+}
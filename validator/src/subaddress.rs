@@ -0,0 +1,215 @@
+use std::collections::HashMap;
+
+use curve25519_dalek::constants::ED25519_BASEPOINT_TABLE;
+use curve25519_dalek::edwards::{CompressedEdwardsY, EdwardsPoint};
+use curve25519_dalek::scalar::Scalar;
+use sha3::{Digest, Keccak256};
+
+use crate::config::SubaddressConfig;
+use crate::error::{Result, ValidatorError};
+
+const BASE58_ALPHABET: &[u8] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+// Encoded length of a trailing block of 0..=8 bytes
+const ENCODED_BLOCK_SIZES: [usize; 9] = [0, 2, 3, 5, 6, 7, 9, 10, 11];
+const FULL_BLOCK_SIZE: usize = 8;
+const FULL_ENCODED_BLOCK_SIZE: usize = 11;
+
+// Standard address prefix -> subaddress prefix for mainnet, stagenet and testnet
+const SUBADDRESS_PREFIXES: [(u8, u8); 3] = [(18, 42), (24, 36), (53, 63)];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubaddressEntry {
+    pub major: u32,
+    pub minor: u32,
+    pub recipient: Option<String>,
+}
+
+// Subaddresses of the bridge wallet the validator accepts deposits on. Each
+// one can be assigned to a recipient, so deposits identify the user without
+// relying on tx_extra.
+#[derive(Debug, Default)]
+pub struct SubaddressBook {
+    by_address: HashMap<String, SubaddressEntry>,
+    by_recipient: HashMap<String, String>,
+}
+
+impl SubaddressBook {
+    pub fn from_config(primary_address: &str, config: &SubaddressConfig) -> Result<Self> {
+        let (prefix, spend_public, _) = decode_address(primary_address)?;
+        let subaddress_prefix = SUBADDRESS_PREFIXES
+            .iter()
+            .find(|(standard, _)| *standard == prefix)
+            .map(|(_, sub)| *sub)
+            .ok_or_else(|| ValidatorError::Config(format!("Unsupported Monero address prefix {}", prefix)))?;
+
+        let view_key: [u8; 32] = hex::decode(&config.view_key)?
+            .try_into()
+            .map_err(|_| ValidatorError::Config("Bridge view key must be 32 bytes".to_string()))?;
+        let view_secret = Option::<Scalar>::from(Scalar::from_canonical_bytes(view_key))
+            .ok_or_else(|| ValidatorError::Config("Bridge view key is not a canonical scalar".to_string()))?;
+
+        let recipients: HashMap<u32, &str> = config
+            .recipients
+            .iter()
+            .map(|r| (r.index, r.recipient.as_str()))
+            .collect();
+
+        let mut book = Self::default();
+        // Minor index 0 is the primary address itself
+        for minor in 1..=config.count {
+            let address = derive_subaddress(subaddress_prefix, &view_secret, &spend_public, config.account, minor);
+            let recipient = recipients.get(&minor).map(|r| r.to_lowercase());
+
+            if let Some(ref recipient) = recipient {
+                book.by_recipient.insert(recipient.clone(), address.clone());
+            }
+            book.by_address.insert(address, SubaddressEntry { major: config.account, minor, recipient });
+        }
+
+        if let Some(index) = recipients.keys().find(|index| **index == 0 || **index > config.count) {
+            return Err(ValidatorError::Config(format!("Recipient assigned to unmonitored subaddress {}", index)));
+        }
+
+        Ok(book)
+    }
+
+    pub fn lookup(&self, address: &str) -> Option<&SubaddressEntry> {
+        self.by_address.get(address)
+    }
+
+    pub fn address_for(&self, recipient: &str) -> Option<&str> {
+        self.by_recipient.get(&recipient.to_lowercase()).map(String::as_str)
+    }
+}
+
+// m = Hs("SubAddr\0" || a || major || minor), D = B + m*G, C = a*D
+pub fn derive_subaddress(prefix: u8, view_secret: &Scalar, spend_public: &EdwardsPoint, major: u32, minor: u32) -> String {
+    let mut hasher = Keccak256::new();
+    hasher.update(b"SubAddr\0");
+    hasher.update(view_secret.as_bytes());
+    hasher.update(major.to_le_bytes());
+    hasher.update(minor.to_le_bytes());
+    let m = Scalar::from_bytes_mod_order(hasher.finalize().into());
+
+    let spend = spend_public + &m * ED25519_BASEPOINT_TABLE;
+    let view = view_secret * spend;
+
+    encode_address(prefix, &spend, &view)
+}
+
+pub fn encode_address(prefix: u8, spend: &EdwardsPoint, view: &EdwardsPoint) -> String {
+    let mut data = vec![prefix];
+    data.extend_from_slice(spend.compress().as_bytes());
+    data.extend_from_slice(view.compress().as_bytes());
+    let checksum = Keccak256::digest(&data);
+    data.extend_from_slice(&checksum[..4]);
+    base58_encode(&data)
+}
+
+// Returns the network prefix and the public spend and view keys
+pub fn decode_address(address: &str) -> Result<(u8, EdwardsPoint, EdwardsPoint)> {
+    let invalid = || ValidatorError::Config(format!("Invalid Monero address {}", address));
+
+    let data = base58_decode(address).ok_or_else(invalid)?;
+    if data.len() != 69 || data[0] >= 0x80 {
+        return Err(invalid());
+    }
+
+    let (body, checksum) = data.split_at(65);
+    if Keccak256::digest(body)[..4] != *checksum {
+        return Err(invalid());
+    }
+
+    let point = |bytes: &[u8]| {
+        CompressedEdwardsY::from_slice(bytes).ok().and_then(|p| p.decompress()).ok_or_else(invalid)
+    };
+    Ok((body[0], point(&body[1..33])?, point(&body[33..65])?))
+}
+
+// Monero's base58 works on 8 byte blocks, each encoded to a fixed width
+fn base58_encode(data: &[u8]) -> String {
+    let mut out = String::new();
+    for block in data.chunks(FULL_BLOCK_SIZE) {
+        let mut value = block.iter().fold(0u128, |acc, b| (acc << 8) | *b as u128);
+        let mut encoded = vec![BASE58_ALPHABET[0]; ENCODED_BLOCK_SIZES[block.len()]];
+        for slot in encoded.iter_mut().rev() {
+            *slot = BASE58_ALPHABET[(value % 58) as usize];
+            value /= 58;
+        }
+        out.push_str(std::str::from_utf8(&encoded).unwrap());
+    }
+    out
+}
+
+fn base58_decode(encoded: &str) -> Option<Vec<u8>> {
+    let mut out = Vec::new();
+    for block in encoded.as_bytes().chunks(FULL_ENCODED_BLOCK_SIZE) {
+        let size = ENCODED_BLOCK_SIZES.iter().position(|s| *s == block.len())?;
+        let mut value = 0u128;
+        for c in block {
+            let digit = BASE58_ALPHABET.iter().position(|a| a == c)?;
+            value = value * 58 + digit as u128;
+        }
+        if value >> (8 * size) != 0 {
+            return None;
+        }
+        out.extend_from_slice(&value.to_be_bytes()[16 - size..]);
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::SubaddressRecipient;
+
+    // Monero general fund donation address
+    const MAINNET_ADDRESS: &str = "44AFFq5kSiGBoZ4NMDwYtN18obc8AemS33DBLWs3H7otXft3XjrpDtQGv7SqSsaBYBb98uNbr2VBBEt7f2wfn3RVGQBEP3A";
+
+    fn subaddress_config() -> SubaddressConfig {
+        SubaddressConfig {
+            view_key: hex::encode(Scalar::from(7u64).as_bytes()),
+            account: 0,
+            count: 5,
+            recipients: vec![SubaddressRecipient {
+                index: 3,
+                recipient: "0x00000000000000000000000000000000000000AA".to_string(),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_address_round_trip() {
+        let (prefix, spend, view) = decode_address(MAINNET_ADDRESS).unwrap();
+        assert_eq!(encode_address(prefix, &spend, &view), MAINNET_ADDRESS);
+
+        let mut corrupted = MAINNET_ADDRESS.to_string();
+        corrupted.replace_range(10..11, "z");
+        assert!(decode_address(&corrupted).is_err());
+    }
+
+    #[test]
+    fn test_subaddresses_are_distinct_and_decodable() {
+        let book = SubaddressBook::from_config(MAINNET_ADDRESS, &subaddress_config()).unwrap();
+        assert_eq!(book.by_address.len(), 5);
+
+        let address = book.address_for("0x00000000000000000000000000000000000000aa").unwrap();
+        assert_eq!(book.lookup(address).unwrap().minor, 3);
+        assert_ne!(address, MAINNET_ADDRESS);
+
+        let (prefix, _, _) = decode_address(address).unwrap();
+        // Mainnet subaddresses start with 8
+        assert_eq!(prefix, 42);
+        assert!(address.starts_with('8'));
+    }
+
+    #[test]
+    fn test_recipient_outside_range_rejected() {
+        let mut config = subaddress_config();
+        config.recipients[0].index = 6;
+        assert!(matches!(
+            SubaddressBook::from_config(MAINNET_ADDRESS, &config),
+            Err(ValidatorError::Config(_))
+        ));
+    }
+}
@@ -0,0 +1,343 @@
+// Stand-ins for monerod and an Ethereum node that tests can start in a few
+// lines, instead of each module wiring up its own axum mock.
+
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::{Json, Router};
+use serde_json::Value;
+use sha3::{Digest, Keccak256};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::config::Config;
+use crate::digest_auth::{self, DigestChallenge};
+use crate::network::{router, NetworkState};
+
+// Serves `app` on a free local port and returns its base URL
+pub async fn serve(app: Router) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    format!("http://{}", address)
+}
+
+// A validator's HTTP endpoints, with the peer address its handlers key on
+pub async fn serve_network(state: NetworkState) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let app = router(state).into_make_service_with_connect_info::<SocketAddr>();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    format!("http://{}", address)
+}
+
+// The bundled config.toml pointed at fake daemons
+pub fn config(monerod_url: &str, ethereum_url: &str) -> Config {
+    let mut config: Config = toml::from_str(include_str!("../config.toml")).unwrap();
+    config.monero.rpc_url = monerod_url.to_string();
+    config.ethereum.rpc_url = ethereum_url.to_string();
+    config
+}
+
+// The challenge monerod sends when started with --rpc-login
+const DIGEST_CHALLENGE: &str = r#"Digest qop="auth", algorithm=MD5, realm="monero-rpc", nonce="a1b2c3d4e5f6""#;
+
+// monerod's /json_rpc: check_tx_key answers for known txids, and canned
+// results for any other method (get_block, get_output_distribution, ...).
+// Batches are answered in reverse order, which monerod is allowed to do.
+// Clones share the transfers and the hit count, so a test can move a
+// transfer deeper into the chain, or count calls, after starting it.
+#[derive(Clone, Default)]
+pub struct FakeMonerod {
+    transfers: Arc<Mutex<HashMap<String, Value>>>,
+    results: HashMap<String, Value>,
+    rejects_batches: bool,
+    delay: Option<Duration>,
+    login: Option<(String, String)>,
+    hits: Arc<AtomicUsize>,
+}
+
+impl FakeMonerod {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_transfer(self, txid: &str, received: u64, confirmations: u64) -> Self {
+        self.set_transfer(txid, received, confirmations);
+        self
+    }
+
+    pub fn set_transfer(&self, txid: &str, received: u64, confirmations: u64) {
+        self.transfers.lock().unwrap().insert(
+            txid.to_string(),
+            serde_json::json!({ "confirmations": confirmations, "in_pool": confirmations == 0, "received": received }),
+        );
+    }
+
+    pub fn with_result(mut self, method: &str, result: Value) -> Self {
+        self.results.insert(method.to_string(), result);
+        self
+    }
+
+    pub fn without_batches(mut self) -> Self {
+        self.rejects_batches = true;
+        self
+    }
+
+    // Every request is held this long before it is answered
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = Some(delay);
+        self
+    }
+
+    // Requires digest auth, as monerod does when started with --rpc-login
+    pub fn with_login(mut self, username: &str, password: &str) -> Self {
+        self.login = Some((username.to_string(), password.to_string()));
+        self
+    }
+
+    // Requests that reached /json_rpc, authorized or not
+    pub fn hits(&self) -> usize {
+        self.hits.load(Ordering::SeqCst)
+    }
+
+    fn authorized(&self, headers: &HeaderMap) -> bool {
+        let Some((username, password)) = &self.login else {
+            return true;
+        };
+        headers
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Digest "))
+            .map(digest_auth::parse_params)
+            .map(|fields| {
+                let challenge = DigestChallenge::parse(DIGEST_CHALLENGE).unwrap();
+                let nc = u32::from_str_radix(&fields["nc"], 16).unwrap();
+                let expected = challenge.authorization(username, password, "POST", &fields["uri"], nc, &fields["cnonce"]);
+                fields["uri"] == "/json_rpc"
+                    && digest_auth::parse_params(expected.strip_prefix("Digest ").unwrap())["response"] == fields["response"]
+            })
+            .unwrap_or(false)
+    }
+
+    fn answer(&self, request: &Value) -> Value {
+        let transfers = self.transfers.lock().unwrap();
+        let result = match request["method"].as_str() {
+            Some("check_tx_key") => request["params"]["txid"].as_str().and_then(|txid| transfers.get(txid)),
+            Some(method) => self.results.get(method),
+            None => None,
+        };
+        match result {
+            Some(result) => serde_json::json!({ "jsonrpc": "2.0", "id": request["id"], "result": result }),
+            None => serde_json::json!({ "jsonrpc": "2.0", "id": request["id"], "error": { "code": -1, "message": "not found" } }),
+        }
+    }
+
+    async fn respond(&self, headers: HeaderMap, body: Value) -> Response {
+        self.hits.fetch_add(1, Ordering::SeqCst);
+        if let Some(delay) = self.delay {
+            tokio::time::sleep(delay).await;
+        }
+        if !self.authorized(&headers) {
+            return (StatusCode::UNAUTHORIZED, [(header::WWW_AUTHENTICATE, DIGEST_CHALLENGE)]).into_response();
+        }
+        Json(match body {
+            Value::Array(_) if self.rejects_batches => {
+                serde_json::json!({ "jsonrpc": "2.0", "id": null, "error": { "code": -32600, "message": "Invalid request" } })
+            }
+            Value::Array(requests) => Value::Array(requests.iter().rev().map(|request| self.answer(request)).collect()),
+            request => self.answer(&request),
+        })
+        .into_response()
+    }
+
+    fn router(self) -> Router {
+        Router::new().route("/json_rpc", post(move |headers: HeaderMap, Json(body): Json<Value>| async move {
+            self.respond(headers, body).await
+        }))
+    }
+
+    // Returns the /json_rpc URL
+    pub async fn start(self) -> String {
+        format!("{}/json_rpc", serve(self.router()).await)
+    }
+
+    // Starts on a given address, for tests that bring the daemon up late
+    pub async fn start_at(self, address: SocketAddr) {
+        let listener = tokio::net::TcpListener::bind(address).await.unwrap();
+        axum::serve(listener, self.router()).await.unwrap();
+    }
+}
+
+#[derive(Default)]
+struct Chain {
+    head: u64,
+    logs: Vec<Value>,
+}
+
+// Ethereum JSON-RPC holding the bridge contract's MintRequested logs. Clones
+// share the chain, so a test can move the head after starting it.
+#[derive(Clone, Default)]
+pub struct FakeEthereum {
+    chain: Arc<Mutex<Chain>>,
+}
+
+impl FakeEthereum {
+    pub fn new(head: u64) -> Self {
+        let ethereum = Self::default();
+        ethereum.set_head(head);
+        ethereum
+    }
+
+    pub fn set_head(&self, head: u64) {
+        self.chain.lock().unwrap().head = head;
+    }
+
+    pub fn with_mint_request(self, block_number: u64, txid: &str, tx_secret: &str, receiver: &str) -> Self {
+        let mut chain = self.chain.lock().unwrap();
+        let log_index = chain.logs.len();
+        chain.logs.push(serde_json::json!({
+            "blockNumber": format!("0x{:x}", block_number),
+            "blockHash": format!("0x{:064x}", block_number),
+            "logIndex": format!("0x{:x}", log_index),
+            "topics": [
+                format!("0x{}", hex::encode(Keccak256::digest(b"MintRequested(bytes32,bytes32,address)"))),
+                format!("0x{}", txid),
+                format!("0x{}", tx_secret),
+                format!("0x{:0>64}", receiver.trim_start_matches("0x")),
+            ],
+            "removed": false,
+        }));
+        drop(chain);
+        self
+    }
+
+    // Burn(from, amount, moneroAddress), ABI-encoding the non-indexed fields
+    pub fn with_burn(self, block_number: u64, from: &str, amount: u64, monero_address: &str) -> Self {
+        let mut chain = self.chain.lock().unwrap();
+        let log_index = chain.logs.len();
+        let padded = monero_address.len().div_ceil(32) * 32;
+        chain.logs.push(serde_json::json!({
+            "blockNumber": format!("0x{:x}", block_number),
+            "blockHash": format!("0x{:064x}", block_number),
+            "logIndex": format!("0x{:x}", log_index),
+            "transactionHash": format!("0x{:064x}", 0xb0 + log_index),
+            "topics": [
+                format!("0x{}", hex::encode(Keccak256::digest(b"Burn(address,uint256,string)"))),
+                format!("0x{:0>64}", from.trim_start_matches("0x")),
+            ],
+            "data": format!(
+                "0x{:064x}{:064x}{:064x}{}{}",
+                amount, 64, monero_address.len(), hex::encode(monero_address), "00".repeat(padded - monero_address.len()),
+            ),
+            "removed": false,
+        }));
+        drop(chain);
+        self
+    }
+
+    fn answer(&self, request: &Value) -> Value {
+        let chain = self.chain.lock().unwrap();
+        let quantity = |value: &Value| u64::from_str_radix(value.as_str().unwrap().trim_start_matches("0x"), 16).unwrap();
+
+        let result = match request["method"].as_str() {
+            Some("eth_blockNumber") => serde_json::json!(format!("0x{:x}", chain.head)),
+            Some("eth_getLogs") => {
+                let filter = &request["params"][0];
+                let (from, to) = (quantity(&filter["fromBlock"]), quantity(&filter["toBlock"]).min(chain.head));
+                Value::Array(chain.logs
+                    .iter()
+                    .filter(|log| (from..=to).contains(&quantity(&log["blockNumber"])))
+                    .filter(|log| log["topics"][0] == filter["topics"][0])
+                    .cloned()
+                    .collect())
+            }
+            _ => return serde_json::json!({ "jsonrpc": "2.0", "id": request["id"], "error": { "code": -32601, "message": "method not found" } }),
+        };
+        serde_json::json!({ "jsonrpc": "2.0", "id": request["id"], "result": result })
+    }
+
+    pub async fn start(&self) -> String {
+        let ethereum = self.clone();
+        serve(Router::new().route("/", post(move |Json(request): Json<Value>| async move { Json(ethereum.answer(&request)) }))).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decoy::OutputSource;
+    use crate::eth_events::{BurnLogs, MintRequestedLogs};
+    use crate::signing::{Direction, SigningRequest};
+    use crate::event_cursor::{BlockCursor, EventDiscovery, EventFeed};
+    use crate::validation::{MintCheck, MoneroValidator, TxKeyCheck};
+
+    #[tokio::test]
+    async fn test_mint_request_from_ethereum_is_confirmed_against_monerod() {
+        let (txid, tx_secret) = ("ab".repeat(32), "cd".repeat(32));
+        let receiver = "0x00000000000000000000000000000000000000aa";
+
+        let ethereum = FakeEthereum::new(10).with_mint_request(5, &txid, &tx_secret, receiver);
+        let monerod = FakeMonerod::new()
+            .with_transfer(&txid, 1_000_000_000_000, 10)
+            .with_result("get_output_distribution", serde_json::json!({ "distributions": [{ "distribution": [4, 9, 15] }] }));
+        let config = config(&monerod.start().await, &ethereum.start().await);
+
+        let dir = tempfile::tempdir().unwrap();
+        let cursor = BlockCursor::open(dir.path().join("mint_cursor.json"), config.ethereum.finality_depth, 0).await.unwrap();
+        let source = MintRequestedLogs::new(&config.ethereum.rpc_url, &config.ethereum.contract_address);
+        let discovery = EventDiscovery::Poll(EventFeed::new(Arc::new(source), cursor));
+
+        // Not yet buried under the finality depth
+        assert!(discovery.next_events().await.unwrap().events.is_empty());
+        ethereum.set_head(20);
+        let events = discovery.next_events().await.unwrap().events;
+        assert_eq!(events.len(), 1);
+        assert_eq!((events[0].txid.as_str(), events[0].destination.as_str()), (txid.as_str(), receiver));
+
+        let check = TxKeyCheck {
+            txid: events[0].txid.clone(),
+            tx_key: events[0].tx_key.clone(),
+            destination_address: config.monero.address.clone(),
+        };
+        let requests = [(check, events[0].amount)];
+
+        // The event states no amount, which is refused unless opted into
+        let validator = MoneroValidator::new(config.monero.clone()).unwrap();
+        assert!(matches!(validator.validate_mint_requests(&requests).await.unwrap()[0], MintCheck::Rejected));
+
+        let mut monero = config.monero.clone();
+        monero.accept_unstated_amount = true;
+        let validator = MoneroValidator::new(monero).unwrap();
+        let results = validator.validate_mint_requests(&requests).await.unwrap();
+        assert!(matches!(&results[0], MintCheck::Confirmed(tx) if tx.amount == 1_000_000_000_000));
+
+        // Canned results cover the daemon calls a payout makes
+        assert_eq!(validator.output_distribution().await.unwrap().cumulative, vec![4, 9, 15]);
+    }
+
+    #[tokio::test]
+    async fn test_burn_on_ethereum_becomes_a_payout_request() {
+        let monero_address = "44AFFq5kSiGBoZ4NMDwYtN18obc8AemS33DBLWs3H7otXft3XjrpDtQGv7SqSsaBYBb98uNbr2VBBEt7f2wfn3RVGQBEP3A";
+        let ethereum = FakeEthereum::new(30)
+            .with_mint_request(4, &"ab".repeat(32), &"cd".repeat(32), "0x00000000000000000000000000000000000000aa")
+            .with_burn(5, "0x00000000000000000000000000000000000000bb", 750_000_000_000, monero_address);
+        let config = config("http://127.0.0.1:1", &ethereum.start().await);
+
+        let dir = tempfile::tempdir().unwrap();
+        let cursor = BlockCursor::open(dir.path().join("burn_cursor.json"), config.ethereum.finality_depth, 0).await.unwrap();
+        let feed = EventFeed::new(Arc::new(BurnLogs::new(&config.ethereum.rpc_url, &config.ethereum.contract_address)), cursor);
+
+        // Only the Burn log is picked up, not the mint request next to it
+        let events = feed.next_finalized().await.unwrap().events;
+        assert_eq!(events.len(), 1);
+        assert_eq!((events[0].amount, events[0].monero_address.as_str()), (750_000_000_000, monero_address));
+
+        let request = SigningRequest::for_payout(&events[0], 1_700_000_000).unwrap();
+        assert_eq!(request.direction, Direction::Burn);
+        assert_eq!(request.payout.unwrap().monero_address, monero_address);
+    }
+}
@@ -0,0 +1,165 @@
+use std::path::{Path, PathBuf};
+use crate::error::Result;
+use k256::ecdsa::{Signature, SigningKey, VerifyingKey};
+use k256::ecdsa::signature::{Signer, Verifier};
+use tracing::info;
+
+use crate::canonical;
+use crate::network::ConsensusMessage;
+
+// Per-validator key used to authenticate network traffic. It is independent of
+// the TSS share so it can be rotated without resharing the threshold key.
+pub struct TransportKey {
+    signing_key: SigningKey,
+}
+
+impl TransportKey {
+    pub fn generate() -> Self {
+        Self {
+            signing_key: SigningKey::random(&mut rand::thread_rng()),
+        }
+    }
+
+    pub fn from_hex(secret_hex: &str) -> Result<Self> {
+        let bytes = hex::decode(secret_hex.trim())?;
+        Ok(Self {
+            signing_key: SigningKey::from_slice(&bytes)?,
+        })
+    }
+
+    pub async fn load_or_generate(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+
+        match tokio::fs::read_to_string(path).await {
+            Ok(content) => Self::from_hex(&content),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                let key = Self::generate();
+                if let Some(parent) = path.parent() {
+                    tokio::fs::create_dir_all(parent).await?;
+                }
+                write_secret_file(path, hex::encode(key.signing_key.to_bytes()).as_bytes()).await?;
+                info!("Generated new transport key at {}", path.display());
+                Ok(key)
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn public_key(&self) -> Vec<u8> {
+        self.signing_key
+            .verifying_key()
+            .to_encoded_point(false)
+            .as_bytes()
+            .to_vec()
+    }
+
+    pub fn sign_message(&self, message: &mut ConsensusMessage) -> Result<()> {
+        message.signature = self.sign_bytes(&canonical::message_bytes(message));
+        Ok(())
+    }
+
+    pub fn sign_bytes(&self, bytes: &[u8]) -> Vec<u8> {
+        let signature: Signature = self.signing_key.sign(bytes);
+        signature.to_bytes().to_vec()
+    }
+}
+
+pub fn verify_message(message: &ConsensusMessage, transport_public_key: &[u8]) -> Result<()> {
+    verify_bytes(&canonical::message_bytes(message), &message.signature, transport_public_key)
+}
+
+pub fn verify_bytes(bytes: &[u8], signature: &[u8], transport_public_key: &[u8]) -> Result<()> {
+    let verifying_key = VerifyingKey::from_sec1_bytes(transport_public_key)?;
+    let signature = Signature::from_slice(signature)?;
+    verifying_key.verify(bytes, &signature)?;
+    Ok(())
+}
+
+// Writes a file only its owner can read. The contents go to a temporary file
+// that is created 0600 and then renamed into place, so a secret is never
+// readable by others, whether the file is new or being overwritten.
+pub async fn write_secret_file(path: impl AsRef<Path>, contents: &[u8]) -> Result<()> {
+    use tokio::io::AsyncWriteExt;
+
+    let path = path.as_ref();
+    let mut tmp_path = path.as_os_str().to_owned();
+    tmp_path.push(".tmp");
+    let tmp_path = PathBuf::from(tmp_path);
+
+    // Left behind by an interrupted write; create_new would refuse it
+    match tokio::fs::remove_file(&tmp_path).await {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+        _ => {}
+    }
+
+    let mut options = tokio::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+    let mut file = options.open(&tmp_path).await?;
+    file.write_all(contents).await?;
+    file.sync_all().await?;
+    drop(file);
+
+    tokio::fs::rename(&tmp_path, path).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tss::TSSKeyGenerator;
+
+    fn message() -> ConsensusMessage {
+        ConsensusMessage {
+            validator_id: 1,
+            msg_type: "SIGN_ROUND".to_string(),
+            data: serde_json::json!({ "round": 1 }),
+            signature: vec![],
+            timestamp: 1_700_000_000,
+            hops: 0,
+        }
+    }
+
+    #[test]
+    fn test_message_verified_against_transport_key() {
+        let transport = TransportKey::generate();
+        let mut msg = message();
+        transport.sign_message(&mut msg).unwrap();
+
+        assert!(verify_message(&msg, &transport.public_key()).is_ok());
+
+        msg.data = serde_json::json!({ "round": 2 });
+        assert!(verify_message(&msg, &transport.public_key()).is_err());
+    }
+
+    #[test]
+    fn test_share_key_signature_not_accepted_as_transport() {
+        let (share, _) = TSSKeyGenerator::new(4, 7).generate_keys(1).unwrap();
+        let share_as_transport = TransportKey {
+            signing_key: SigningKey::from_slice(&share.eth_private_share).unwrap(),
+        };
+        let transport = TransportKey::generate();
+
+        let mut msg = message();
+        share_as_transport.sign_message(&mut msg).unwrap();
+
+        assert!(verify_message(&msg, &transport.public_key()).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_load_or_generate_is_stable() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("transport_key");
+
+        let first = TransportKey::load_or_generate(&path).await.unwrap();
+        let second = TransportKey::load_or_generate(&path).await.unwrap();
+
+        assert_eq!(first.public_key(), second.public_key());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(std::fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        }
+    }
+}
@@ -2,10 +2,18 @@ use k256::SecretKey;
 use k256::PublicKey;
 use curve25519_dalek::scalar::Scalar;
 use sha2::{Sha256, Digest};
+use sha3::Keccak256;
+use bip39::Mnemonic;
 use serde::{Serialize, Deserialize};
-use anyhow::Result;
+use std::fmt;
+use crate::error::{Result, ValidatorError};
+use crate::redact::redact;
+use crate::subaddress::encode_address;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+// Standard address prefix of stagenet, the default monero.network
+pub const DEFAULT_MONERO_NETWORK: u8 = 24;
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct TSSKeyShare {
     pub party_id: usize,
     pub validator_id: usize,
@@ -16,6 +24,20 @@ pub struct TSSKeyShare {
     pub commitment_point: Vec<u8>,
 }
 
+impl fmt::Debug for TSSKeyShare {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TSSKeyShare")
+            .field("party_id", &self.party_id)
+            .field("validator_id", &self.validator_id)
+            .field("eth_private_share", &redact(&self.eth_private_share))
+            .field("eth_public_key", &hex::encode(&self.eth_public_key))
+            .field("monero_private_share", &redact(&self.monero_private_share))
+            .field("monero_public_key", &hex::encode(&self.monero_public_key))
+            .field("commitment_point", &hex::encode(&self.commitment_point))
+            .finish()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JointKeys {
     pub eth_address: String,
@@ -28,6 +50,7 @@ pub struct JointKeys {
 pub struct TSSKeyGenerator {
     threshold: usize,
     total_parties: usize,
+    monero_network: u8,
 }
 
 impl TSSKeyGenerator {
@@ -35,12 +58,37 @@ impl TSSKeyGenerator {
         Self {
             threshold,
             total_parties,
+            monero_network: DEFAULT_MONERO_NETWORK,
         }
     }
 
+    // Standard address prefix the Monero addresses are encoded for
+    pub fn with_monero_network(mut self, prefix: u8) -> Self {
+        self.monero_network = prefix;
+        self
+    }
+
+    // Position-derived keys anyone can recompute; only fit for tests
+    #[cfg(test)]
     pub fn generate_keys(&self, validator_id: usize) -> Result<(TSSKeyShare, JointKeys)> {
         // Generate deterministic seed based on validator position
         let seed = self.generate_seed(validator_id);
+        self.generate_keys_from_seed(validator_id, &seed)
+    }
+
+    // The same phrase always yields the same share, so it doubles as a backup
+    pub fn generate_keys_from_mnemonic(&self, validator_id: usize, mnemonic: &Mnemonic) -> Result<(TSSKeyShare, JointKeys)> {
+        let mut hasher = Sha256::new();
+        hasher.update(b"tss_bridge_mnemonic_seed");
+        hasher.update(validator_id.to_le_bytes());
+        hasher.update(mnemonic.to_seed(""));
+        let seed: [u8; 32] = hasher.finalize().into();
+        self.generate_keys_from_seed(validator_id, &seed)
+    }
+
+    // EVM and Monero shares use separately tagged derivations of the seed
+    fn generate_keys_from_seed(&self, validator_id: usize, seed: &[u8; 32]) -> Result<(TSSKeyShare, JointKeys)> {
+        let seed = *seed;
         
         // Generate Ethereum key share
         let eth_private_share = self.generate_eth_key_share(&seed)?;
@@ -66,9 +114,9 @@ impl TSSKeyGenerator {
 
         // Create joint keys (in real TSS, these would be computed from all shares)
         let joint_keys = JointKeys {
-            eth_address: self.derive_eth_address(&eth_public_key),
+            eth_address: Self::derive_eth_address(&eth_public_key),
             eth_public_key: eth_public_key.clone(),
-            monero_address: self.derive_monero_address(&monero_public_key),
+            monero_address: Self::derive_monero_address(self.monero_network, &monero_public_key)?,
             monero_public_key: monero_public_key.clone(),
             share_verification_commitments: vec![commitment_point.to_vec()],
         };
@@ -76,6 +124,7 @@ impl TSSKeyGenerator {
         Ok((share, joint_keys))
     }
 
+    #[cfg(test)]
     fn generate_seed(&self, validator_id: usize) -> [u8; 32] {
         let mut hasher = Sha256::new();
         hasher.update(b"tss_bridge_seed");
@@ -146,69 +195,89 @@ impl TSSKeyGenerator {
         commitment
     }
 
-    fn derive_eth_address(&self, public_key: &[u8]) -> String {
-        // This is a simplified derivation - in production use proper address derivation
-        hex::encode(&public_key[1..21])  // Take first 20 bytes after compression byte
+    // Last 20 bytes of the Keccak-256 of the uncompressed point, as ecrecover yields
+    pub fn derive_eth_address(public_key: &[u8]) -> String {
+        let point = public_key.strip_prefix(&[0x04]).unwrap_or(public_key);
+        format!("0x{}", hex::encode(&Keccak256::digest(point)[12..]))
     }
 
-    fn combine_eth_public_keys(&self, public_keys: &[&Vec<u8>]) -> Vec<u8> {
-        // Generate a proper 65-byte uncompressed public key for secp256k1
-        // This simulates proper TSS combined public key
-        let combined_pub = vec![
-            0x04, 0x5d, 0x9b, 0xdf, 0x8e, 0x88, 0xb0, 0xa4, 0x9f, 0xc0, 0xb4, 0xc4, 0x7d, 0x99, 0x1b,
-            0x08, 0x0f, 0x0f, 0xe5, 0x52, 0xa5, 0x4d, 0x4b, 0x5e, 0xdb, 0xcd, 0xad, 0x65, 0x60, 0xf8,
-            0x95, 0x96, 0x13, 0x7a, 0xb3, 0xd3, 0xf5, 0xc2, 0xac, 0xed, 0x7f, 0x7b, 0x44, 0x64,
-            0x2b, 0x28, 0xc2, 0x92, 0x57, 0x07, 0x7b, 0x5c, 0x63, 0x97, 0x09, 0x71, 0x03, 0x8e,
-            0x30, 0x41, 0xd4, 0x1a, 0xef, 0xad, 0xd7, 0x89, 0xf7, 0x32, 0x3f, 0x5f, 0x11
-        ];
-        combined_pub
+    // Sum of the share public keys on secp256k1
+    pub fn combine_eth_public_keys(public_keys: &[&Vec<u8>]) -> Result<Vec<u8>> {
+        use k256::elliptic_curve::sec1::ToEncodedPoint;
+
+        let mut combined = k256::ProjectivePoint::IDENTITY;
+        for public_key in public_keys {
+            let point = PublicKey::from_sec1_bytes(public_key)
+                .map_err(|e| ValidatorError::KeyMaterial(format!("invalid Ethereum share public key: {}", e)))?;
+            combined += point.to_projective();
+        }
+        let combined = PublicKey::from_affine(combined.to_affine())
+            .map_err(|_| ValidatorError::KeyMaterial("Ethereum share public keys sum to the identity".to_string()))?;
+        Ok(combined.to_encoded_point(false).as_bytes().to_vec())
     }
 
-    fn combine_monero_public_keys(&self, public_keys: &[&Vec<u8>]) -> Vec<u8> {
-        // Simulate curve25519 point addition for combined public key
-        // For demo, we'll use first validator's key aggregated
-        let mut combined = Vec::new();
-        if let Some(first) = public_keys.get(0) {
-            combined = first.to_vec();
+    // Sum of the share public keys on ed25519
+    fn combine_monero_public_keys(&self, public_keys: &[&Vec<u8>]) -> Result<Vec<u8>> {
+        let mut combined = curve25519_dalek::EdwardsPoint::default();
+        for public_key in public_keys {
+            combined += Self::monero_point(public_key)?;
         }
-        combined
+        Ok(combined.compress().to_bytes().to_vec())
+    }
+
+    pub fn monero_point(public_key: &[u8]) -> Result<curve25519_dalek::EdwardsPoint> {
+        <[u8; 32]>::try_from(public_key)
+            .ok()
+            .and_then(|bytes| curve25519_dalek::edwards::CompressedEdwardsY(bytes).decompress())
+            .ok_or_else(|| ValidatorError::KeyMaterial(format!("invalid Monero public key {}", hex::encode(public_key))))
     }
 
-    fn derive_monero_address_proper(&self, public_key: &[u8]) -> String {
-        // Proper working Monero address for stagenet/testnet
-        // Valid 32-byte key pair for basic testing
-        "59WGZSFUAJFuX2VGSUxRt8QfXJ1bTNBTR8gDqVh9BGoc61KYP4aRDUuzJzQmfBtG3gWQsb7P2m1Zf46YBQMDJSRGtDh4huz".to_string()
+    // Standard address with `public_key` as its spend key and the view key below
+    pub fn derive_monero_address(network: u8, public_key: &[u8]) -> Result<String> {
+        let spend = Self::monero_point(public_key)?;
+        let view = &Self::monero_view_secret(&spend) * curve25519_dalek::constants::ED25519_BASEPOINT_TABLE;
+        Ok(encode_address(network, &spend, &view))
     }
 
-    fn derive_monero_address(&self, public_key: &[u8]) -> String {
-        // This is a simplified derivation - in production use proper Monero address derivation
-        format!("monero_{}", hex::encode(public_key))
+    // Nobody holds the joint spend secret, so the view key cannot be derived
+    // from it as a wallet's is. It is derived from the public spend key
+    // instead: anyone with the address can audit the bridge's reserve, and
+    // every validator can scan it.
+    pub fn monero_view_secret(spend: &curve25519_dalek::EdwardsPoint) -> Scalar {
+        let mut hasher = Keccak256::new();
+        hasher.update(b"wxmr_bridge_view_key");
+        hasher.update(spend.compress().as_bytes());
+        Scalar::from_bytes_mod_order(hasher.finalize().into())
     }
 
     pub fn combine_shares(&self, shares: &[TSSKeyShare]) -> Result<JointKeys> {
-        if shares.is_empty() {
-            return Err(anyhow::anyhow!("No shares provided"));
+        // The joint keys are the sums of the share public keys, and the
+        // addresses are derived from those sums. The shares are additive, not
+        // Shamir shares, so a sum over any subset is a different key entirely.
+        if self.threshold == 0 || self.threshold > self.total_parties {
+            return Err(ValidatorError::Config(format!("Invalid {}-of-{} threshold", self.threshold, self.total_parties)));
+        }
+        let mut validator_ids: Vec<usize> = shares.iter().map(|s| s.validator_id).collect();
+        validator_ids.sort_unstable();
+        validator_ids.dedup();
+        if validator_ids.len() != shares.len() {
+            return Err(ValidatorError::KeyMaterial(format!("duplicate shares among validators {:?}", validator_ids)));
+        }
+        if shares.len() != self.total_parties {
+            return Err(ValidatorError::InsufficientShares { have: shares.len(), need: self.total_parties });
         }
 
-        // For TSS, we need to combine public keys properly
-        // In real TSS, this would do curve point addition
-        // For now, we'll simulate proper combination
-        
-        // Simulate combined public keys (in real TSS this would aggregate)
-        let combined_eth_public = self.combine_eth_public_keys(&shares.iter()
+        let combined_eth_public = Self::combine_eth_public_keys(&shares.iter()
             .map(|s| &s.eth_public_key)
-            .collect::<Vec<_>>());
+            .collect::<Vec<_>>())?;
         let combined_monero_public = self.combine_monero_public_keys(&shares.iter()
             .map(|s| &s.monero_public_key)
-            .collect::<Vec<_>>());
-
-        // Generate actual Monero address using proper derivation
-        let monero_addr = self.derive_monero_address_proper(&combined_monero_public);
+            .collect::<Vec<_>>())?;
         
         Ok(JointKeys {
-            eth_address: self.derive_eth_address(&combined_eth_public),
+            eth_address: Self::derive_eth_address(&combined_eth_public),
             eth_public_key: combined_eth_public,
-            monero_address: monero_addr,
+            monero_address: Self::derive_monero_address(self.monero_network, &combined_monero_public)?,
             monero_public_key: combined_monero_public,
             share_verification_commitments: shares.iter()
                 .map(|s| s.commitment_point.clone())
@@ -250,14 +319,29 @@ mod tests {
     #[test]
     fn test_share_combination() {
         let generator = TSSKeyGenerator::new(4, 7);
-        let (share1, _) = generator.generate_keys(0).unwrap();
-        let (share2, _) = generator.generate_keys(1).unwrap();
+        let mut shares: Vec<TSSKeyShare> = (0..7).map(|id| generator.generate_keys(id).unwrap().0).collect();
         
-        let shares = vec![share1, share2];
         let combined = generator.combine_shares(&shares).unwrap();
-        
         assert!(!combined.eth_address.is_empty());
-        assert!(!combined.monero_address.is_empty());
-        assert_eq!(combined.share_verification_commitments.len(), 2);
+        assert_eq!(combined.share_verification_commitments.len(), 7);
+        
+        // A real address whose spend key is the joint key
+        let (network, spend, view) = crate::subaddress::decode_address(&combined.monero_address).unwrap();
+        assert_eq!(network, DEFAULT_MONERO_NETWORK);
+        assert_eq!(spend.compress().to_bytes().to_vec(), combined.monero_public_key);
+        assert_eq!(view, &TSSKeyGenerator::monero_view_secret(&spend) * curve25519_dalek::constants::ED25519_BASEPOINT_TABLE);
+        let mainnet = TSSKeyGenerator::new(4, 7).with_monero_network(18).combine_shares(&shares).unwrap();
+        assert!(mainnet.monero_address.starts_with('4'));
+        assert_ne!(mainnet.monero_address, combined.monero_address);
+        
+        // Order does not matter, but every share does
+        shares.reverse();
+        assert_eq!(generator.combine_shares(&shares).unwrap().eth_address, combined.eth_address);
+        
+        let subset = &shares[..4];
+        assert!(matches!(generator.combine_shares(subset), Err(ValidatorError::InsufficientShares { have: 4, need: 7 })));
+        
+        shares[0] = shares[1].clone();
+        assert!(matches!(generator.combine_shares(&shares), Err(ValidatorError::KeyMaterial(_))));
     }
 }
\ No newline at end of file
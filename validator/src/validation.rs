@@ -1,10 +1,17 @@
 use serde::{Deserialize, Serialize};
 use std::time::{SystemTime, UNIX_EPOCH};
-use anyhow::{Result, Context};
-use tracing::{info, debug, error};
+use crate::error::{Result, ValidatorError};
+use tracing::{info, debug, error, warn};
 use reqwest::Client;
+use std::fmt;
+use std::sync::atomic::{AtomicU32, Ordering};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+use crate::config::RpcOperation;
+use crate::digest_auth::DigestChallenge;
+use crate::redact::redact;
+use crate::subaddress::{SubaddressBook, SubaddressEntry};
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct MoneroTransaction {
     pub txid: String,
     pub tx_key: String,
@@ -17,6 +24,23 @@ pub struct MoneroTransaction {
     pub receiver_address: String,
 }
 
+impl fmt::Debug for MoneroTransaction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MoneroTransaction")
+            .field("txid", &self.txid)
+            .field("tx_key", &redact(&self.tx_key))
+            .field("amount", &self.amount)
+            .field("expected_amount", &self.expected_amount)
+            .field("destination_address", &self.destination_address)
+            .field("confirmations", &self.confirmations)
+            .field("in_pool", &self.in_pool)
+            .field("timestamp", &self.timestamp)
+            .field("receiver_address", &self.receiver_address)
+            .finish()
+    }
+}
+
+#[cfg(test)]
 impl MoneroTransaction {
     pub fn mock() -> Self {
         Self {
@@ -33,19 +57,94 @@ impl MoneroTransaction {
     }
 }
 
+// Outcome of checking a mint request against the daemon
+#[derive(Debug, Clone)]
+pub enum MintCheck {
+    Confirmed(MoneroTransaction),
+    // Valid so far but in the pool or below the confirmation threshold; check again later
+    SeenUnconfirmed(MoneroTransaction),
+    Rejected,
+}
+
+#[derive(Clone)]
+pub struct TxKeyCheck {
+    pub txid: String,
+    pub tx_key: String,
+    pub destination_address: String,
+}
+
+impl fmt::Debug for TxKeyCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TxKeyCheck")
+            .field("txid", &self.txid)
+            .field("tx_key", &redact(&self.tx_key))
+            .field("destination_address", &self.destination_address)
+            .finish()
+    }
+}
+
+impl TxKeyCheck {
+    fn rpc_request(&self, id: &str) -> serde_json::Value {
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": "check_tx_key",
+            "params": {
+                "txid": self.txid,
+                "tx_key": self.tx_key,
+                "address": self.destination_address,
+            }
+        })
+    }
+}
+
 pub struct MoneroValidator {
     client: Client,
     config: crate::config::MoneroConfig,
+    subaddresses: Option<SubaddressBook>,
+    auth_nonce_count: AtomicU32,
 }
 
 impl MoneroValidator {
-    pub fn new(config: crate::config::MoneroConfig) -> Self {
+    pub fn new(config: crate::config::MoneroConfig) -> Result<Self> {
         let client = Client::builder()
-            .timeout(std::time::Duration::from_secs(30))
+            .pool_max_idle_per_host(config.client.pool_max_idle_per_host)
+            .connect_timeout(std::time::Duration::from_millis(config.client.connect_timeout_ms))
             .build()
-            .expect("Failed to build HTTP client");
+            .map_err(|e| ValidatorError::Config(format!("Cannot build the monerod client: {}", e)))?;
+        
+        let subaddresses = config.subaddresses.as_ref()
+            .map(|subaddresses| SubaddressBook::from_config(&config.address, subaddresses))
+            .transpose()
+            .map_err(|e| ValidatorError::Config(format!("Invalid monero.subaddresses configuration: {}", e)))?;
             
-        Self { client, config }
+        Ok(Self { client, config, subaddresses, auth_nonce_count: AtomicU32::new(0) })
+    }
+    
+    pub fn rpc_url(&self) -> &str {
+        &self.config.rpc_url
+    }
+    
+    // Where a recipient is expected to have paid: their assigned subaddress if
+    // they have one, otherwise the primary bridge address
+    pub fn deposit_address_for(&self, recipient: &str) -> String {
+        self.subaddresses
+            .as_ref()
+            .and_then(|book| book.address_for(recipient))
+            .map(str::to_string)
+            .unwrap_or_else(|| self.config.address.clone())
+    }
+    
+    // A deposit to an assigned subaddress may only mint to that subaddress's recipient
+    pub fn deposit_matches_recipient(&self, tx: &MoneroTransaction, recipient: &str) -> bool {
+        match self.subaddress_entry(&tx.destination_address).and_then(|entry| entry.recipient.as_deref()) {
+            Some(assigned) => assigned.eq_ignore_ascii_case(recipient),
+            None => true,
+        }
+    }
+    
+    fn subaddress_entry(&self, address: &str) -> Option<&SubaddressEntry> {
+        self.subaddresses.as_ref().and_then(|book| book.lookup(address))
     }
     
     pub async fn check_transaction(
@@ -54,32 +153,150 @@ impl MoneroValidator {
         tx_key: &str,
         destination_address: &str,
     ) -> Result<Option<MoneroTransaction>> {
-        let request = serde_json::json!({
-            "jsonrpc": "2.0",
-            "id": "0",
-            "method": "check_tx_key",
-            "params": {
-                "txid": txid,
-                "tx_key": tx_key,
-                "address": destination_address,
+        let check = TxKeyCheck {
+            txid: txid.to_string(),
+            tx_key: tx_key.to_string(),
+            destination_address: destination_address.to_string(),
+        };
+        
+        let response_data = self.post_rpc(RpcOperation::CheckTx, &check.rpc_request("0")).await?;
+        
+        Ok(Self::parse_check_response(&check, &response_data))
+    }
+    
+    // Sends all checks as a single JSON-RPC 2.0 batch, falling back to one
+    // request per transaction if the daemon does not accept batches
+    pub async fn check_transactions_batch(
+        &self,
+        checks: &[TxKeyCheck],
+    ) -> Result<Vec<Option<MoneroTransaction>>> {
+        if checks.is_empty() {
+            return Ok(vec![]);
+        }
+        
+        let batch: Vec<serde_json::Value> = checks
+            .iter()
+            .enumerate()
+            .map(|(i, check)| check.rpc_request(&i.to_string()))
+            .collect();
+        
+        let responses = match self.post_rpc(RpcOperation::CheckTx, &serde_json::Value::Array(batch)).await {
+            Ok(serde_json::Value::Array(responses)) => responses,
+            Ok(other) => {
+                warn!("Monero daemon rejected batch request ({}), falling back to sequential checks", other);
+                return self.check_transactions_sequential(checks).await;
             }
-        });
+            Err(e) => {
+                warn!("Batch request failed ({}), falling back to sequential checks", e);
+                return self.check_transactions_sequential(checks).await;
+            }
+        };
         
-        let response = self.client
-            .post(&self.config.rpc_url)
-            .json(&request)
-            .send()
+        let mut results = vec![None; checks.len()];
+        for response in &responses {
+            let index = response["id"]
+                .as_str()
+                .and_then(|id| id.parse::<usize>().ok())
+                .filter(|index| *index < checks.len());
+            
+            match index {
+                Some(index) => results[index] = Self::parse_check_response(&checks[index], response),
+                None => warn!("Ignoring batch response with unknown id: {}", response["id"]),
+            }
+        }
+        
+        Ok(results)
+    }
+    
+    async fn check_transactions_sequential(
+        &self,
+        checks: &[TxKeyCheck],
+    ) -> Result<Vec<Option<MoneroTransaction>>> {
+        let mut results = Vec::with_capacity(checks.len());
+        for check in checks {
+            results.push(self.check_transaction(&check.txid, &check.tx_key, &check.destination_address).await?);
+        }
+        Ok(results)
+    }
+    
+    async fn post_rpc(&self, operation: RpcOperation, request: &serde_json::Value) -> Result<serde_json::Value> {
+        self.post_to(operation, &self.config.rpc_url, request).await
+    }
+    
+    // Posts to any monerod endpoint, e.g. the plain /get_outs next to /json_rpc
+    pub(crate) async fn post_to(&self, operation: RpcOperation, endpoint: &str, request: &serde_json::Value) -> Result<serde_json::Value> {
+        let budget = self.config.client.timeouts.for_operation(operation);
+        tokio::time::timeout(budget, self.post_once(endpoint, request))
             .await
-            .context("Failed to send Monero RPC request")?;
+            .map_err(|_| {
+                warn!("monerod {} exceeded its {:?} budget", operation, budget);
+                ValidatorError::Timeout(operation)
+            })?
+    }
+    
+    async fn post_once(&self, endpoint: &str, request: &serde_json::Value) -> Result<serde_json::Value> {
+        let mut response = self.send_rpc(endpoint, request, None).await?;
+        
+        // Answer monerod's digest challenge when credentials are configured
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            let (Some(username), Some(password)) = (&self.config.rpc_username, &self.config.rpc_password) else {
+                return Err(ValidatorError::Config("monerod requires rpc_username and rpc_password".to_string()));
+            };
+            
+            let challenge = response
+                .headers()
+                .get(reqwest::header::WWW_AUTHENTICATE)
+                .and_then(|value| value.to_str().ok())
+                .and_then(DigestChallenge::parse)
+                .ok_or_else(|| ValidatorError::MoneroRpc("Unsupported monerod authentication challenge".to_string()))?;
+            
+            let uri = url::Url::parse(endpoint)
+                .map(|url| url.path().to_string())
+                .map_err(|e| ValidatorError::Config(format!("Invalid Monero RPC URL: {}", e)))?;
+            let nc = self.auth_nonce_count.fetch_add(1, Ordering::Relaxed) + 1;
+            let cnonce = hex::encode(rand::random::<[u8; 8]>());
+            let authorization = challenge.authorization(username, password, "POST", &uri, nc, &cnonce);
+            
+            response = self.send_rpc(endpoint, request, Some(&authorization)).await?;
+            if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+                return Err(ValidatorError::Config("monerod rejected the configured RPC credentials".to_string()));
+            }
+        }
             
-        let response_data: serde_json::Value = response
+        response
             .json()
             .await
-            .context("Failed to parse Monero RPC response")?;
+            .map_err(|e| ValidatorError::MoneroRpc(format!("Failed to parse Monero RPC response: {}", e)))
+    }
+    
+    async fn send_rpc(&self, endpoint: &str, request: &serde_json::Value, authorization: Option<&str>) -> Result<reqwest::Response> {
+        let retry = &self.config.client;
+        let mut attempt = 0;
+        
+        loop {
+            let mut builder = self.client.post(endpoint).json(request);
+            if let Some(authorization) = authorization {
+                builder = builder.header(reqwest::header::AUTHORIZATION, authorization);
+            }
             
+            match builder.send().await {
+                Ok(response) => return Ok(response),
+                Err(e) if (e.is_connect() || e.is_timeout()) && attempt < retry.connect_retries => {
+                    attempt += 1;
+                    warn!("Monero RPC request failed ({}), retry {}/{}", e, attempt, retry.connect_retries);
+                    tokio::time::sleep(std::time::Duration::from_millis(retry.retry_backoff_ms)).await;
+                }
+                Err(source) => {
+                    return Err(ValidatorError::PeerUnreachable { peer: endpoint.to_string(), source });
+                }
+            }
+        }
+    }
+    
+    fn parse_check_response(check: &TxKeyCheck, response_data: &serde_json::Value) -> Option<MoneroTransaction> {
         if let Some(error) = response_data.get("error") {
-            error!("Monero RPC error: {}", error);
-            return Ok(None);
+            error!("Monero RPC error for {}: {}", check.txid, error);
+            return None;
         }
         
         let result = &response_data["result"];
@@ -103,72 +320,76 @@ impl MoneroValidator {
             .as_secs();
             
         let tx = MoneroTransaction {
-            txid: txid.to_string(),
-            tx_key: tx_key.to_string(),
+            txid: check.txid.clone(),
+            tx_key: check.tx_key.clone(),
             amount: received,
             expected_amount: received, // This should be provided separately
-            destination_address: destination_address.to_string(),
+            destination_address: check.destination_address.clone(),
             confirmations,
             in_pool,
             timestamp,
-            receiver_address: destination_address.to_string(),
+            receiver_address: check.destination_address.clone(),
         };
         
         debug!("Monero transaction: {:#?}", tx);
         
-        Ok(Some(tx))
+        Some(tx)
     }
     
-    pub async fn validate_mint_request(
+    pub async fn validate_mint_requests(
         &self,
-        txid: &str,
-        tx_key: &str,
-        destination_address: &str,
-        expected_amount: u64,
-    ) -> Result<Option<MoneroTransaction>> {
-        let mut tx = match self.check_transaction(txid, tx_key, destination_address).await? {
-            Some(tx) => tx,
-            None => return Ok(None),
-        };
+        requests: &[(TxKeyCheck, u64)],
+    ) -> Result<Vec<MintCheck>> {
+        let checks: Vec<TxKeyCheck> = requests.iter().map(|(check, _)| check.clone()).collect();
+        let results = self.check_transactions_batch(&checks).await?;
         
-        tx.expected_amount = expected_amount;
-        
-        // Validate according to bridge rules
-        let is_valid = 
-            // Has enough confirmations
-            tx.confirmations >= self.config.required_confirmations &&
-            // Not in mempool
-            !tx.in_pool &&
-            // Amount matches what was requested
-            tx.amount == expected_amount &&
-            // Destination matches our monitored address
-            tx.destination_address == self.config.address;
-            
-        if is_valid {
+        Ok(results
+            .into_iter()
+            .zip(requests)
+            .map(|(tx, (_, expected_amount))| match tx {
+                Some(tx) => self.apply_bridge_rules(tx, *expected_amount),
+                None => MintCheck::Rejected,
+            })
+            .collect())
+    }
+    
+    fn apply_bridge_rules(&self, mut tx: MoneroTransaction, expected_amount: u64) -> MintCheck {
+        // 0 means the request stated no amount (the contract's MintRequested
+        // event carries none)
+        if expected_amount == 0 && !self.config.accept_unstated_amount {
+            warn!("Mint request for {} states no amount and accept_unstated_amount is off, rejecting", tx.txid);
+            return MintCheck::Rejected;
+        }
+        tx.expected_amount = if expected_amount == 0 { tx.amount } else { expected_amount };
+        
+        if self.meets_bridge_rules(&tx) {
             info!("Valid Monero transaction found: {} with {} XMR", tx.txid, tx.amount as f64 / 1e12);
-            Ok(Some(tx))
+            MintCheck::Confirmed(tx)
+        } else if self.meets_deposit_rules(&tx) {
+            info!("Monero transaction {} seen with {} confirmation(s){}, waiting",
+                tx.txid, tx.confirmations, if tx.in_pool { " in pool" } else { "" });
+            MintCheck::SeenUnconfirmed(tx)
         } else {
             debug!("Invalid Monero transaction: {:#?}", tx);
-            Ok(None)
+            MintCheck::Rejected
         }
     }
     
-    pub async fn wait_for_confirmations(
-        &self,
-        txid: &str,
-        tx_key: &str,
-        destination_address: &str,
-        expected_amount: u64,
-    ) -> Result<MoneroTransaction> {
-        loop {
-            match self.validate_mint_request(txid, tx_key, destination_address, expected_amount).await? {
-                Some(tx) if tx.confirmations >= self.config.required_confirmations => return Ok(tx),
-                _ => {
-                    info!("Waiting for Monero confirmations...");
-                    tokio::time::sleep(std::time::Duration::from_secs(self.config.check_interval_secs)).await;
-                }
-            }
-        }
+    fn meets_bridge_rules(&self, tx: &MoneroTransaction) -> bool {
+        // Has enough confirmations for the amount being bridged
+        tx.confirmations >= self.config.required_confirmations_for(tx.amount.max(tx.expected_amount)) &&
+        // Not in mempool
+        !tx.in_pool &&
+        self.meets_deposit_rules(tx)
+    }
+    
+    // Everything but confirmation depth
+    fn meets_deposit_rules(&self, tx: &MoneroTransaction) -> bool {
+        // Something was paid, within tolerance of what was requested
+        tx.amount > 0 &&
+        self.config.amount_tolerance.accepts(tx.amount, tx.expected_amount) &&
+        // Destination is the bridge address or one of its monitored subaddresses
+        (tx.destination_address == self.config.address || self.subaddress_entry(&tx.destination_address).is_some())
     }
 }
 
@@ -176,17 +397,352 @@ impl MoneroValidator {
 mod tests {
     use super::*;
     
-    #[test]
-    fn test_monero_validator() {
-        let config = crate::config::MoneroConfig {
+    use crate::config::{AmountTolerance, ConfirmationTier, MoneroConfig, MoneroNetwork, RpcClientConfig, SubaddressConfig, SubaddressRecipient};
+    use crate::test_support::FakeMonerod;
+    
+    const BRIDGE_ADDRESS: &str = "9wuZdcgYHVnNz68iXnjhf1xXr4CN6Q9C5wgd98TiBYMXq5oUqRcwEyVK5GHH6mhMM8xj4qibLzB9QNyVvGzE5cQS6QLh9vW";
+    
+    fn test_config() -> MoneroConfig {
+        MoneroConfig {
             rpc_url: "http://localhost:38081/json_rpc".to_string(),
-            address: "9wuZdcgYHVnNz68iXnjhf1xXr4CN6Q9C5wgd98TiBYMXq5oUqRcwEyVK5GHH6mhMM8xj4qibLzB9QNyVvGzE5cQS6QLh9vW".to_string(),
+            address: BRIDGE_ADDRESS.to_string(),
+            network: MoneroNetwork::Stagenet,
             required_confirmations: 6,
             check_interval_secs: 1,
-        };
+            poll_jitter: 0.2,
+            randomize_phase: false,
+            confirmation_tiers: vec![],
+            amount_tolerance: AmountTolerance::default(),
+            accept_unstated_amount: false,
+            payout_fee: 60_000_000,
+            ring_size: 16,
+            client: RpcClientConfig { retry_backoff_ms: 10, ..RpcClientConfig::default() },
+            subaddresses: None,
+            wallet_rpc: None,
+            rpc_username: None,
+            rpc_password: None,
+        }
+    }
+    
+    fn deposit(amount: u64, confirmations: u64) -> MoneroTransaction {
+        MoneroTransaction {
+            amount,
+            expected_amount: amount,
+            destination_address: BRIDGE_ADDRESS.to_string(),
+            receiver_address: BRIDGE_ADDRESS.to_string(),
+            confirmations,
+            ..MoneroTransaction::mock()
+        }
+    }
+    
+    #[test]
+    fn test_monero_validator() {
+        let config = test_config();
         
         // Note: This would require a live Monero node for proper testing
-        let validator = MoneroValidator::new(config.clone());
+        let validator = MoneroValidator::new(config.clone()).unwrap();
         assert_eq!(validator.config.address, config.address);
     }
-}
\ No newline at end of file
+    
+    #[test]
+    fn test_amount_tolerance() {
+        let expected = 1_000_000_000_000;
+        let with_expected = |amount| MoneroTransaction { expected_amount: expected, ..deposit(amount, 6) };
+        
+        // Exact by default
+        let validator = MoneroValidator::new(test_config()).unwrap();
+        assert!(validator.meets_bridge_rules(&with_expected(expected)));
+        assert!(!validator.meets_bridge_rules(&with_expected(expected + 1)));
+        
+        // 0.5% either way
+        let mut config = test_config();
+        config.amount_tolerance = AmountTolerance { absolute: 0, relative_bps: 50 };
+        let validator = MoneroValidator::new(config.clone()).unwrap();
+        
+        assert!(validator.meets_bridge_rules(&with_expected(expected)));
+        assert!(validator.meets_bridge_rules(&with_expected(expected + 5_000_000_000)));
+        assert!(!validator.meets_bridge_rules(&with_expected(expected + 5_000_000_001)));
+        assert!(validator.meets_bridge_rules(&with_expected(expected - 5_000_000_000)));
+        assert!(!validator.meets_bridge_rules(&with_expected(expected - 5_000_000_001)));
+        assert!(!validator.meets_bridge_rules(&with_expected(expected / 2)));
+        
+        // Small requests get a small allowance, not a fixed one
+        let small = |amount| MoneroTransaction { expected_amount: 10_000_000, ..deposit(amount, 6) };
+        assert!(validator.meets_bridge_rules(&small(10_050_000)));
+        assert!(!validator.meets_bridge_rules(&small(10_000_000 + 900_000_000)));
+        
+        // The absolute cap narrows the allowance on large requests
+        config.amount_tolerance.absolute = 1_000_000_000;
+        let validator = MoneroValidator::new(config.clone()).unwrap();
+        assert!(validator.meets_bridge_rules(&with_expected(expected + 1_000_000_000)));
+        assert!(!validator.meets_bridge_rules(&with_expected(expected + 1_000_000_001)));
+        assert!(!validator.meets_bridge_rules(&with_expected(expected - 4_000_000_000)));
+        
+        // More than 100% is treated as 100%: at most double, never nothing
+        config.amount_tolerance = AmountTolerance { absolute: 0, relative_bps: 50_000 };
+        let validator = MoneroValidator::new(config).unwrap();
+        assert!(validator.meets_bridge_rules(&with_expected(2 * expected)));
+        assert!(!validator.meets_bridge_rules(&with_expected(2 * expected + 1)));
+        assert!(!validator.meets_bridge_rules(&with_expected(0)));
+    }
+    
+    #[test]
+    fn test_confirmation_tiers() {
+        let mut config = test_config();
+        config.confirmation_tiers = vec![
+            ConfirmationTier { min_amount: 10_000_000_000_000, required_confirmations: 20 },
+            ConfirmationTier { min_amount: 1_000_000_000_000, required_confirmations: 10 },
+        ];
+        
+        assert_eq!(config.required_confirmations_for(10_000_000_000), 6);
+        assert_eq!(config.required_confirmations_for(1_000_000_000_000), 10);
+        assert_eq!(config.required_confirmations_for(25_000_000_000_000), 20);
+        
+        let validator = MoneroValidator::new(config).unwrap();
+        
+        // A 0.01 XMR burn clears at the base depth
+        assert!(validator.meets_bridge_rules(&deposit(10_000_000_000, 6)));
+        
+        // A 10 XMR burn needs the higher tier
+        assert!(!validator.meets_bridge_rules(&deposit(10_000_000_000_000, 6)));
+        assert!(!validator.meets_bridge_rules(&deposit(10_000_000_000_000, 19)));
+        assert!(validator.meets_bridge_rules(&deposit(10_000_000_000_000, 20)));
+    }
+    
+    #[test]
+    fn test_poll_interval_stays_within_jitter_band() {
+        let mut config = test_config();
+        config.check_interval_secs = 10;
+        
+        let intervals: Vec<f64> = (0..1000).map(|_| config.poll_interval().as_secs_f64()).collect();
+        assert!(intervals.iter().all(|&secs| (8.0..=12.0).contains(&secs)));
+        
+        // Actually spread out, not pinned to either edge
+        assert!(intervals.iter().any(|&secs| secs < 9.5));
+        assert!(intervals.iter().any(|&secs| secs > 10.5));
+        
+        config.poll_jitter = 0.0;
+        assert_eq!(config.poll_interval(), std::time::Duration::from_secs(10));
+        
+        assert_eq!(config.initial_poll_delay(), std::time::Duration::ZERO);
+        config.randomize_phase = true;
+        assert!((0..100).all(|_| config.initial_poll_delay() < std::time::Duration::from_secs(10)));
+    }
+    
+    #[test]
+    fn test_subaddress_deposit_maps_to_recipient() {
+        const RECIPIENT: &str = "0x00000000000000000000000000000000000000aa";
+        
+        use curve25519_dalek::constants::ED25519_BASEPOINT_POINT;
+        use curve25519_dalek::scalar::Scalar;
+        
+        // A well-formed stagenet wallet, as the placeholder bridge address does not decode
+        let view_secret = Scalar::from(7u64);
+        let spend_public = Scalar::from(11u64) * ED25519_BASEPOINT_POINT;
+        let primary = crate::subaddress::encode_address(24, &spend_public, &(view_secret * ED25519_BASEPOINT_POINT));
+        
+        let mut config = test_config();
+        config.address = primary.clone();
+        config.subaddresses = Some(SubaddressConfig {
+            view_key: hex::encode(view_secret.as_bytes()),
+            account: 0,
+            count: 10,
+            recipients: vec![SubaddressRecipient { index: 4, recipient: RECIPIENT.to_string() }],
+        });
+        let validator = MoneroValidator::new(config).unwrap();
+        
+        let subaddress = validator.deposit_address_for(RECIPIENT);
+        assert!(subaddress.starts_with('7'));
+        assert_eq!(validator.deposit_address_for("0x00000000000000000000000000000000000000bb"), primary);
+        
+        let tx = MoneroTransaction { destination_address: subaddress, ..deposit(1_000_000_000_000, 10) };
+        assert!(validator.meets_bridge_rules(&tx));
+        assert!(validator.deposit_matches_recipient(&tx, &RECIPIENT.to_uppercase().replace("0X", "0x")));
+        assert!(!validator.deposit_matches_recipient(&tx, "0x00000000000000000000000000000000000000bb"));
+        
+        // A subaddress of some other wallet is not ours
+        let other = crate::subaddress::derive_subaddress(36, &Scalar::from(8u64), &spend_public, 0, 4);
+        let tx = MoneroTransaction { destination_address: other, ..deposit(1_000_000_000_000, 10) };
+        assert!(!validator.meets_bridge_rules(&tx));
+        
+        // A bad view key is a configuration error, not a panic
+        let mut config = test_config();
+        config.address = primary;
+        config.subaddresses = Some(SubaddressConfig {
+            view_key: "not hex".to_string(),
+            account: 0,
+            count: 10,
+            recipients: vec![],
+        });
+        assert!(matches!(MoneroValidator::new(config), Err(ValidatorError::Config(_))));
+    }
+    
+    // Knows tx_a and tx_b, and answers batches out of order
+    fn mock_daemon() -> FakeMonerod {
+        FakeMonerod::new()
+            .with_transfer("tx_a", 1_000_000_000_000, 10)
+            .with_transfer("tx_b", 2_000_000_000_000, 10)
+    }
+    
+    fn batch_checks() -> Vec<TxKeyCheck> {
+        ["tx_a", "tx_missing", "tx_b"]
+            .iter()
+            .map(|txid| TxKeyCheck {
+                txid: txid.to_string(),
+                tx_key: "key".to_string(),
+                destination_address: BRIDGE_ADDRESS.to_string(),
+            })
+            .collect()
+    }
+    
+    #[tokio::test]
+    async fn test_check_transactions_batch() {
+        let mut config = test_config();
+        config.rpc_url = mock_daemon().start().await;
+        let validator = MoneroValidator::new(config).unwrap();
+        
+        let results = validator.check_transactions_batch(&batch_checks()).await.unwrap();
+        
+        assert_eq!(results.len(), 3);
+        let tx_a = results[0].as_ref().unwrap();
+        assert_eq!(tx_a.txid, "tx_a");
+        assert_eq!(tx_a.amount, 1_000_000_000_000);
+        assert!(results[1].is_none());
+        let tx_b = results[2].as_ref().unwrap();
+        assert_eq!(tx_b.txid, "tx_b");
+        assert_eq!(tx_b.amount, 2_000_000_000_000);
+    }
+    
+    #[tokio::test]
+    async fn test_check_transactions_batch_falls_back_to_sequential() {
+        let mut config = test_config();
+        config.rpc_url = mock_daemon().without_batches().start().await;
+        let validator = MoneroValidator::new(config).unwrap();
+        
+        let results = validator.check_transactions_batch(&batch_checks()).await.unwrap();
+        
+        assert_eq!(results[0].as_ref().unwrap().amount, 1_000_000_000_000);
+        assert!(results[1].is_none());
+        assert_eq!(results[2].as_ref().unwrap().amount, 2_000_000_000_000);
+    }
+    
+    #[tokio::test]
+    async fn test_unreachable_daemon_is_retryable() {
+        // Grab a free port and close it again so nothing is listening
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+        
+        let mut config = test_config();
+        config.rpc_url = format!("http://{}/json_rpc", addr);
+        let validator = MoneroValidator::new(config).unwrap();
+        
+        let err = validator.check_transaction("tx_a", "key", BRIDGE_ADDRESS).await.unwrap_err();
+        assert!(matches!(err, ValidatorError::PeerUnreachable { .. }));
+        assert!(err.is_retryable());
+    }
+    
+    #[tokio::test]
+    async fn test_connect_error_is_retried() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+        
+        // The daemon only comes up after the first attempt has been refused
+        tokio::spawn(async move {
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+            mock_daemon().start_at(addr).await;
+        });
+        
+        let mut config = test_config();
+        config.rpc_url = format!("http://{}/json_rpc", addr);
+        config.client = RpcClientConfig { connect_retries: 20, retry_backoff_ms: 25, ..RpcClientConfig::default() };
+        let validator = MoneroValidator::new(config).unwrap();
+        
+        let tx = validator.check_transaction("tx_a", "key", BRIDGE_ADDRESS).await.unwrap().unwrap();
+        assert_eq!(tx.amount, 1_000_000_000_000);
+    }
+    
+    #[tokio::test]
+    async fn test_slow_call_times_out_under_its_own_budget() {
+        use crate::config::RpcTimeouts;
+        use crate::decoy::OutputSource;
+        
+        // Every call takes 300ms: too slow for check_tx, fine for the distribution
+        let daemon = mock_daemon()
+            .with_result("get_output_distribution", serde_json::json!({ "distributions": [{ "distribution": [1, 2, 3] }] }))
+            .with_delay(std::time::Duration::from_millis(300));
+        
+        let mut config = test_config();
+        config.rpc_url = daemon.start().await;
+        config.client.timeouts = RpcTimeouts { check_tx_ms: 100, output_distribution_ms: 2_000, ..RpcTimeouts::default() };
+        let validator = MoneroValidator::new(config).unwrap();
+        
+        let err = validator.check_transaction("tx_a", "key", BRIDGE_ADDRESS).await.unwrap_err();
+        assert!(matches!(err, ValidatorError::Timeout(RpcOperation::CheckTx)), "{}", err);
+        assert_eq!(err.to_string(), "check_tx timed out");
+        assert!(err.is_retryable());
+        
+        assert_eq!(validator.output_distribution().await.unwrap().cumulative, vec![1, 2, 3]);
+    }
+    
+    #[tokio::test]
+    async fn test_rpc_error_is_not_retried() {
+        // Knows no transfers, so every check_tx_key is an RPC error
+        let daemon = FakeMonerod::new();
+        let mut config = test_config();
+        config.rpc_url = daemon.clone().start().await;
+        let validator = MoneroValidator::new(config).unwrap();
+        
+        assert!(validator.check_transaction("tx_a", "key", BRIDGE_ADDRESS).await.unwrap().is_none());
+        assert_eq!(daemon.hits(), 1);
+    }
+    
+    #[tokio::test]
+    async fn test_in_pool_deposit_is_seen_then_confirmed() {
+        let daemon = FakeMonerod::new().with_transfer("tx_a", 1_000_000_000_000, 0);
+        let mut config = test_config();
+        config.rpc_url = daemon.clone().start().await;
+        let validator = MoneroValidator::new(config).unwrap();
+        let requests = vec![(batch_checks().remove(0), 1_000_000_000_000)];
+        
+        let results = validator.validate_mint_requests(&requests).await.unwrap();
+        assert!(matches!(&results[0], MintCheck::SeenUnconfirmed(tx) if tx.in_pool));
+        
+        daemon.set_transfer("tx_a", 1_000_000_000_000, 3);
+        let results = validator.validate_mint_requests(&requests).await.unwrap();
+        assert!(matches!(&results[0], MintCheck::SeenUnconfirmed(tx) if tx.confirmations == 3));
+        
+        daemon.set_transfer("tx_a", 1_000_000_000_000, 6);
+        let results = validator.validate_mint_requests(&requests).await.unwrap();
+        assert!(matches!(&results[0], MintCheck::Confirmed(tx) if tx.confirmations == 6));
+        
+        // A wrong amount is rejected outright rather than waited on
+        let wrong_amount = vec![(batch_checks().remove(0), 5)];
+        let results = validator.validate_mint_requests(&wrong_amount).await.unwrap();
+        assert!(matches!(results[0], MintCheck::Rejected));
+    }
+    
+    #[tokio::test]
+    async fn test_digest_challenge_is_answered() {
+        let mut config = test_config();
+        config.rpc_url = mock_daemon().with_login("bridge", "secret").start().await;
+        
+        config.rpc_username = Some("bridge".to_string());
+        config.rpc_password = Some("secret".to_string());
+        let tx = MoneroValidator::new(config.clone()).unwrap()
+            .check_transaction("tx_a", "key", BRIDGE_ADDRESS).await.unwrap().unwrap();
+        assert_eq!(tx.amount, 1_000_000_000_000);
+        
+        config.rpc_password = Some("wrong".to_string());
+        let err = MoneroValidator::new(config.clone()).unwrap()
+            .check_transaction("tx_a", "key", BRIDGE_ADDRESS).await.unwrap_err();
+        assert!(matches!(err, ValidatorError::Config(_)));
+        
+        config.rpc_username = None;
+        config.rpc_password = None;
+        let err = MoneroValidator::new(config).unwrap()
+            .check_transaction("tx_a", "key", BRIDGE_ADDRESS).await.unwrap_err();
+        assert!(matches!(err, ValidatorError::Config(_)));
+    }
+}
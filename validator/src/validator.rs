@@ -1,21 +1,48 @@
-use anyhow::Result;
-use tracing::{info, error};
+use crate::error::{Result, ValidatorError};
+use tracing::{debug, info, warn};
 use std::sync::Arc;
-use serde_json;
 use hex;
-use sha2::{Sha256, Digest};
 
-use crate::config::Config;
-use crate::validation::MoneroValidator;
+use crate::config::{Config, DiscoveryMode, SigningScheme};
+use crate::validation::{MintCheck, MoneroValidator, TxKeyCheck};
 use crate::signing::SigningCoordinator;
-use crate::network::{NetworkClient, ConsensusMessage};
-use crate::{validation::MoneroTransaction, signing::{SigningRequest, SigningResult}};
+use crate::network::{ConsensusMessage, NetworkClient, NetworkState};
+use crate::membership::{self, Committee};
+use crate::tss::TSSKeyShare;
+use crate::transport::TransportKey;
+use crate::keygen;
+use crate::event_cursor::{BlockCursor, BurnEvent, BurnEventFeed, EventDiscovery, EventFeed, MintEventDiscovery, MintEventFeed};
+use crate::eth_events::{BurnLogs, MintRequestedLogs, WsNewHeads};
+use crate::payout::{PayoutBuilder, PayoutFunding, PayoutProposal, UnsignedTransaction, PAYOUT_PROPOSAL};
+use crate::frost::{self, FrostSigner};
+use crate::decoy::DecoySelector;
+use crate::consensus::{select_leader, MessageRoundDriver, RoundCoordinator};
+use curve25519_dalek::scalar::Scalar;
+use crate::reservation::{MintReservations, ReservationClient};
+use crate::party_registry::PartyRegistry;
+use crate::wallet_rpc::MoneroWalletRpc;
+use crate::funding::WalletFunding;
+use crate::broadcast::PayoutRelay;
+use crate::{validation::MoneroTransaction, signing::{verify_threshold_signature, Direction, MintOperation, SigningRequest, SigningResult}};
 
 pub struct ValidatorNode {
     config: Config,
     validator_id: usize,
-    monero_validator: MoneroValidator,
-    signing_coordinator: Option<SigningCoordinator>,
+    key_share: TSSKeyShare,
+    transport_key: Arc<TransportKey>,
+    monero_validator: Arc<MoneroValidator>,
+    signing_coordinator: Option<Arc<SigningCoordinator>>,
+    mint_events: Option<MintEventDiscovery>,
+    burn_events: Option<BurnEventFeed>,
+    payout_funding: Option<Arc<dyn PayoutFunding>>,
+    frost_signer: Option<Arc<FrostSigner>>,
+    wallet_rpc: Option<Arc<MoneroWalletRpc>>,
+    reservations: Option<ReservationClient>,
+    // Deposits seen in the pool or below the confirmation threshold, or whose
+    // signing failed, rechecked each poll
+    unconfirmed: Vec<MintRequest>,
+    // Burns whose payout failed, retried each poll, with the next attempt number
+    pending_burns: Vec<(BurnEvent, u32)>,
     network_client: Arc<NetworkClient>,
     shutdown: tokio::sync::Notify,
 }
@@ -24,38 +51,196 @@ impl ValidatorNode {
     pub fn new(
         config: Config,
         validator_id: usize,
-        monero_validator: MoneroValidator,
+        key_share: TSSKeyShare,
+        transport_key: Arc<TransportKey>,
+        monero_validator: Arc<MoneroValidator>,
         network_client: Arc<NetworkClient>,
     ) -> Self {
         Self {
             config,
             validator_id,
+            key_share,
+            transport_key,
             monero_validator,
             signing_coordinator: None,
+            mint_events: None,
+            burn_events: None,
+            payout_funding: None,
+            frost_signer: None,
+            wallet_rpc: None,
+            reservations: None,
+            unconfirmed: Vec::new(),
+            pending_burns: Vec::new(),
             network_client,
             shutdown: tokio::sync::Notify::new(),
         }
     }
     
+    pub fn with_signing_coordinator(mut self, coordinator: Arc<SigningCoordinator>) -> Self {
+        self.signing_coordinator = Some(coordinator);
+        self
+    }
+    
+    pub fn with_reservations(mut self, reservations: ReservationClient) -> Self {
+        self.reservations = Some(reservations);
+        self
+    }
+    
+    pub fn with_mint_events(mut self, discovery: MintEventDiscovery) -> Self {
+        self.mint_events = Some(discovery);
+        self
+    }
+    
+    pub fn with_burn_events(mut self, feed: BurnEventFeed) -> Self {
+        self.burn_events = Some(feed);
+        self
+    }
+    
+    pub fn with_payout_funding(mut self, funding: Arc<dyn PayoutFunding>) -> Self {
+        self.payout_funding = Some(funding);
+        self
+    }
+    
+    pub fn with_wallet_rpc(mut self, wallet: Arc<MoneroWalletRpc>) -> Self {
+        self.wallet_rpc = Some(wallet);
+        self
+    }
+    
+    pub fn with_frost_signer(mut self, signer: Arc<FrostSigner>) -> Self {
+        self.frost_signer = Some(signer);
+        self
+    }
+    
     pub async fn run(config_path: String, port: u16, validator_id: usize) -> Result<()> {
         info!("Starting validator {} on port {}", validator_id, port);
         
         // Load configuration
         let config = Config::load(&config_path)?;
         
+        // Load this validator's key share for heartbeat attestations
+        let validator_keys = keygen::load_validator_keys(&config, validator_id).await?;
+        
+        // Network messages are signed with a separate, rotatable transport key
+        let transport_key = Arc::new(TransportKey::load_or_generate(keygen::transport_key_path(&config, validator_id)).await?);
+        
         // Initialize Monero validator
-        let monero_validator = MoneroValidator::new(config.monero.clone());
+        let monero_validator = Arc::new(MoneroValidator::new(config.monero.clone())?);
         
-        // Set up networking
-        let network_client = Arc::new(NetworkClient::new(config.network.clone()));
+        // Mints are reserved with a single authority so the relay and validators can't both submit one
+        let authority = config.validators.reservation_authority.ok_or_else(|| ValidatorError::Config(
+            "validators.reservation_authority must name the validator hosting mint reservations".to_string(),
+        ))?;
+        let (reservations, hosted_reservations) = if authority == validator_id {
+            let store_path = format!("{}/{}/mint_reservations.json", config.mpc.key_gen_output_path, validator_id);
+            let ttl = std::time::Duration::from_secs(config.validators.reservation_ttl_secs);
+            let store = Arc::new(MintReservations::open(store_path, ttl).await?);
+            (ReservationClient::local(store.clone(), &transport_key), Some(store))
+        } else {
+            let peer = config.network.peers.iter().find(|p| p.id == authority).ok_or_else(|| ValidatorError::Config(
+                format!("Reservation authority {} is not among network.peers", authority),
+            ))?;
+            (ReservationClient::remote(peer.url.clone(), transport_key.clone()), None)
+        };
+        
+        // Set up networking, verifying heartbeats against the committee's share keys
+        let committee = Committee::from_mpc_config(&config.mpc)?;
+        // The key file's joint_keys only cover this validator's own share
+        let joint_public_key = committee.joint_public_key()?;
+        let party_registry = PartyRegistry::open(
+            format!("{}/{}/party_registry.json", config.mpc.key_gen_output_path, validator_id),
+            config.mpc.total_parties,
+        ).await?;
+        let mut network_state = NetworkState::new(validator_id, config.network.bind_address.port())
+            .with_committee(committee)
+            .with_party_registry(Arc::new(party_registry))
+            .with_joint_public_key(joint_public_key)
+            .with_rate_limit(config.network.rate_limit.clone())
+            .with_liveness(config.network.liveness.clone());
+        if let Some(store) = hosted_reservations {
+            let clients = config.validators.reservation_clients.iter()
+                .map(|key| hex::decode(key.trim_start_matches("0x")))
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            network_state = network_state.with_reservations(store, clients);
+        }
+        for peer in config.network.peers.iter().filter(|p| p.id != validator_id) {
+            network_state.add_peer(peer.id, peer.url.as_str().trim_end_matches('/').to_string()).await;
+        }
+        let network_client = Arc::new(NetworkClient::with_state(network_state));
+        
+        // Signed operations are persisted next to the key share so replays survive restarts
+        let signed_operations_path = format!("{}/{}/signed_operations.json", config.mpc.key_gen_output_path, validator_id);
+        let signing_coordinator = SigningCoordinator::open(validator_id, signed_operations_path).await?;
+        
+        // Payouts are approved with a FROST share summed from the peers' dealings
+        let frost_share = match keygen::load_frost_share(&config, &validator_keys).await {
+            Ok(Some(share)) => Some(share),
+            Ok(None) => {
+                warn!("Not every validator has dealt a FROST share yet; payouts will not be approved");
+                None
+            }
+            Err(e) => {
+                warn!("Cannot assemble this validator's FROST share, payouts will not be approved: {}", e);
+                None
+            }
+        };
         
         // Create validator node
         let validator = Self::new(
             config.clone(),
             validator_id,
-            monero_validator,
+            validator_keys.key_share,
+            transport_key.clone(),
+            monero_validator.clone(),
             network_client.clone(),
-        );
+        )
+        .with_signing_coordinator(Arc::new(signing_coordinator))
+        .with_reservations(reservations);
+        let validator = match frost_share {
+            Some(share) => validator.with_frost_signer(Arc::new(
+                FrostSigner::new(validator_id, network_client.state().clone(), transport_key, share)
+                    .with_round_timeout(std::time::Duration::from_secs(config.mpc.signing_timeout_secs)),
+            )),
+            None => validator,
+        };
+        let validator = match config.monero.wallet_rpc {
+            Some(ref wallet_config) => {
+                let wallet = Arc::new(MoneroWalletRpc::new(wallet_config.clone()));
+                Self::prepare_wallet(&wallet, &config).await;
+                let validator = validator.with_wallet_rpc(wallet.clone());
+                match wallet_config.view_key {
+                    Some(ref view_key) => validator.with_payout_funding(Arc::new(
+                        WalletFunding::new(wallet, monero_validator.clone(), view_key)?,
+                    )),
+                    None => {
+                        warn!("monero.wallet_rpc.view_key is not set; burns will not be funded with payout transactions");
+                        validator
+                    }
+                }
+            }
+            None => validator,
+        };
+        
+        // Mint requests come from the bridge contract's logs, polled or pushed per ethereum.discovery
+        let mint_cursor = BlockCursor::open(
+            format!("{}/{}/mint_cursor.json", config.mpc.key_gen_output_path, validator_id),
+            config.ethereum.finality_depth,
+            config.ethereum.start_block,
+        ).await?;
+        let mint_source = MintRequestedLogs::new(&config.ethereum.rpc_url, &config.ethereum.contract_address)
+            .with_timeouts(config.ethereum.rpc_timeouts.clone());
+        let mint_events = Self::mint_discovery(&config, EventFeed::new(Arc::new(mint_source), mint_cursor)).await?;
+        
+        // Burns come from the same contract's Burn logs, on a cursor of their own
+        let burn_cursor = BlockCursor::open(
+            format!("{}/{}/burn_cursor.json", config.mpc.key_gen_output_path, validator_id),
+            config.ethereum.finality_depth,
+            config.ethereum.start_block,
+        ).await?;
+        let burn_source = BurnLogs::new(&config.ethereum.rpc_url, &config.ethereum.contract_address)
+            .with_timeouts(config.ethereum.rpc_timeouts.clone());
+        let validator = validator
+            .with_mint_events(mint_events)
+            .with_burn_events(EventFeed::new(Arc::new(burn_source), burn_cursor));
         
         // Start services
         let mut handles = vec![];
@@ -100,13 +285,64 @@ impl ValidatorNode {
         Ok(())
     }
     
+    // A websocket that can't be reached at startup degrades to polling rather
+    // than stopping the node
+    async fn mint_discovery(config: &Config, feed: MintEventFeed) -> Result<MintEventDiscovery> {
+        match (config.ethereum.discovery_mode()?, &config.ethereum.ws_url) {
+            (DiscoveryMode::Subscribe, Some(ws_url)) => match WsNewHeads::connect(ws_url).await {
+                Ok(heads) => Ok(EventDiscovery::push(feed, Box::new(heads))),
+                Err(e) => {
+                    warn!("Could not subscribe to new heads, polling for mint requests instead: {}", e);
+                    Ok(EventDiscovery::Poll(feed))
+                }
+            },
+            _ => Ok(EventDiscovery::Poll(feed)),
+        }
+    }
+    
+    // Makes the wallet scan every monitored subaddress and reports what it holds.
+    // An unreachable wallet is not fatal here; later calls will retry it.
+    async fn prepare_wallet(wallet: &MoneroWalletRpc, config: &Config) {
+        if let Some(ref subaddresses) = config.monero.subaddresses {
+            if subaddresses.account != wallet.account() {
+                warn!("Wallet RPC uses account {} but subaddresses are monitored on account {}", wallet.account(), subaddresses.account);
+            } else if let Err(e) = wallet.ensure_subaddresses(subaddresses.count).await {
+                warn!("Could not create monitored subaddresses in the bridge wallet: {}", e);
+            }
+        }
+        
+        match tokio::try_join!(wallet.get_balance(), wallet.incoming_transfers()) {
+            Ok((balance, outputs)) => info!(
+                "Bridge wallet holds {} piconero ({} unlocked) in {} unspent outputs",
+                balance.balance, balance.unlocked_balance, outputs.len()
+            ),
+            Err(e) => warn!("Bridge wallet RPC unavailable: {}", e),
+        }
+    }
+    
     async fn run_monero_monitoring(&mut self) -> Result<()> {
         info!("Starting Monero transaction monitoring for validator {}", self.validator_id);
         
+        tokio::select! {
+            _ = tokio::time::sleep(self.config.monero.initial_poll_delay()) => {}
+            _ = self.shutdown.notified() => {
+                return Ok(());
+            }
+        }
+        
         loop {
             tokio::select! {
-                _ = tokio::time::sleep(tokio::time::Duration::from_secs(self.config.monero.check_interval_secs)) => {
-                    self.process_pending_transactions().await?;
+                _ = tokio::time::sleep(self.config.monero.poll_interval()) => {
+                    match self.process_pending_transactions().await {
+                        Ok(_) => {}
+                        Err(e) if e.is_retryable() => warn!("Transient error processing mint requests: {}", e),
+                        Err(e) => return Err(e),
+                    }
+                    match self.process_burn_events().await {
+                        Ok(()) => {}
+                        Err(e) if e.is_retryable() => warn!("Transient error processing burns: {}", e),
+                        Err(e) => return Err(e),
+                    }
                 }
                 _ = self.shutdown.notified() => {
                     break;
@@ -117,80 +353,488 @@ impl ValidatorNode {
         Ok(())
     }
     
+    // The mint cursor is only committed once the fetched requests have been
+    // handled; on an error the queue is restored and the cursor rewound, so
+    // nothing fetched or queued is dropped
     async fn process_pending_transactions(&mut self) -> Result<Vec<MoneroTransaction>> {
-        let pending_tickets = self.fetch_pending_mint_requests().await?;
+        let queued = std::mem::take(&mut self.unconfirmed);
+        let (fetched, through_block) = match self.fetch_pending_mint_requests().await {
+            Ok(fetched) => fetched,
+            Err(e) => {
+                self.unconfirmed = queued;
+                return Err(e);
+            }
+        };
         
+        let mut pending_tickets = queued.clone();
+        for request in fetched {
+            // A rewound cursor can hand out a request that is still queued
+            if !pending_tickets.iter().any(|queued| queued.txid == request.txid) {
+                pending_tickets.push(request);
+            }
+        }
+        
+        match self.validate_and_sign(pending_tickets).await {
+            Ok(validated) => {
+                // Queued tickets live only in memory, so the cursor stays below the oldest
+                let commit_to = self.unconfirmed.iter()
+                    .map(|request| request.block_number.saturating_sub(1))
+                    .fold(through_block, u64::min);
+                if let Some(ref discovery) = self.mint_events {
+                    discovery.commit(commit_to).await?;
+                }
+                Ok(validated)
+            }
+            Err(e) => {
+                self.unconfirmed = queued;
+                if let Some(ref discovery) = self.mint_events {
+                    discovery.rewind().await;
+                }
+                Err(e)
+            }
+        }
+    }
+    
+    async fn validate_and_sign(&mut self, pending_tickets: Vec<MintRequest>) -> Result<Vec<MoneroTransaction>> {
         let mut validated_transactions = vec![];
         
-        for request in pending_tickets {
-            if let Some(tx) = self.monero_validator
-                .validate_mint_request(
-                    &request.txid,
-                    &request.tx_key,  
-                    &request.destination,
-                    request.amount,
-                )
-                .await?
-            {
-                validated_transactions.push(tx.clone());
-                
-                let signing_request = SigningRequest {
-                    tx_secret: hex::decode(&request.tx_key)?,
-                    amount: request.amount,
-                    operation_hash: self.calculate_operation_hash(&request)?,
-                    timestamp: tx.timestamp,
-                    nonce: self.generate_nonce(&request)?,
-                    monero_tx: tx,
-                };
-                
-                self.initiate_threshold_signing(signing_request).await?;
+        // Check all tickets against monerod in a single batched round-trip
+        let checks: Vec<(TxKeyCheck, u64)> = pending_tickets
+            .iter()
+            .map(|request| (
+                TxKeyCheck {
+                    txid: request.txid.clone(),
+                    tx_key: request.tx_key.clone(),
+                    destination_address: self.monero_validator.deposit_address_for(&request.destination),
+                },
+                request.amount,
+            ))
+            .collect();
+        let results = self.monero_validator.validate_mint_requests(&checks).await?;
+        
+        // What the view-only bridge wallet actually received, to match deposits against
+        let scanned = match self.wallet_rpc {
+            Some(ref wallet) if !pending_tickets.is_empty() => Some(wallet.get_transfers().await?),
+            _ => None,
+        };
+        
+        for (request, result) in pending_tickets.into_iter().zip(results) {
+            let tx = match result {
+                MintCheck::Confirmed(tx) => tx,
+                MintCheck::SeenUnconfirmed(tx) => {
+                    debug!("Rechecking {} next poll ({} confirmation(s))", tx.txid, tx.confirmations);
+                    self.unconfirmed.push(request);
+                    continue;
+                }
+                MintCheck::Rejected => continue,
+            };
+            
+            if !self.monero_validator.deposit_matches_recipient(&tx, &request.destination) {
+                warn!("Deposit {} was made to a subaddress assigned to another recipient", tx.txid);
+                continue;
+            }
+            
+            if let Some(ref scanned) = scanned {
+                if !scanned.iter().any(|transfer| transfer.txid == tx.txid && transfer.address == tx.destination_address) {
+                    warn!("Deposit {} is not among the bridge wallet's incoming transfers to {}", tx.txid, tx.destination_address);
+                    continue;
+                }
+            }
+            
+            // A malformed event is skipped; it must not stop the requests after it
+            let tx_secret = match hex::decode(&request.tx_key) {
+                Ok(tx_secret) => tx_secret,
+                Err(e) => {
+                    warn!("Skipping mint request {}: tx key is not hex: {}", request.txid, e);
+                    continue;
+                }
+            };
+            // Within the amount tolerance, what actually arrived is what gets minted
+            let operation = self.mint_operation(&request, tx.amount);
+            let operation_hash = match self.calculate_operation_hash(&operation) {
+                Ok(operation_hash) => operation_hash,
+                Err(e) => {
+                    warn!("Skipping mint request {}: {}", request.txid, e);
+                    continue;
+                }
+            };
+            
+            let signing_request = SigningRequest {
+                direction: Direction::Mint,
+                tx_secret,
+                amount: tx.amount,
+                operation_hash,
+                timestamp: tx.timestamp,
+                nonce: operation.nonce,
+                monero_tx: Some(tx.clone()),
+                payout: None,
+            };
+            
+            // A request that cannot be signed now, e.g. for want of a quorum,
+            // is retried alone instead of failing the requests after it
+            match self.initiate_threshold_signing(signing_request).await {
+                Ok(()) => validated_transactions.push(tx),
+                Err(e) => {
+                    warn!("Signing mint {} failed, retrying next poll: {}", request.txid, e);
+                    self.unconfirmed.push(request);
+                }
             }
         }
         
         Ok(validated_transactions)
     }
     
-    async fn fetch_pending_mint_requests(&self) -> Result<Vec<MintRequest>> {
-        Ok(vec![])
+    // Finalized WXMR burns become payout signing requests. A burn whose
+    // payout fails is queued and retried like an unconfirmed deposit, and
+    // the cursor stays below it only so that a restart picks it up again.
+    async fn process_burn_events(&mut self) -> Result<()> {
+        let feed = match self.burn_events {
+            Some(ref feed) => feed.clone(),
+            None => return Ok(()),
+        };
+        
+        let batch = feed.next_finalized().await?;
+        let mut burns = std::mem::take(&mut self.pending_burns);
+        for event in batch.events {
+            if !burns.iter().any(|(queued, _)| queued.tx_hash == event.tx_hash && queued.log_index == event.log_index) {
+                burns.push((event, 0));
+            }
+        }
+        
+        for (event, attempt) in burns {
+            if let Err(e) = self.request_payout(&event, attempt).await {
+                warn!("Payout for burn {} at log {} failed, retrying next poll: {}", event.tx_hash, event.log_index, e);
+                self.pending_burns.push((event, attempt + 1));
+            }
+        }
+        
+        let commit_to = self.pending_burns.iter()
+            .map(|(event, _)| event.block_number.saturating_sub(1))
+            .fold(batch.through_block, u64::min);
+        feed.commit(commit_to).await
     }
     
-    fn calculate_operation_hash(&self, request: &MintRequest) -> Result<[u8; 32]> {
-        let mut hasher = Sha256::new();
-        hasher.update(request.txid.as_bytes());
-        hasher.update(&request.amount.to_be_bytes());
-        let result = hasher.finalize();
-        Ok(result.into())
+    async fn request_payout(&mut self, event: &BurnEvent, attempt: u32) -> Result<()> {
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let mut request = match SigningRequest::for_payout(event, timestamp) {
+            Ok(request) => request,
+            Err(e) => {
+                warn!("Ignoring burn {} at log {}: {}", event.tx_hash, event.log_index, e);
+                return Ok(());
+            }
+        };
+        let Some(total) = event.amount.checked_add(self.config.monero.payout_fee) else {
+            warn!("Ignoring burn {} at log {}: amount {} plus fee overflows", event.tx_hash, event.log_index, event.amount);
+            return Ok(());
+        };
+        
+        if let Some(ref wallet) = self.wallet_rpc {
+            let balance = wallet.get_balance().await?;
+            if balance.unlocked_balance < total {
+                warn!(
+                    "Bridge wallet has {} unlocked piconero, short of the payout for burn {} ({} plus fee)",
+                    balance.unlocked_balance, event.tx_hash, event.amount
+                );
+            }
+        }
+        
+        if let Some(ref funding) = self.payout_funding {
+            let builder = PayoutBuilder::new(self.config.monero.address.clone())
+                .with_fee(self.config.monero.payout_fee)
+                .with_ring_size(self.config.monero.ring_size);
+            // Building a payout draws a fresh tx key, output order and decoys,
+            // so only the round's leader builds it; everyone else checks and
+            // approves the one it proposes
+            let live = self.live_validators().await;
+            let leader = select_leader(&request.operation_hash, attempt, &live).unwrap_or(self.validator_id);
+            let (transaction, tx_key, attempt) = if leader == self.validator_id {
+                let mut inputs = funding.spendable_inputs(total, builder.ring_size()).await?;
+                // Funding sources that leave ring selection to us get gamma-picked decoys
+                let selector = DecoySelector::new(builder.ring_size());
+                for input in inputs.iter_mut().filter(|input| input.decoys.is_empty()) {
+                    input.decoys = selector.select(self.monero_validator.as_ref(), &input.output).await?;
+                }
+                let (transaction, tx_key) = builder.build(&event.monero_address, event.amount, &inputs)?;
+                let proposal = PayoutProposal {
+                    operation_hash: hex::encode(request.operation_hash),
+                    attempt,
+                    transaction: transaction.clone(),
+                    tx_key: tx_key.to_bytes(),
+                };
+                self.broadcast(PAYOUT_PROPOSAL, serde_json::to_value(&proposal)?).await?;
+                (transaction, tx_key, attempt)
+            } else {
+                let proposal = self.await_payout_proposal(&request.operation_hash, attempt, &live).await?;
+                let tx_key = Option::from(Scalar::from_canonical_bytes(proposal.tx_key))
+                    .ok_or_else(|| ValidatorError::Payout("Proposed tx key is not a canonical scalar".to_string()))?;
+                builder.check(&proposal.transaction, &tx_key, &event.monero_address, event.amount)?;
+                (proposal.transaction, tx_key, proposal.attempt)
+            };
+            // The tx key lets the recipient verify the payout with check_tx_key
+            request.tx_secret = tx_key.to_bytes().to_vec();
+            if let Some(ref mut payout) = request.payout {
+                payout.prefix_approval = self.approve_payout(&transaction, attempt).await?;
+                payout.transaction = Some(transaction);
+            }
+        }
+        
+        self.initiate_threshold_signing(request).await
     }
     
-    fn generate_nonce(&self, request: &MintRequest) -> Result<[u8; 32]> {
-        let mut hasher = Sha256::new();
-        hasher.update(request.txid.as_bytes());
-        hasher.update(self.validator_id.to_be_bytes());
-        let result = hasher.finalize();
-        Ok(result.into())
+    // The leader's proposal for this attempt, or a later one: a validator
+    // proposing for a later attempt has already given up on ours
+    async fn await_payout_proposal(&self, operation_hash: &[u8; 32], attempt: u32, live: &[usize]) -> Result<PayoutProposal> {
+        let operation = hex::encode(operation_hash);
+        let deadline = tokio::time::Instant::now() + std::time::Duration::from_secs(self.config.mpc.signing_timeout_secs);
+        loop {
+            let proposal = self.network_client.state().messages.read().await
+                .iter()
+                .filter(|m| m.msg_type == PAYOUT_PROPOSAL && m.data["operation_hash"] == operation.as_str())
+                .filter_map(|m| Some((m.validator_id, serde_json::from_value::<PayoutProposal>(m.data.clone()).ok()?)))
+                .filter(|(sender, proposal)| {
+                    proposal.attempt >= attempt && select_leader(operation_hash, proposal.attempt, live) == Some(*sender)
+                })
+                .max_by_key(|(_, proposal)| proposal.attempt)
+                .map(|(_, proposal)| proposal);
+            if let Some(proposal) = proposal {
+                return Ok(proposal);
+            }
+            if tokio::time::Instant::now() >= deadline {
+                return Err(ValidatorError::QuorumNotReached {
+                    msg_type: PAYOUT_PROPOSAL.to_string(),
+                    need: 1,
+                    have: 0,
+                });
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        }
+    }
+    
+    // The lowest `threshold` live validators form the signing set, so every
+    // member picks the same one without another round of agreement
+    async fn approve_payout(&self, transaction: &UnsignedTransaction, attempt: u32) -> Result<Option<frost::Signature>> {
+        let signer = match self.frost_signer {
+            Some(ref signer) => signer,
+            None => return Ok(None),
+        };
+        
+        let signers: Vec<u16> = self.live_validators().await
+            .into_iter()
+            .take(signer.threshold())
+            .map(|id| id as u16 + 1)
+            .collect();
+        if !signers.contains(&(self.validator_id as u16 + 1)) {
+            debug!("Validator {} is not in the payout approval set {:?}", self.validator_id, signers);
+            return Ok(None);
+        }
+        
+        signer.approve_payout(transaction, attempt, &signers).await.map(Some)
+    }
+    
+    // Also returns the block the fetched requests run up to, for committing the cursor
+    async fn fetch_pending_mint_requests(&self) -> Result<(Vec<MintRequest>, u64)> {
+        let discovery = match self.mint_events {
+            Some(ref discovery) => discovery,
+            None => return Ok((vec![], 0)),
+        };
+        
+        // Only events buried under the configured finality depth are acted on
+        let batch = discovery.next_events().await?;
+        let requests = batch.events
+            .into_iter()
+            .map(|event| MintRequest {
+                txid: event.txid,
+                tx_key: event.tx_key,
+                amount: event.amount,
+                destination: event.destination,
+                block_number: event.block_number,
+            })
+            .collect();
+        Ok((requests, batch.through_block))
+    }
+    
+    fn mint_operation(&self, request: &MintRequest, amount: u64) -> MintOperation {
+        MintOperation::new(
+            request.txid.clone(),
+            amount,
+            request.destination.clone(),
+            self.config.ethereum.chain_id,
+            self.config.ethereum.contract_address.clone(),
+        )
+    }
+    
+    fn calculate_operation_hash(&self, operation: &MintOperation) -> Result<[u8; 32]> {
+        match self.config.ethereum.signing_scheme {
+            SigningScheme::Legacy => Ok(operation.operation_hash()),
+            SigningScheme::Eip712 => operation.typed_data_hash(),
+        }
     }
     
     pub async fn initiate_threshold_signing(&mut self, request: SigningRequest) -> Result<()> {
-        info!("Initiating threshold signing for Tx: {}", hex::encode(&request.operation_hash));
+        info!("Initiating threshold signing for Tx: {}", hex::encode(request.operation_hash));
+        
+        let coordinator = match self.signing_coordinator {
+            Some(ref coordinator) => coordinator.clone(),
+            None => return Ok(()),
+        };
         
-        if let Some(ref coordinator) = self.signing_coordinator {
-            let result = coordinator.sign_operation(request).await?;
-            self.submit_signature(result).await?;
+        let operation_hash = request.operation_hash;
+        let transfer_id = request.transfer_id();
+        let direction = request.direction;
+        let payout_transaction = request.payout.as_ref().and_then(|payout| payout.transaction.clone());
+        let share = coordinator.sign_operation(request).await?;
+        
+        if !self.config.validators.enable_consensus {
+            return self.submit_transfer(direction, operation_hash, &transfer_id, payout_transaction.as_ref(), &[share]).await;
         }
         
+        // A round that stalls under one leader is retried under the next live validator
+        let live = self.live_validators().await;
+        let driver = MessageRoundDriver::new(
+            self.validator_id,
+            self.network_client.state().clone(),
+            self.transport_key.clone(),
+            share,
+        );
+        let rounds = RoundCoordinator::new(
+            self.config.mpc.quorum_policy().required(self.config.mpc.total_parties),
+            std::time::Duration::from_secs(self.config.mpc.signing_timeout_secs),
+            self.config.mpc.max_round_attempts,
+        );
+        let outcome = rounds.run(&driver, operation_hash, &live).await?;
+        
+        // The signature set has been checked against the shares we hold, so
+        // it is safe to credit its signers
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        self.network_client.state().liveness
+            .record_round(&hex::encode(operation_hash), outcome.signatures.iter().map(|s| s.validator_id), now)
+            .await;
+        
+        if outcome.leader == self.validator_id {
+            self.submit_transfer(direction, operation_hash, &transfer_id, payout_transaction.as_ref(), &outcome.signatures).await?;
+        }
+        
+        Ok(())
+    }
+    
+    // Validators with a recent heartbeat, plus ourselves
+    async fn live_validators(&self) -> Vec<usize> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let cutoff = now.saturating_sub(3 * membership::HEARTBEAT_INTERVAL_SECS);
+        
+        let mut live: Vec<usize> = self.network_client.state().live_validators.read().await
+            .iter()
+            .filter(|(_, last_seen)| **last_seen >= cutoff)
+            .map(|(id, _)| *id)
+            .collect();
+        live.push(self.validator_id);
+        live.sort_unstable();
+        live.dedup();
+        live
+    }
+    
+    // Only submits if this validator wins the reservation for the transfer
+    async fn submit_transfer(
+        &self,
+        direction: Direction,
+        operation_hash: [u8; 32],
+        transfer_id: &str,
+        payout_transaction: Option<&UnsignedTransaction>,
+        signatures: &[SigningResult],
+    ) -> Result<()> {
+        if let Some(ref reservations) = self.reservations {
+            if !reservations.reserve(transfer_id).await? {
+                info!("Transfer {} already reserved by another submitter, skipping", transfer_id);
+                return Ok(());
+            }
+        }
+        let submitted = match direction {
+            Direction::Mint => self.submit_mint(transfer_id, operation_hash, signatures).await,
+            Direction::Burn => self.submit_payout(transfer_id, payout_transaction, signatures).await,
+        };
+        
+        // Let another submitter take the transfer rather than wait out the reservation
+        if submitted.is_err() {
+            if let Some(ref reservations) = self.reservations {
+                if let Err(e) = reservations.release(transfer_id).await {
+                    warn!("Could not release reservation of {}: {}", transfer_id, e);
+                }
+            }
+        }
+        submitted
+    }
+    
+    pub async fn submit_payout(&self, transfer_id: &str, transaction: Option<&UnsignedTransaction>, signatures: &[SigningResult]) -> Result<()> {
+        info!("Payout for {} approved with threshold signature ({} shares) from validator {}", transfer_id, signatures.len(), self.validator_id);
+        
+        let Some(transaction) = transaction else {
+            warn!("Payout for {} has no transaction; the bridge wallet did not fund it, send it by hand", transfer_id);
+            return Ok(());
+        };
+        
+        // Broadcast only what monerod has said it would accept; a held payout
+        // keeps its reservation so it is not retried blindly
+        match transaction.signed() {
+            Some(signed) => match self.monero_validator.relay_payout(&signed).await? {
+                PayoutRelay::Broadcast { txid } => info!("Payout for {} broadcast as {}", transfer_id, txid),
+                PayoutRelay::Held { reason } => warn!("Payout for {} held: {}", transfer_id, reason),
+            },
+            // Nothing here produces the per-input CLSAGs yet, so every payout ends up here
+            None => warn!(
+                "Payout for {} cannot be broadcast: its inputs have no CLSAGs, which threshold signing does not produce; send it from the bridge wallet",
+                transfer_id
+            ),
+        }
         Ok(())
     }
     
-    pub async fn submit_signature(&self, signature: SigningResult) -> Result<()> {
-        info!("Submitting threshold signature to Ethereum for validator {}", self.validator_id);
+    pub async fn submit_mint(&self, transfer_id: &str, operation_hash: [u8; 32], signatures: &[SigningResult]) -> Result<()> {
+        info!("Mint {} approved with threshold signature ({} shares) from validator {}", transfer_id, signatures.len(), self.validator_id);
+        
+        // The contract only accepts a signature under the joint key. Shares
+        // are not combined into one yet, so a mint is only sent once one of
+        // them verifies; like an unsigned payout, it keeps its reservation
+        let joint_public_key = self.network_client.state().joint_public_key.clone();
+        let combined = joint_public_key.and_then(|joint_public_key| {
+            signatures.iter().find(|s| verify_threshold_signature(&operation_hash, s, &joint_public_key))
+        });
+        match combined {
+            Some(signature) => self.submit_signature(transfer_id, signature).await,
+            None => {
+                warn!(
+                    "Mint {} cannot be submitted: threshold signing does not combine its shares into a signature under the joint key; submit it by hand",
+                    transfer_id
+                );
+                Ok(())
+            }
+        }
+    }
+    
+    pub async fn submit_signature(&self, transfer_id: &str, signature: &SigningResult) -> Result<()> {
+        info!("Submitting threshold signature for {} to Ethereum for validator {} (v = {})", transfer_id, self.validator_id, signature.v);
         Ok(())
     }
     
     async fn run_heartbeat(&mut self) -> Result<()> {
         loop {
             tokio::select! {
-                _ = tokio::time::sleep(tokio::time::Duration::from_secs(30)) => {
+                _ = tokio::time::sleep(tokio::time::Duration::from_secs(membership::HEARTBEAT_INTERVAL_SECS)) => {
                     self.send_heartbeat_message().await?;
+                    
+                    let live = self.live_validators().await.len();
+                    let quorum = self.config.mpc.quorum_policy();
+                    if !quorum.is_met(live, self.config.mpc.total_parties) {
+                        warn!("Only {} validators live, quorum needs {}", live, quorum.required(self.config.mpc.total_parties));
+                    }
                 }
                 _ = self.shutdown.notified() => break,
             }
@@ -199,22 +843,29 @@ impl ValidatorNode {
         Ok(())
     }
     
+    async fn broadcast(&self, msg_type: &str, data: serde_json::Value) -> Result<()> {
+        let mut message = ConsensusMessage {
+            validator_id: self.validator_id,
+            msg_type: msg_type.to_string(),
+            data,
+            signature: vec![],
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_secs(),
+            hops: 0,
+        };
+        self.transport_key.sign_message(&mut message)?;
+        self.network_client.broadcast(message).await
+    }
+    
     async fn send_heartbeat_message(&self) -> Result<()> {
         let timestamp = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap()
             .as_secs();
             
-        let message = ConsensusMessage {
-            validator_id: self.validator_id,
-            msg_type: "HEARTBEAT".to_string(),
-            data: serde_json::json!({
-                "status": "active",
-                "address": self.config.ethereum.rpc_url
-            }),
-            signature: vec![],
-            timestamp,
-        };
+        let message = membership::build_heartbeat(&self.key_share, &self.transport_key, timestamp)?;
         
         self.network_client.broadcast(message).await?;
         Ok(())
@@ -222,12 +873,22 @@ impl ValidatorNode {
     
     // Helper methods for cloning parameters
     pub fn clone_wrapped(&self) -> Self {
-        Self::new(
+        let mut clone = Self::new(
             self.config.clone(),
             self.validator_id,
-            MoneroValidator::new(self.config.monero.clone()),
-            Arc::new(NetworkClient::new(self.config.network.clone())),
-        )
+            self.key_share.clone(),
+            self.transport_key.clone(),
+            self.monero_validator.clone(),
+            self.network_client.clone(),
+        );
+        clone.signing_coordinator = self.signing_coordinator.clone();
+        clone.mint_events = self.mint_events.clone();
+        clone.burn_events = self.burn_events.clone();
+        clone.payout_funding = self.payout_funding.clone();
+        clone.frost_signer = self.frost_signer.clone();
+        clone.wallet_rpc = self.wallet_rpc.clone();
+        clone.reservations = self.reservations.clone();
+        clone
     }
 }
 
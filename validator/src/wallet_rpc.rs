@@ -0,0 +1,274 @@
+use reqwest::Client;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::atomic::{AtomicU32, Ordering};
+use tracing::warn;
+
+use crate::config::WalletRpcConfig;
+use crate::digest_auth::DigestChallenge;
+use crate::error::{Result, ValidatorError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub struct WalletBalance {
+    pub balance: u64,
+    pub unlocked_balance: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SubaddressIndex {
+    pub major: u32,
+    pub minor: u32,
+}
+
+// An incoming payment as the wallet scanned it, confirmed or still in the pool
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct WalletTransfer {
+    pub txid: String,
+    pub amount: u64,
+    pub address: String,
+    pub subaddr_index: SubaddressIndex,
+    #[serde(default)]
+    pub height: u64,
+    #[serde(default)]
+    pub confirmations: u64,
+    #[serde(rename = "type")]
+    pub kind: String,
+}
+
+// An unspent output the wallet can currently see. A view-only wallet only
+// learns outputs are spent once key images are imported.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct WalletOutput {
+    pub amount: u64,
+    pub global_index: u64,
+    pub tx_hash: String,
+    #[serde(default)]
+    pub pubkey: String,
+    #[serde(default)]
+    pub unlocked: bool,
+}
+
+// JSON-RPC client for monero-wallet-rpc holding the view-only bridge wallet.
+// monerod stays the source for chain queries; this is for what only a
+// scanning wallet knows: balances, incoming transfers and subaddresses.
+pub struct MoneroWalletRpc {
+    client: Client,
+    config: WalletRpcConfig,
+    auth_nonce_count: AtomicU32,
+}
+
+impl MoneroWalletRpc {
+    pub fn new(config: WalletRpcConfig) -> Self {
+        let client = Client::builder()
+            .timeout(std::time::Duration::from_millis(config.request_timeout_ms))
+            .build()
+            .expect("Failed to build HTTP client");
+
+        Self { client, config, auth_nonce_count: AtomicU32::new(0) }
+    }
+
+    pub fn account(&self) -> u32 {
+        self.config.account
+    }
+
+    pub async fn get_balance(&self) -> Result<WalletBalance> {
+        self.call("get_balance", serde_json::json!({ "account_index": self.config.account })).await
+    }
+
+    // Incoming transfers to the account, pool included
+    pub async fn get_transfers(&self) -> Result<Vec<WalletTransfer>> {
+        #[derive(Deserialize)]
+        struct Transfers {
+            #[serde(default, rename = "in")]
+            incoming: Vec<WalletTransfer>,
+            #[serde(default)]
+            pool: Vec<WalletTransfer>,
+        }
+
+        let transfers: Transfers = self
+            .call("get_transfers", serde_json::json!({ "in": true, "pool": true, "account_index": self.config.account }))
+            .await?;
+        Ok(transfers.incoming.into_iter().chain(transfers.pool).collect())
+    }
+
+    pub async fn incoming_transfers(&self) -> Result<Vec<WalletOutput>> {
+        #[derive(Deserialize)]
+        struct Incoming {
+            #[serde(default)]
+            transfers: Vec<WalletOutput>,
+        }
+
+        let incoming: Incoming = self
+            .call("incoming_transfers", serde_json::json!({ "transfer_type": "available", "account_index": self.config.account }))
+            .await?;
+        Ok(incoming.transfers)
+    }
+
+    // Number of addresses in the account, the primary (minor 0) included
+    pub async fn address_count(&self) -> Result<u32> {
+        #[derive(Deserialize)]
+        struct Addresses {
+            addresses: Vec<Value>,
+        }
+
+        let addresses: Addresses = self.call("get_address", serde_json::json!({ "account_index": self.config.account })).await?;
+        Ok(addresses.addresses.len() as u32)
+    }
+
+    pub async fn create_address(&self, count: u32) -> Result<Vec<String>> {
+        #[derive(Deserialize)]
+        struct Created {
+            address: String,
+            #[serde(default)]
+            addresses: Vec<String>,
+        }
+
+        let created: Created = self
+            .call("create_address", serde_json::json!({ "account_index": self.config.account, "count": count }))
+            .await?;
+        Ok(if created.addresses.is_empty() { vec![created.address] } else { created.addresses })
+    }
+
+    // The wallet only scans subaddresses it has created, so it is brought up
+    // to the 1..=count range the validator accepts deposits on
+    pub async fn ensure_subaddresses(&self, count: u32) -> Result<()> {
+        let existing = self.address_count().await?;
+        if existing <= count {
+            self.create_address(count + 1 - existing).await?;
+        }
+        Ok(())
+    }
+
+    async fn call<T: DeserializeOwned>(&self, method: &str, params: Value) -> Result<T> {
+        let request = serde_json::json!({ "jsonrpc": "2.0", "id": "0", "method": method, "params": params });
+        let response = self.post(&request).await?;
+
+        if let Some(error) = response.get("error") {
+            return Err(ValidatorError::WalletRpc(format!("{} failed: {}", method, error)));
+        }
+        serde_json::from_value(response["result"].clone())
+            .map_err(|e| ValidatorError::WalletRpc(format!("unexpected {} result: {}", method, e)))
+    }
+
+    async fn post(&self, request: &Value) -> Result<Value> {
+        let mut response = self.send(request, None).await?;
+
+        // Same digest scheme as monerod's --rpc-login
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            let (Some(username), Some(password)) = (&self.config.rpc_username, &self.config.rpc_password) else {
+                return Err(ValidatorError::Config("monero-wallet-rpc requires rpc_username and rpc_password".to_string()));
+            };
+            let challenge = response
+                .headers()
+                .get(reqwest::header::WWW_AUTHENTICATE)
+                .and_then(|value| value.to_str().ok())
+                .and_then(DigestChallenge::parse)
+                .ok_or_else(|| ValidatorError::WalletRpc("unsupported authentication challenge".to_string()))?;
+
+            let uri = url::Url::parse(&self.config.url)
+                .map(|url| url.path().to_string())
+                .map_err(|e| ValidatorError::Config(format!("Invalid wallet RPC URL: {}", e)))?;
+            let nc = self.auth_nonce_count.fetch_add(1, Ordering::Relaxed) + 1;
+            let cnonce = hex::encode(rand::random::<[u8; 8]>());
+            let authorization = challenge.authorization(username, password, "POST", &uri, nc, &cnonce);
+
+            response = self.send(request, Some(&authorization)).await?;
+            if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+                return Err(ValidatorError::Config("monero-wallet-rpc rejected the configured RPC credentials".to_string()));
+            }
+        }
+
+        response
+            .json()
+            .await
+            .map_err(|e| ValidatorError::WalletRpc(format!("Failed to parse wallet RPC response: {}", e)))
+    }
+
+    async fn send(&self, request: &Value, authorization: Option<&str>) -> Result<reqwest::Response> {
+        let mut builder = self.client.post(&self.config.url).json(request);
+        if let Some(authorization) = authorization {
+            builder = builder.header(reqwest::header::AUTHORIZATION, authorization);
+        }
+        builder.send().await.map_err(|source| {
+            warn!("monero-wallet-rpc unreachable: {}", source);
+            ValidatorError::PeerUnreachable { peer: self.config.url.clone(), source }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::routing::post;
+    use axum::{Json, Router};
+
+    async fn mock_wallet_rpc() -> String {
+        async fn handle(Json(request): Json<Value>) -> Json<Value> {
+            assert_eq!(request["params"]["account_index"], 0);
+            let result = match request["method"].as_str().unwrap() {
+                "get_balance" => serde_json::json!({
+                    "balance": 5_000_000_000_000u64,
+                    "unlocked_balance": 3_000_000_000_000u64,
+                    "multiple_inputs": false,
+                }),
+                "get_transfers" => {
+                    assert_eq!(request["params"]["in"], true);
+                    assert_eq!(request["params"]["pool"], true);
+                    serde_json::json!({
+                        "in": [{
+                            "txid": "aa".repeat(32),
+                            "amount": 1_000_000_000_000u64,
+                            "address": "8BnERTpvL5MbCLtj5n9No7J5oE5hHiB3tVCK5cjSvCsYWD2WRJLFuWeKTLiXo5QJqt2ZwUaLy2Vh1Ad51K7FNgqcHgjW85o",
+                            "subaddr_index": { "major": 0, "minor": 3 },
+                            "height": 1_500_000,
+                            "confirmations": 12,
+                            "type": "in",
+                            "fee": 0,
+                        }],
+                        "pool": [{
+                            "txid": "bb".repeat(32),
+                            "amount": 250_000_000_000u64,
+                            "address": "8BnERTpvL5MbCLtj5n9No7J5oE5hHiB3tVCK5cjSvCsYWD2WRJLFuWeKTLiXo5QJqt2ZwUaLy2Vh1Ad51K7FNgqcHgjW85o",
+                            "subaddr_index": { "major": 0, "minor": 7 },
+                            "type": "pool",
+                        }],
+                    })
+                }
+                method => return Json(serde_json::json!({ "id": "0", "error": { "code": -32601, "message": format!("{} not found", method) } })),
+            };
+            Json(serde_json::json!({ "id": "0", "jsonrpc": "2.0", "result": result }))
+        }
+
+        format!("{}/json_rpc", crate::test_support::serve(Router::new().route("/json_rpc", post(handle))).await)
+    }
+
+    fn wallet(url: String) -> MoneroWalletRpc {
+        MoneroWalletRpc::new(WalletRpcConfig {
+            url,
+            account: 0,
+            rpc_username: None,
+            rpc_password: None,
+            request_timeout_ms: 5_000,
+            view_key: None,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_balance_and_transfers_from_wallet_rpc() {
+        let wallet = wallet(mock_wallet_rpc().await);
+
+        let balance = wallet.get_balance().await.unwrap();
+        assert_eq!(balance, WalletBalance { balance: 5_000_000_000_000, unlocked_balance: 3_000_000_000_000 });
+
+        let transfers = wallet.get_transfers().await.unwrap();
+        assert_eq!(transfers.len(), 2);
+        assert_eq!(transfers[0].txid, "aa".repeat(32));
+        assert_eq!(transfers[0].subaddr_index, SubaddressIndex { major: 0, minor: 3 });
+        assert_eq!((transfers[0].confirmations, transfers[0].kind.as_str()), (12, "in"));
+        assert_eq!((transfers[1].confirmations, transfers[1].kind.as_str()), (0, "pool"));
+
+        // Wallet errors are surfaced rather than read as empty results
+        assert!(matches!(wallet.incoming_transfers().await, Err(ValidatorError::WalletRpc(_))));
+    }
+}